@@ -2,6 +2,20 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+mod babei;
+mod call;
 mod dapai;
+mod gang;
+mod hule;
+mod jiuzhong_jiupai;
+mod lizhi;
+mod turn;
 
+pub use babei::BabeiError;
+pub use call::{CallResponse, CallWindowError};
 pub use dapai::{Dapai, DapaiError};
+pub use gang::GangError;
+pub use hule::HuleError;
+pub use jiuzhong_jiupai::JiuzhongJiupaiError;
+pub use lizhi::LizhiError;
+pub use turn::{TurnAction, TurnActionError};
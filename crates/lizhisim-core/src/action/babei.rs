@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::bingpai::BingpaiError;
+use crate::bipai::BipaiError;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum BabeiError {
+    #[error("babei is disabled by the policy")]
+    Disabled,
+    #[error("babei is unavailable because no lingshang zimo remains")]
+    LingshangZimoUnavailable,
+    #[error("babei requires a held Z4")]
+    BeiMissing,
+    #[error("babei after lizhi must set aside the zimopai")]
+    UnavailableAfterLizhi,
+    #[error(transparent)]
+    Bingpai(#[from] BingpaiError),
+    #[error(transparent)]
+    Bipai(#[from] BipaiError),
+}
@@ -0,0 +1,34 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::bipai::BipaiError;
+use crate::fulu::FuluError;
+use crate::tile::TileKind;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CallResponse {
+    Pass,
+    Chi { bingpai_tiles: [TileKind; 2] },
+    Peng { bingpai_tiles: [TileKind; 2] },
+    Daminggang { bingpai_tiles: [TileKind; 3] },
+    Rong,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum CallWindowError {
+    #[error("seat has no response slot in this call window")]
+    SeatNotEligible,
+    #[error("seat has already responded in this call window")]
+    ResponseAlreadyRecorded,
+    #[error("response {response:?} is not legal for the seat")]
+    IllegalResponse { response: CallResponse },
+    #[error("call window still has unfilled response slots")]
+    ResponsesPending,
+    #[error(transparent)]
+    Bipai(#[from] BipaiError),
+    #[error(transparent)]
+    Fulu(#[from] FuluError),
+}
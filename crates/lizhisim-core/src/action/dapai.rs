@@ -5,6 +5,7 @@
 use thiserror::Error;
 
 use crate::bingpai::BingpaiError;
+use crate::bipai::BipaiError;
 use crate::he::HeFull;
 use crate::tile::TileKind;
 
@@ -14,14 +15,35 @@ pub enum Dapai {
     Shouqie(TileKind),
 }
 
+impl Dapai {
+    pub const fn tile_kind(self) -> TileKind {
+        match self {
+            Self::Moqie(tile_kind) | Self::Shouqie(tile_kind) => tile_kind,
+        }
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum DapaiError {
-    #[error("actor index {actor_index} is out of range for four players")]
-    ActorIndexOutOfRange { actor_index: usize },
+    #[error("moqie of {tile_kind:?} does not match the zimopai {zimopai:?}")]
+    MoqieTileMismatch {
+        tile_kind: TileKind,
+        zimopai: TileKind,
+    },
     #[error("moqie is unavailable for the initial-deal dealer first dapai")]
     MoqieUnavailableForInitialDealFirstDapai,
+    #[error("moqie is unavailable for the dapai after a fulu")]
+    MoqieUnavailableAfterFulu,
+    #[error("shouqie is unavailable after lizhi")]
+    ShouqieUnavailableAfterLizhi,
+    #[error("dapai of {tile_kind:?} right after the call is forbidden by the shiti policy")]
+    ShitiForbidden { tile_kind: TileKind },
+    #[error("dapai waits until the held Z4 is set aside by babei")]
+    BabeiRequired,
     #[error(transparent)]
     Bingpai(#[from] BingpaiError),
     #[error(transparent)]
+    Bipai(#[from] BipaiError),
+    #[error(transparent)]
     HeFull(#[from] HeFull),
 }
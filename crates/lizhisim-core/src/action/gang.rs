@@ -0,0 +1,26 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::bingpai::BingpaiError;
+use crate::bipai::BipaiError;
+use crate::fulu::FuluError;
+use crate::tile::TileKind;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum GangError {
+    #[error("gang is unavailable because no lingshang zimo remains")]
+    LingshangZimoUnavailable,
+    #[error("angang requires four held copies of {tile_kind:?}")]
+    AngangTilesMissing { tile_kind: TileKind },
+    #[error("angang of {tile_kind:?} after lizhi must use the zimopai and keep the waits")]
+    AngangUnavailableAfterLizhi { tile_kind: TileKind },
+    #[error(transparent)]
+    Bingpai(#[from] BingpaiError),
+    #[error(transparent)]
+    Bipai(#[from] BipaiError),
+    #[error(transparent)]
+    Fulu(#[from] FuluError),
+}
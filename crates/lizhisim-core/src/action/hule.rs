@@ -0,0 +1,13 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum HuleError {
+    #[error("bingpai with the hulepai does not form a hule shape")]
+    NotHuleShape,
+    #[error("no reading of the hule shape has yaku")]
+    NoYaku,
+}
@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum JiuzhongJiupaiError {
+    #[error("jiuzhong jiupai is disabled by the round policy")]
+    Disabled,
+    #[error("jiuzhong jiupai is only available on an uninterrupted first turn")]
+    NotFirstTurn,
+    #[error("jiuzhong jiupai requires nine yaojiu kinds but the player holds {kind_count}")]
+    YaojiuKindsInsufficient { kind_count: usize },
+}
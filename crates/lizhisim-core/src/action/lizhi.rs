@@ -0,0 +1,27 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::action::DapaiError;
+use crate::score::Score;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum LizhiError {
+    #[error("lizhi has already been declared")]
+    AlreadyDeclared,
+    #[error("lizhi requires a bingpai without open fulu")]
+    OpenFulu,
+    #[error("lizhi requires {required:?} points but the player has {score:?}")]
+    ScoreInsufficient { score: Score, required: Score },
+    #[error("lizhi requires {required} remaining tiles but {remaining_count} remain")]
+    RemainingCountInsufficient {
+        remaining_count: usize,
+        required: usize,
+    },
+    #[error("lizhi requires tingpai after the declaration dapai")]
+    NotTingpai,
+    #[error(transparent)]
+    Dapai(#[from] DapaiError),
+}
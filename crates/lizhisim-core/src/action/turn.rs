@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::action::{
+    BabeiError, Dapai, DapaiError, GangError, HuleError, JiuzhongJiupaiError, LizhiError,
+};
+use crate::tile::TileKind;
+
+/// An action the actor may take after zimo.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TurnAction {
+    Dapai(Dapai),
+    /// Lizhi declared together with its declaration dapai.
+    Lizhi(Dapai),
+    Angang(TileKind),
+    Jiagang(TileKind),
+    Babei,
+    ZimoHule,
+    JiuzhongJiupai,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TurnActionError {
+    #[error("turn action {action:?} is not legal")]
+    IllegalAction { action: TurnAction },
+    #[error(transparent)]
+    Dapai(#[from] DapaiError),
+    #[error(transparent)]
+    Lizhi(#[from] LizhiError),
+    #[error(transparent)]
+    Gang(#[from] GangError),
+    #[error(transparent)]
+    Babei(#[from] BabeiError),
+    #[error(transparent)]
+    Hule(#[from] HuleError),
+    #[error(transparent)]
+    JiuzhongJiupai(#[from] JiuzhongJiupaiError),
+}
@@ -0,0 +1,215 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::fulu::{Fulu, FuluKind};
+use crate::seat::Seat;
+use crate::tile::TileKind;
+use crate::yaku::YakuRuleId;
+
+const SANYUANPAI: [TileKind; 3] = [TileKind::Z5, TileKind::Z6, TileKind::Z7];
+const FENGPAI: [TileKind; 4] = [TileKind::Z1, TileKind::Z2, TileKind::Z3, TileKind::Z4];
+
+/// Which calls make the seat that fed them liable for the caller's hule.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BaoPolicy {
+    /// Whether feeding the third sanyuanpai peng or daminggang makes a seat liable for dasanyuan.
+    pub dasanyuan: bool,
+    /// Whether feeding the fourth fengpai peng or daminggang makes a seat liable for dasixi.
+    pub dasixi: bool,
+    /// Whether the seat whose dapai was taken by daminggang is liable for a zimo hule on the
+    /// lingshang tile that follows.
+    pub daminggang_lingshang: bool,
+}
+
+#[cfg(test)]
+impl BaoPolicy {
+    pub(crate) const fn mahjong_soul() -> Self {
+        Self {
+            dasanyuan: true,
+            dasixi: true,
+            daminggang_lingshang: false,
+        }
+    }
+}
+
+/// What a liable seat answers for.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BaoKind {
+    Dasanyuan,
+    Dasixi,
+    /// The zimo hule on the lingshang tile of a daminggang.
+    DaminggangLingshang,
+}
+
+impl BaoKind {
+    /// The yaku the liable seat pays for, or `None` when it pays for the whole hule.
+    pub const fn yaku(self) -> Option<YakuRuleId> {
+        match self {
+            Self::Dasanyuan => Some(YakuRuleId::Dasanyuan),
+            Self::Dasixi => Some(YakuRuleId::Dasixi),
+            Self::DaminggangLingshang => None,
+        }
+    }
+}
+
+/// The seat liable for a player's hule, recorded at the call that made it liable.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Bao<P> {
+    kind: BaoKind,
+    seat: Seat<P>,
+}
+
+impl<P> Bao<P> {
+    pub fn kind(&self) -> BaoKind {
+        self.kind
+    }
+
+    pub fn seat(&self) -> &Seat<P> {
+        &self.seat
+    }
+}
+
+impl<P> Clone for Bao<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Bao<P> {}
+
+/// The liability the newest fulu creates: a called peng or daminggang completing the sanyuanpai
+/// or fengpai kezi, or any daminggang for its lingshang tile.
+pub(crate) fn fulu_bao<P>(fulu: &[Fulu<P>], policy: BaoPolicy) -> Option<Bao<P>> {
+    let newest = fulu.last()?;
+    let called_tile = newest.called_tile()?;
+    if !matches!(newest.kind(), FuluKind::Peng | FuluKind::Daminggang) {
+        return None;
+    }
+
+    let tile_kind = called_tile.tile_kind().base_tile();
+    let completes = |kinds: &[TileKind]| {
+        kinds.contains(&tile_kind)
+            && kinds.iter().all(|&kind| {
+                fulu.iter().any(|fulu| {
+                    fulu.kind() != FuluKind::Chi && fulu.bingpai_tiles()[0].base_tile() == kind
+                })
+            })
+    };
+    let kind = if policy.dasanyuan && completes(&SANYUANPAI) {
+        BaoKind::Dasanyuan
+    } else if policy.dasixi && completes(&FENGPAI) {
+        BaoKind::Dasixi
+    } else if policy.daminggang_lingshang && newest.kind() == FuluKind::Daminggang {
+        BaoKind::DaminggangLingshang
+    } else {
+        return None;
+    };
+
+    Some(Bao {
+        kind,
+        seat: *called_tile.source(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player_set::FourPlayer;
+
+    use super::*;
+
+    use TileKind::*;
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn all_enabled() -> BaoPolicy {
+        BaoPolicy {
+            dasanyuan: true,
+            dasixi: true,
+            daminggang_lingshang: true,
+        }
+    }
+
+    #[test]
+    fn third_sanyuanpai_peng_makes_its_source_liable() {
+        let fulu = [
+            Fulu::peng([Z5, Z5], Z5, seat(0)),
+            Fulu::angang([Z6, Z6, Z6, Z6]),
+            Fulu::peng([Z7, Z7], Z7, seat(2)),
+        ];
+
+        assert_eq!(
+            fulu_bao(&fulu, all_enabled()),
+            Some(Bao {
+                kind: BaoKind::Dasanyuan,
+                seat: seat(2)
+            })
+        );
+    }
+
+    #[test]
+    fn fourth_fengpai_daminggang_makes_its_source_liable_for_dasixi() {
+        let fulu = [
+            Fulu::peng([Z1, Z1], Z1, seat(0)),
+            Fulu::peng([Z2, Z2], Z2, seat(0)),
+            Fulu::peng([Z3, Z3], Z3, seat(2)),
+            Fulu::daminggang([Z4, Z4, Z4], Z4, seat(3)),
+        ];
+
+        assert_eq!(
+            fulu_bao(&fulu, all_enabled()),
+            Some(Bao {
+                kind: BaoKind::Dasixi,
+                seat: seat(3)
+            })
+        );
+    }
+
+    #[test]
+    fn disabled_policy_records_no_liability() {
+        let fulu = [
+            Fulu::peng([Z5, Z5], Z5, seat(0)),
+            Fulu::peng([Z6, Z6], Z6, seat(0)),
+            Fulu::peng([Z7, Z7], Z7, seat(2)),
+        ];
+        let policy = BaoPolicy {
+            dasanyuan: false,
+            ..all_enabled()
+        };
+
+        assert_eq!(fulu_bao(&fulu, policy), None);
+    }
+
+    #[test]
+    fn unrelated_peng_records_no_liability() {
+        let fulu = [
+            Fulu::peng([Z5, Z5], Z5, seat(0)),
+            Fulu::peng([Z6, Z6], Z6, seat(0)),
+            Fulu::peng([M1, M1], M1, seat(2)),
+        ];
+
+        assert_eq!(fulu_bao(&fulu, all_enabled()), None);
+    }
+
+    #[test]
+    fn daminggang_makes_its_source_liable_for_the_lingshang_tile() {
+        let fulu = [Fulu::daminggang([M1, M1, M1], M1, seat(3))];
+
+        assert_eq!(
+            fulu_bao(&fulu, all_enabled()),
+            Some(Bao {
+                kind: BaoKind::DaminggangLingshang,
+                seat: seat(3)
+            })
+        );
+    }
+
+    #[test]
+    fn daminggang_records_no_liability_unless_the_preset_adds_it() {
+        let fulu = [Fulu::daminggang([M1, M1, M1], M1, seat(3))];
+
+        assert_eq!(fulu_bao(&fulu, BaoPolicy::mahjong_soul()), None);
+    }
+}
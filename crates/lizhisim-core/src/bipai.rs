@@ -7,30 +7,45 @@ use core::marker::PhantomData;
 use thiserror::Error;
 
 use crate::bingpai::Bingpai;
-use crate::player_set::FourPlayer;
+use crate::player_set::{FourPlayer, PlayerSet, ThreePlayer};
+use crate::seat::SeatArray;
 use crate::tile::TileKind;
 use crate::tile_set::TileSet;
 
-const FOUR_PLAYER_QIPAI_TILE_COUNT: usize = 13 * 4;
-const FOUR_PLAYER_WANGPAI_TILE_COUNT: usize = 14;
-const FOUR_PLAYER_FIRST_LINGSHANG_ZIMO_INDEX: usize = 135;
-const FOUR_PLAYER_LINGSHANG_TILE_COUNT: usize = 4;
-const FOUR_PLAYER_INITIAL_BAOPAI_INDICATOR_INDEX: usize =
-    FOUR_PLAYER_FIRST_LINGSHANG_ZIMO_INDEX - FOUR_PLAYER_LINGSHANG_TILE_COUNT;
-const FOUR_PLAYER_MAX_BAOPAI_INDICATOR_COUNT: usize = 5;
+/// Each seat takes three batches of four tiles and then one more tile.
+const QIPAI_BATCH_COUNT: usize = 3;
+const QIPAI_BATCH_TILE_COUNT: usize = 4;
+const QIPAI_TILE_COUNT_PER_SEAT: usize = QIPAI_BATCH_COUNT * QIPAI_BATCH_TILE_COUNT + 1;
+const WANGPAI_TILE_COUNT: usize = 14;
+const LINGSHANG_TILE_COUNT: usize = 4;
+/// Babei rules double the lingshang zimo; the extra four come from the end of the live wall.
+const BABEI_LINGSHANG_TILE_COUNT: usize = 8;
+const MAX_BAOPAI_INDICATOR_COUNT: usize = 5;
 
 mod private {
     pub trait Sealed {}
 }
 
-pub trait BipaiSpec: private::Sealed {
-    type BipaiTiles: AsRef<[TileKind]>;
+/// The wall of a player set. Qipai deals from the front of the tiles and wangpai is the last
+/// 14 tiles, so both layouts follow from the tile count and the seat count.
+pub trait BipaiSpec: PlayerSet + private::Sealed {
+    type BipaiTiles: AsRef<[TileKind]> + Copy + for<'a> TryFrom<&'a [TileKind]>;
+    /// The bingpai qipai deals to every seat, in deal order.
+    type Qipai: SeatArray<Bingpai>;
 }
 
 impl private::Sealed for FourPlayer {}
 
 impl BipaiSpec for FourPlayer {
     type BipaiTiles = [TileKind; 136];
+    type Qipai = [Bingpai; 4];
+}
+
+impl private::Sealed for ThreePlayer {}
+
+impl BipaiSpec for ThreePlayer {
+    type BipaiTiles = [TileKind; 108];
+    type Qipai = [Bingpai; 3];
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -65,6 +80,7 @@ pub struct Bipai<P: BipaiSpec, QipaiState = QipaiPending> {
     cursor: usize,
     baopai_indicator_count: usize,
     lingshang_zimo_count: usize,
+    lingshang_tile_count: usize,
     qipai_state: PhantomData<fn() -> QipaiState>,
 }
 
@@ -72,14 +88,34 @@ impl<P: BipaiSpec, QipaiState> Bipai<P, QipaiState> {
     pub fn remaining_count(&self) -> usize {
         self.remaining_count
     }
+
+    /// Every tile of the wall in its dealt order, whether drawn yet or not.
+    pub(crate) fn tiles(&self) -> &P::BipaiTiles {
+        &self.tiles
+    }
+
+    fn first_lingshang_zimo_index(&self) -> usize {
+        self.tiles.as_ref().len() - 1
+    }
+
+    fn last_live_wall_index(&self) -> usize {
+        self.tiles.as_ref().len() - WANGPAI_TILE_COUNT - 1
+    }
+
+    fn initial_baopai_indicator_index(&self) -> usize {
+        self.first_lingshang_zimo_index() - LINGSHANG_TILE_COUNT
+    }
 }
 
-impl Bipai<FourPlayer, QipaiPending> {
-    pub fn try_new(tiles: [TileKind; 136], tile_set: TileSet) -> Result<Self, BipaiError> {
-        let actual_counts = tiles.iter().fold([0usize; 37], |mut counts, tile_kind| {
-            counts[tile_kind.index()] += 1;
-            counts
-        });
+impl<P: BipaiSpec> Bipai<P, QipaiPending> {
+    pub fn try_new(tiles: P::BipaiTiles, tile_set: TileSet) -> Result<Self, BipaiError> {
+        let actual_counts = tiles
+            .as_ref()
+            .iter()
+            .fold([0usize; 37], |mut counts, tile_kind| {
+                counts[tile_kind.index()] += 1;
+                counts
+            });
 
         if let Some((_index, (actual_count, tile_kind))) = TileKind::ALL
             .iter()
@@ -96,7 +132,7 @@ impl Bipai<FourPlayer, QipaiPending> {
             });
         }
 
-        let remaining_count = tiles.len() - FOUR_PLAYER_WANGPAI_TILE_COUNT;
+        let remaining_count = tiles.as_ref().len() - WANGPAI_TILE_COUNT;
         Ok(Self {
             tiles,
             tile_set,
@@ -104,36 +140,39 @@ impl Bipai<FourPlayer, QipaiPending> {
             cursor: 0,
             baopai_indicator_count: 0,
             lingshang_zimo_count: 0,
+            lingshang_tile_count: LINGSHANG_TILE_COUNT,
             qipai_state: PhantomData,
         })
     }
 
-    pub fn qipai(self) -> (Bipai<FourPlayer, QipaiCompleted>, [Bingpai; 4]) {
+    /// Raises the lingshang zimo capacity to the eight tiles a babei rule needs.
+    pub(crate) fn with_babei_lingshang(mut self) -> Self {
+        self.lingshang_tile_count = BABEI_LINGSHANG_TILE_COUNT;
+        self
+    }
+
+    pub fn qipai(self) -> (Bipai<P, QipaiCompleted>, P::Qipai) {
         let tiles = self.tiles.as_ref();
-        let bingpai = core::array::from_fn(|deal_index| {
-            let deal_offset = deal_index * 4;
+        let bingpai = P::Qipai::from_fn(|deal_index| {
             let mut counts = [0; 37];
-
-            for batch_start in [0, 16, 32] {
-                for &tile_kind in &tiles[batch_start + deal_offset..batch_start + deal_offset + 4] {
-                    counts[tile_kind.index()] += 1;
-                }
+            for tile_index in 0..QIPAI_TILE_COUNT_PER_SEAT {
+                let tile_kind = tiles[qipai_index::<P>(deal_index, tile_index)];
+                counts[tile_kind.index()] += 1;
             }
 
-            let tile_kind = tiles[48 + deal_index];
-            counts[tile_kind.index()] += 1;
-
             Bingpai::from_validated_counts(counts, self.tile_set.clone())
         });
+        let qipai_tile_count = QIPAI_TILE_COUNT_PER_SEAT * P::PLAYER_COUNT;
 
         (
             Bipai {
                 tiles: self.tiles,
                 tile_set: self.tile_set,
-                remaining_count: self.remaining_count - FOUR_PLAYER_QIPAI_TILE_COUNT,
-                cursor: FOUR_PLAYER_QIPAI_TILE_COUNT,
+                remaining_count: self.remaining_count - qipai_tile_count,
+                cursor: qipai_tile_count,
                 baopai_indicator_count: 0,
                 lingshang_zimo_count: self.lingshang_zimo_count,
+                lingshang_tile_count: self.lingshang_tile_count,
                 qipai_state: PhantomData,
             },
             bingpai,
@@ -141,14 +180,70 @@ impl Bipai<FourPlayer, QipaiPending> {
     }
 }
 
+impl Bipai<FourPlayer, QipaiPending> {
+    #[cfg(test)]
+    pub(crate) fn red_three_arranged(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+    ) -> Self {
+        Self::red_three_arranged_with_indicators(qipai, zimo, lingshang, &[])
+    }
+
+    /// Arranges like `red_three_arranged`, with each pair of `indicators` placed as a baopai
+    /// indicator and the li baopai indicator under it, in reveal order.
+    #[cfg(test)]
+    pub(crate) fn red_three_arranged_with_indicators(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+        indicators: &[(TileKind, TileKind)],
+    ) -> Self {
+        let tile_set = TileSet::red_three_four_player();
+        let mut tiles = [None; 136];
+        arrange::<FourPlayer>(&mut tiles, &tile_set, &qipai, zimo, lingshang, indicators);
+        let tiles = tiles.map(|tile_kind| tile_kind.expect("every index is arranged"));
+
+        Self::try_new(tiles, tile_set).expect("arranged tiles match the red-three tile set")
+    }
+
+    /// Arranges `qipai` so that the seat drawing `draw_offset` places after the zhuangjia draws
+    /// only yaojiu tiles from the live wall, while the other seats draw non-yaojiu tiles first.
+    #[cfg(test)]
+    pub(crate) fn red_three_yaojiu_zimo(qipai: [[TileKind; 13]; 4], draw_offset: usize) -> Self {
+        let zimo: [TileKind; 70] =
+            yaojiu_zimo::<FourPlayer, 70>(&TileSet::red_three_four_player(), &qipai, draw_offset);
+
+        Self::red_three_arranged(qipai, &zimo, &[])
+    }
+}
+
+impl Bipai<ThreePlayer, QipaiPending> {
+    #[cfg(test)]
+    pub(crate) fn red_two_arranged(
+        qipai: [[TileKind; 13]; 3],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+    ) -> Self {
+        let tile_set = TileSet::red_two_three_player();
+        let mut tiles = [None; 108];
+        arrange::<ThreePlayer>(&mut tiles, &tile_set, &qipai, zimo, lingshang, &[]);
+        let tiles = tiles.map(|tile_kind| tile_kind.expect("every index is arranged"));
+
+        Self::try_new(tiles, tile_set).expect("arranged tiles match the red-two tile set")
+    }
+
+    /// Arranges `qipai` like `red_three_yaojiu_zimo`, for three seats.
+    #[cfg(test)]
+    pub(crate) fn red_two_yaojiu_zimo(qipai: [[TileKind; 13]; 3], draw_offset: usize) -> Self {
+        let zimo: [TileKind; 55] =
+            yaojiu_zimo::<ThreePlayer, 55>(&TileSet::red_two_three_player(), &qipai, draw_offset);
+
+        Self::red_two_arranged(qipai, &zimo, &[])
+    }
+}
+
 impl<P: BipaiSpec> Bipai<P, QipaiCompleted> {
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "will be called by the Round baopai policy transition"
-        )
-    )]
     pub(crate) fn reveal_initial_baopai_indicator(mut self) -> Result<Self, BipaiError> {
         if self.baopai_indicator_count != 0 {
             return Err(BipaiError::InitialBaopaiIndicatorAlreadyRevealed);
@@ -157,15 +252,8 @@ impl<P: BipaiSpec> Bipai<P, QipaiCompleted> {
         Ok(self)
     }
 
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "will be called by the Round additional baopai policy transition"
-        )
-    )]
     pub(crate) fn reveal_additional_baopai_indicator(mut self) -> Result<Self, BipaiError> {
-        if self.baopai_indicator_count >= FOUR_PLAYER_MAX_BAOPAI_INDICATOR_COUNT {
+        if self.baopai_indicator_count >= MAX_BAOPAI_INDICATOR_COUNT {
             return Err(BipaiError::BaopaiIndicatorLimitReached);
         }
         self.baopai_indicator_count += 1;
@@ -181,18 +269,13 @@ impl<P: BipaiSpec> Bipai<P, QipaiCompleted> {
         self.cursor += 1;
         Ok((self, tile_kind))
     }
-}
 
-impl Bipai<FourPlayer, QipaiCompleted> {
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "will be called by the Round replacement draw transition"
-        )
-    )]
+    pub(crate) fn lingshang_zimo_available(&self) -> bool {
+        self.lingshang_zimo_count < self.lingshang_tile_count && self.remaining_count > 0
+    }
+
     pub(crate) fn lingshang_zimo(mut self) -> Result<(Self, TileKind), BipaiError> {
-        if self.lingshang_zimo_count >= FOUR_PLAYER_LINGSHANG_TILE_COUNT {
+        if self.lingshang_zimo_count >= self.lingshang_tile_count {
             return Err(BipaiError::LingshangWallExhausted);
         }
         self.remaining_count = self
@@ -201,42 +284,132 @@ impl Bipai<FourPlayer, QipaiCompleted> {
             .ok_or(BipaiError::LiveWallExhausted)?;
         // Replacement tiles move monotonically backward from the end of `tiles` instead of
         // reproducing the top-bottom order of physical stacks. The resulting swap is intentional.
-        let tile_kind =
-            self.tiles.as_ref()[FOUR_PLAYER_FIRST_LINGSHANG_ZIMO_INDEX - self.lingshang_zimo_count];
+        // Past the wangpai they continue from the last live-wall index, which the decrements
+        // above already keep away from zimo.
+        let index = match self.lingshang_zimo_count.checked_sub(LINGSHANG_TILE_COUNT) {
+            None => self.first_lingshang_zimo_index() - self.lingshang_zimo_count,
+            Some(extra_index) => self.last_live_wall_index() - extra_index,
+        };
+        let tile_kind = self.tiles.as_ref()[index];
         self.lingshang_zimo_count += 1;
         Ok((self, tile_kind))
     }
 
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "will be projected by the future Round public view"
-        )
-    )]
     pub(crate) fn baopai_indicators(&self) -> impl ExactSizeIterator<Item = TileKind> + '_ {
         // Baopai indicators move monotonically backward from the end of `tiles` instead of
         // reproducing the top-bottom order of physical stacks. The resulting swap is intentional.
         (0..self.baopai_indicator_count).map(|indicator_index| {
-            self.tiles.as_ref()[FOUR_PLAYER_INITIAL_BAOPAI_INDICATOR_INDEX - indicator_index * 2]
+            self.tiles.as_ref()[self.initial_baopai_indicator_index() - indicator_index * 2]
         })
     }
 
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "will be projected by the future Round eligible-hule view"
-        )
-    )]
     pub(crate) fn li_baopai_indicators(&self) -> impl ExactSizeIterator<Item = TileKind> + '_ {
         // Li-baopai indicators move monotonically backward from the end of `tiles` instead of
         // reproducing the top-bottom order of physical stacks. The resulting swap is intentional.
         (0..self.baopai_indicator_count).map(|indicator_index| {
-            self.tiles.as_ref()
-                [FOUR_PLAYER_INITIAL_BAOPAI_INDICATOR_INDEX - indicator_index * 2 - 1]
+            self.tiles.as_ref()[self.initial_baopai_indicator_index() - indicator_index * 2 - 1]
         })
     }
+
+    /// The baopai the revealed indicators point at, one per indicator.
+    pub(crate) fn baopai(&self) -> impl Iterator<Item = TileKind> + '_ {
+        self.baopai_indicators()
+            .map(|indicator| self.tile_set.baopai(indicator))
+    }
+
+    /// The li baopai under the revealed indicators, one per indicator.
+    pub(crate) fn li_baopai(&self) -> impl Iterator<Item = TileKind> + '_ {
+        self.li_baopai_indicators()
+            .map(|indicator| self.tile_set.baopai(indicator))
+    }
+}
+
+/// Index in `tiles` of the `tile_index`-th qipai tile of the seat dealt `deal_index`-th.
+fn qipai_index<P: PlayerSet>(deal_index: usize, tile_index: usize) -> usize {
+    let batch_index = tile_index / QIPAI_BATCH_TILE_COUNT;
+    if batch_index < QIPAI_BATCH_COUNT {
+        (batch_index * P::PLAYER_COUNT + deal_index) * QIPAI_BATCH_TILE_COUNT
+            + tile_index % QIPAI_BATCH_TILE_COUNT
+    } else {
+        QIPAI_BATCH_COUNT * QIPAI_BATCH_TILE_COUNT * P::PLAYER_COUNT + deal_index
+    }
+}
+
+/// The live-wall zimo of `tile_set` after `qipai`, in which the seat drawing `draw_offset` places
+/// after the zhuangjia draws only yaojiu tiles while the other seats draw non-yaojiu tiles first.
+#[cfg(test)]
+fn yaojiu_zimo<P: PlayerSet, const N: usize>(
+    tile_set: &TileSet,
+    qipai: &[[TileKind; 13]],
+    draw_offset: usize,
+) -> [TileKind; N] {
+    let mut leftover = TileKind::ALL.map(|tile_kind| tile_set.max_count(tile_kind));
+    for tile_kind in qipai.iter().flatten() {
+        leftover[tile_kind.index()] -= 1;
+    }
+    let mut take = |yaojiu: bool| {
+        let tile_kind = TileKind::ALL
+            .into_iter()
+            .find(|tile_kind| tile_kind.is_yaojiu() == yaojiu && leftover[tile_kind.index()] > 0)?;
+        leftover[tile_kind.index()] -= 1;
+        Some(tile_kind)
+    };
+    core::array::from_fn(|index| {
+        if index % P::PLAYER_COUNT == draw_offset {
+            take(true)
+        } else {
+            take(false).or_else(|| take(true))
+        }
+        .expect("the tile set has enough yaojiu tiles")
+    })
+}
+
+/// Places `qipai`, the live-wall `zimo`, the `lingshang` tiles and the baopai and li baopai
+/// `indicators`, then fills every other index with the tiles of `tile_set` left over, in
+/// `TileKind::ALL` order.
+#[cfg(test)]
+fn arrange<P: PlayerSet>(
+    tiles: &mut [Option<TileKind>],
+    tile_set: &TileSet,
+    qipai: &[[TileKind; 13]],
+    zimo: &[TileKind],
+    lingshang: &[TileKind],
+    indicators: &[(TileKind, TileKind)],
+) {
+    for (deal_index, bingpai) in qipai.iter().enumerate() {
+        for (tile_index, &tile_kind) in bingpai.iter().enumerate() {
+            tiles[qipai_index::<P>(deal_index, tile_index)] = Some(tile_kind);
+        }
+    }
+    let qipai_tile_count = QIPAI_TILE_COUNT_PER_SEAT * P::PLAYER_COUNT;
+    for (index, &tile_kind) in zimo.iter().enumerate() {
+        tiles[qipai_tile_count + index] = Some(tile_kind);
+    }
+    let first_lingshang_zimo_index = tiles.len() - 1;
+    let last_live_wall_index = tiles.len() - WANGPAI_TILE_COUNT - 1;
+    for (index, &tile_kind) in lingshang.iter().enumerate() {
+        let tile_index = match index.checked_sub(LINGSHANG_TILE_COUNT) {
+            None => first_lingshang_zimo_index - index,
+            Some(extra_index) => last_live_wall_index - extra_index,
+        };
+        tiles[tile_index] = Some(tile_kind);
+    }
+    let initial_baopai_indicator_index = first_lingshang_zimo_index - LINGSHANG_TILE_COUNT;
+    for (index, &(baopai_indicator, li_baopai_indicator)) in indicators.iter().enumerate() {
+        tiles[initial_baopai_indicator_index - index * 2] = Some(baopai_indicator);
+        tiles[initial_baopai_indicator_index - index * 2 - 1] = Some(li_baopai_indicator);
+    }
+
+    let mut leftover = TileKind::ALL.map(|tile_kind| tile_set.max_count(tile_kind));
+    for tile_kind in tiles.iter().flatten() {
+        leftover[tile_kind.index()] -= 1;
+    }
+    let mut leftover = TileKind::ALL
+        .into_iter()
+        .flat_map(|tile_kind| (0..leftover[tile_kind.index()]).map(move |_| tile_kind));
+    for tile_kind in tiles.iter_mut().filter(|tile_kind| tile_kind.is_none()) {
+        *tile_kind = leftover.next();
+    }
 }
 
 #[cfg(test)]
@@ -257,20 +430,40 @@ mod tests {
         (tiles, tile_set)
     }
 
-    #[test]
-    fn four_player_index_layout_classifies_every_tile_once() {
-        let mut indices = (0..52)
-            .chain(FOUR_PLAYER_QIPAI_TILE_COUNT..136 - FOUR_PLAYER_WANGPAI_TILE_COUNT)
-            .chain(
-                (0..FOUR_PLAYER_LINGSHANG_TILE_COUNT)
-                    .map(|index| FOUR_PLAYER_FIRST_LINGSHANG_ZIMO_INDEX - index),
-            )
-            .chain((0..5).map(|index| FOUR_PLAYER_INITIAL_BAOPAI_INDICATOR_INDEX - index * 2))
-            .chain((0..5).map(|index| FOUR_PLAYER_INITIAL_BAOPAI_INDICATOR_INDEX - index * 2 - 1))
+    fn classified_indices<P: PlayerSet>(tile_count: usize) -> Vec<usize> {
+        let qipai_tile_count = QIPAI_TILE_COUNT_PER_SEAT * P::PLAYER_COUNT;
+        let first_lingshang_zimo_index = tile_count - 1;
+        let initial_baopai_indicator_index = first_lingshang_zimo_index - LINGSHANG_TILE_COUNT;
+        let mut indices = (0..qipai_tile_count)
+            .map(|index| {
+                qipai_index::<P>(
+                    index / QIPAI_TILE_COUNT_PER_SEAT,
+                    index % QIPAI_TILE_COUNT_PER_SEAT,
+                )
+            })
+            .chain(qipai_tile_count..tile_count - WANGPAI_TILE_COUNT)
+            .chain((0..LINGSHANG_TILE_COUNT).map(|index| first_lingshang_zimo_index - index))
+            .chain((0..5).map(|index| initial_baopai_indicator_index - index * 2))
+            .chain((0..5).map(|index| initial_baopai_indicator_index - index * 2 - 1))
             .collect::<Vec<_>>();
         indices.sort_unstable();
+        indices
+    }
 
-        assert_eq!(indices, (0..136).collect::<Vec<_>>());
+    #[test]
+    fn four_player_index_layout_classifies_every_tile_once() {
+        assert_eq!(
+            classified_indices::<FourPlayer>(136),
+            (0..136).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn three_player_index_layout_classifies_every_tile_once() {
+        assert_eq!(
+            classified_indices::<ThreePlayer>(108),
+            (0..108).collect::<Vec<_>>()
+        );
     }
 
     #[test]
@@ -864,4 +1057,194 @@ mod tests {
 
         assert!(Bipai::<FourPlayer>::try_new(tiles, tile_set).is_ok());
     }
+
+    fn red_two_tiles() -> ([TileKind; 108], TileSet) {
+        let tile_set = TileSet::red_two_three_player();
+
+        let mut tiles = [TileKind::M1; 108];
+        let mut cursor = 0;
+        for tile_kind in TileKind::ALL {
+            for _ in 0..tile_set.max_count(tile_kind) {
+                tiles[cursor] = tile_kind;
+                cursor += 1;
+            }
+        }
+        (tiles, tile_set)
+    }
+
+    #[test]
+    fn newly_constructed_three_player_bipai_has_94_remaining_tiles() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+
+        assert_eq!(bipai.remaining_count(), 94);
+    }
+
+    #[test]
+    fn three_player_qipai_deals_thirteen_expected_tiles_in_deal_order() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+        let (_, actual): (_, [Bingpai; 3]) = bipai.qipai();
+
+        let mut expected = [[0; 37]; 3];
+        expected[0][TileKind::M1.index()] = 4;
+        expected[0][TileKind::P2.index()] = 4;
+        expected[0][TileKind::P5.index()] = 3;
+        expected[0][TileKind::P6.index()] = 1;
+        expected[0][TileKind::P8.index()] = 1;
+
+        expected[1][TileKind::M9.index()] = 4;
+        expected[1][TileKind::P3.index()] = 4;
+        expected[1][TileKind::P6.index()] = 3;
+        expected[1][TileKind::P7.index()] = 1;
+        expected[1][TileKind::P8.index()] = 1;
+
+        expected[2][TileKind::P1.index()] = 4;
+        expected[2][TileKind::P4.index()] = 4;
+        expected[2][TileKind::P7.index()] = 3;
+        expected[2][TileKind::P8.index()] = 2;
+
+        assert_eq!(actual.map(|bingpai| *bingpai.counts()), expected);
+    }
+
+    #[test]
+    fn three_player_qipai_leaves_55_remaining_tiles() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+        let (bipai, _): (_, [Bingpai; 3]) = bipai.qipai();
+
+        assert_eq!(bipai.remaining_count(), 55);
+    }
+
+    #[test]
+    fn three_player_wangpai_is_the_last_fourteen_tiles() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+        let (bipai, _) = bipai.qipai();
+        let bipai = bipai.reveal_initial_baopai_indicator().unwrap();
+        let (bipai, lingshang) = bipai.lingshang_zimo().unwrap();
+
+        assert_eq!(
+            (
+                lingshang,
+                bipai.baopai_indicators().collect::<Vec<_>>(),
+                bipai.li_baopai_indicators().collect::<Vec<_>>(),
+            ),
+            (tiles[107], vec![tiles[103]], vec![tiles[102]]),
+        );
+    }
+
+    #[test]
+    fn three_player_zimo_stops_after_55_tiles() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+        let (mut bipai, _) = bipai.qipai();
+        for _ in 0..55 {
+            (bipai, _) = bipai.zimo().unwrap();
+        }
+
+        assert_eq!(bipai.zimo(), Err(BipaiError::LiveWallExhausted));
+    }
+
+    #[test]
+    fn three_player_bipai_rejects_four_player_tile_set() {
+        let (tiles, _) = red_two_tiles();
+
+        assert_eq!(
+            Bipai::<ThreePlayer>::try_new(tiles, TileSet::red_three_four_player()),
+            Err(BipaiError::TileSetMismatch {
+                tile_kind: TileKind::M2,
+                actual_count: 0,
+                expected_count: 4,
+            }),
+        );
+    }
+
+    #[test]
+    fn three_player_fifth_lingshang_zimo_is_rejected_without_babei() {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set).unwrap();
+        let (mut bipai, _) = bipai.qipai();
+        for _ in 0..4 {
+            (bipai, _) = bipai.lingshang_zimo().unwrap();
+        }
+
+        assert_eq!(
+            bipai.lingshang_zimo(),
+            Err(BipaiError::LingshangWallExhausted)
+        );
+    }
+
+    fn babei_lingshang_qipai() -> ([TileKind; 108], Bipai<ThreePlayer, QipaiCompleted>) {
+        let (tiles, tile_set) = red_two_tiles();
+        let bipai = Bipai::<ThreePlayer>::try_new(tiles, tile_set)
+            .unwrap()
+            .with_babei_lingshang();
+        let (bipai, _) = bipai.qipai();
+        (tiles, bipai)
+    }
+
+    #[test]
+    fn babei_lingshang_zimo_continues_from_index_93_after_the_wangpai() {
+        let (tiles, mut bipai) = babei_lingshang_qipai();
+
+        let mut actual = Vec::new();
+        for _ in 0..8 {
+            let tile_kind;
+            (bipai, tile_kind) = bipai.lingshang_zimo().unwrap();
+            actual.push(tile_kind);
+        }
+
+        assert_eq!(
+            actual,
+            [107, 106, 105, 104, 93, 92, 91, 90].map(|index| tiles[index]),
+        );
+    }
+
+    #[test]
+    fn babei_lingshang_wall_is_exhausted_after_eight_lingshang_zimo() {
+        let (_, mut bipai) = babei_lingshang_qipai();
+        for _ in 0..8 {
+            (bipai, _) = bipai.lingshang_zimo().unwrap();
+        }
+
+        assert_eq!(
+            bipai.lingshang_zimo(),
+            Err(BipaiError::LingshangWallExhausted)
+        );
+    }
+
+    /// The three-player bipai after eight lingshang zimo and the 47 normal zimo left.
+    fn eight_babei_and_every_zimo() -> (
+        [TileKind; 108],
+        Bipai<ThreePlayer, QipaiCompleted>,
+        Option<TileKind>,
+    ) {
+        let (tiles, mut bipai) = babei_lingshang_qipai();
+        for _ in 0..8 {
+            (bipai, _) = bipai.lingshang_zimo().unwrap();
+        }
+
+        let mut last = None;
+        for _ in 0..47 {
+            let tile_kind;
+            (bipai, tile_kind) = bipai.zimo().unwrap();
+            last = Some(tile_kind);
+        }
+        (tiles, bipai, last)
+    }
+
+    #[test]
+    fn eight_babei_lingshang_zimo_shorten_normal_zimo_through_index_85() {
+        let (tiles, _, last) = eight_babei_and_every_zimo();
+
+        assert_eq!(last, Some(tiles[85]));
+    }
+
+    #[test]
+    fn eight_babei_lingshang_zimo_exhaust_the_live_wall_after_47_zimo() {
+        let (_, bipai, _) = eight_babei_and_every_zimo();
+
+        assert_eq!(bipai.zimo(), Err(BipaiError::LiveWallExhausted));
+    }
 }
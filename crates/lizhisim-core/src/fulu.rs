@@ -0,0 +1,300 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+use thiserror::Error;
+
+use crate::bingpai::{Bingpai, BingpaiError};
+use crate::seat::Seat;
+use crate::tile::TileKind;
+
+pub(crate) const MAX_FULU_COUNT: usize = 4;
+const MAX_FULU_BINGPAI_TILE_COUNT: usize = 4;
+const SHOUPAI_TILE_COUNT: usize = 13;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum FuluError {
+    #[error("player cannot hold another fulu")]
+    FuluFull,
+    #[error("jiagang requires a peng fulu of {tile_kind:?}")]
+    JiagangTargetMissing { tile_kind: TileKind },
+    #[error("bingpai and fulu hold {actual_count} tiles, which breaks tile conservation")]
+    TileCountMismatch { actual_count: usize },
+    #[error(transparent)]
+    Bingpai(#[from] BingpaiError),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FuluKind {
+    Chi,
+    Peng,
+    Daminggang,
+    Angang,
+    Jiagang,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct CalledTile<P> {
+    tile_kind: TileKind,
+    source: Seat<P>,
+}
+
+impl<P> CalledTile<P> {
+    pub fn tile_kind(&self) -> TileKind {
+        self.tile_kind
+    }
+
+    pub fn source(&self) -> &Seat<P> {
+        &self.source
+    }
+}
+
+/// A fulu keeps each tile as its [`TileKind`], so `M0`/`P0`/`S0` stay distinguishable from the base fives.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Fulu<P> {
+    kind: FuluKind,
+    bingpai_tiles: Vec<TileKind, MAX_FULU_BINGPAI_TILE_COUNT>,
+    called_tile: Option<CalledTile<P>>,
+}
+
+impl<P> Fulu<P> {
+    pub(crate) fn chi(
+        bingpai_tiles: [TileKind; 2],
+        called_tile: TileKind,
+        source: Seat<P>,
+    ) -> Self {
+        Self::called(FuluKind::Chi, &bingpai_tiles, called_tile, source)
+    }
+
+    pub(crate) fn peng(
+        bingpai_tiles: [TileKind; 2],
+        called_tile: TileKind,
+        source: Seat<P>,
+    ) -> Self {
+        Self::called(FuluKind::Peng, &bingpai_tiles, called_tile, source)
+    }
+
+    pub(crate) fn daminggang(
+        bingpai_tiles: [TileKind; 3],
+        called_tile: TileKind,
+        source: Seat<P>,
+    ) -> Self {
+        Self::called(FuluKind::Daminggang, &bingpai_tiles, called_tile, source)
+    }
+
+    pub(crate) fn angang(bingpai_tiles: [TileKind; 4]) -> Self {
+        Self {
+            kind: FuluKind::Angang,
+            bingpai_tiles: Vec::from_array(bingpai_tiles),
+            called_tile: None,
+        }
+    }
+
+    fn called(
+        kind: FuluKind,
+        bingpai_tiles: &[TileKind],
+        called_tile: TileKind,
+        source: Seat<P>,
+    ) -> Self {
+        Self {
+            kind,
+            bingpai_tiles: bingpai_tiles.iter().copied().collect(),
+            called_tile: Some(CalledTile {
+                tile_kind: called_tile,
+                source,
+            }),
+        }
+    }
+
+    /// Upgrades a peng of the same base tile; the added tile is appended to the bingpai tiles.
+    pub(crate) fn extend_to_jiagang(&mut self, tile_kind: TileKind) -> Result<(), FuluError> {
+        if !self.is_jiagang_target(tile_kind) {
+            return Err(FuluError::JiagangTargetMissing { tile_kind });
+        }
+        // A peng holds two bingpai tiles, so the third always fits.
+        let _ = self.bingpai_tiles.push(tile_kind);
+        self.kind = FuluKind::Jiagang;
+        Ok(())
+    }
+
+    pub(crate) fn is_jiagang_target(&self, tile_kind: TileKind) -> bool {
+        self.kind == FuluKind::Peng && self.bingpai_tiles[0].base_tile() == tile_kind.base_tile()
+    }
+
+    pub fn kind(&self) -> FuluKind {
+        self.kind
+    }
+
+    /// Tiles that came from the caller's bingpai, in the order they were taken.
+    pub fn bingpai_tiles(&self) -> &[TileKind] {
+        &self.bingpai_tiles
+    }
+
+    pub fn called_tile(&self) -> Option<&CalledTile<P>> {
+        self.called_tile.as_ref()
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = TileKind> + '_ {
+        self.bingpai_tiles.iter().copied().chain(
+            self.called_tile
+                .iter()
+                .map(|called_tile| called_tile.tile_kind),
+        )
+    }
+
+    pub fn is_gang(&self) -> bool {
+        matches!(
+            self.kind,
+            FuluKind::Daminggang | FuluKind::Angang | FuluKind::Jiagang
+        )
+    }
+
+    /// Angang keeps the bingpai closed; every other fulu opens it.
+    pub fn is_open(&self) -> bool {
+        self.kind != FuluKind::Angang
+    }
+}
+
+/// Checks that bingpai and fulu never exceed the tile set and that each fulu stands in for three
+/// bingpai tiles, the fourth gang tile being the one replaced by lingshang zimo.
+pub(crate) fn check_tile_conservation<P>(
+    bingpai: &Bingpai,
+    fulu: &[Fulu<P>],
+) -> Result<(), FuluError> {
+    let mut combined = bingpai.clone();
+    for tile_kind in fulu.iter().flat_map(Fulu::tiles) {
+        combined = combined.with_added(tile_kind)?;
+    }
+
+    let bingpai_count = bingpai
+        .counts()
+        .iter()
+        .map(|&count| usize::from(count))
+        .sum::<usize>();
+    let actual_count = bingpai_count + 3 * fulu.len();
+    // The caller holds one extra tile until the dapai that follows chi or peng.
+    if actual_count != SHOUPAI_TILE_COUNT && actual_count != SHOUPAI_TILE_COUNT + 1 {
+        return Err(FuluError::TileCountMismatch { actual_count });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player_set::FourPlayer;
+
+    use super::*;
+
+    use TileKind::*;
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn bingpai(tile_kinds: &[TileKind]) -> Bingpai {
+        tile_kinds
+            .iter()
+            .try_fold(Bingpai::red_three_four_player(), |bingpai, &tile_kind| {
+                bingpai.with_added(tile_kind)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn chi_records_called_tile_and_source_seat() {
+        let fulu = Fulu::chi([M4, M0], M3, seat(0));
+
+        assert_eq!(
+            fulu.called_tile()
+                .map(|called| (called.tile_kind(), *called.source())),
+            Some((M3, seat(0)))
+        );
+    }
+
+    #[test]
+    fn fulu_tiles_keep_hong_baopai_identity() {
+        let fulu = Fulu::peng([M5, M0], M5, seat(2));
+
+        assert_eq!(fulu.tiles().collect::<std::vec::Vec<_>>(), [M5, M0, M5]);
+    }
+
+    #[test]
+    fn angang_is_closed_gang_without_called_tile() {
+        let fulu = Fulu::<FourPlayer>::angang([Z1, Z1, Z1, Z1]);
+
+        assert_eq!(
+            (fulu.is_gang(), fulu.is_open(), fulu.called_tile()),
+            (true, false, None)
+        );
+    }
+
+    #[test]
+    fn jiagang_turns_peng_into_jiagang() {
+        let mut fulu = Fulu::peng([P5, P5], P5, seat(1));
+        fulu.extend_to_jiagang(P0).unwrap();
+
+        assert_eq!(fulu.kind(), FuluKind::Jiagang);
+    }
+
+    #[test]
+    fn jiagang_extends_peng_of_same_base_tile() {
+        let mut fulu = Fulu::peng([P5, P5], P5, seat(1));
+        fulu.extend_to_jiagang(P0).unwrap();
+
+        assert_eq!(fulu.tiles().collect::<std::vec::Vec<_>>(), [P5, P5, P0, P5]);
+    }
+
+    #[test]
+    fn jiagang_rejects_chi() {
+        let mut chi = Fulu::chi([M4, M5], M3, seat(0));
+
+        assert_eq!(
+            chi.extend_to_jiagang(M3),
+            Err(FuluError::JiagangTargetMissing { tile_kind: M3 })
+        );
+    }
+
+    #[test]
+    fn jiagang_rejects_peng_of_another_tile_kind() {
+        let mut peng = Fulu::peng([M3, M3], M3, seat(0));
+
+        assert_eq!(
+            peng.extend_to_jiagang(M4),
+            Err(FuluError::JiagangTargetMissing { tile_kind: M4 })
+        );
+    }
+
+    #[test]
+    fn conservation_accepts_thirteen_tile_equivalent_shoupai() {
+        let bingpai = bingpai(&[M1, M2, M3, P4, P5, P6, S7, S8, S9, Z1]);
+        let fulu = [Fulu::peng([Z5, Z5], Z5, seat(3))];
+
+        assert_eq!(check_tile_conservation(&bingpai, &fulu), Ok(()));
+    }
+
+    #[test]
+    fn conservation_rejects_missing_tiles() {
+        let bingpai = bingpai(&[M1, M2, M3, P4, P5, P6, S7, S8, S9]);
+        let fulu = [Fulu::peng([Z5, Z5], Z5, seat(3))];
+
+        assert_eq!(
+            check_tile_conservation(&bingpai, &fulu),
+            Err(FuluError::TileCountMismatch { actual_count: 12 })
+        );
+    }
+
+    #[test]
+    fn conservation_rejects_tile_kind_beyond_tile_set() {
+        let bingpai = bingpai(&[Z5, Z5, M1, M2, M3, P4, P5, P6, S7, S8]);
+        let fulu = [Fulu::peng([Z5, Z5], Z5, seat(3))];
+
+        assert_eq!(
+            check_tile_conservation(&bingpai, &fulu),
+            Err(FuluError::Bingpai(BingpaiError::TileCountExceeded {
+                tile_kind: Z5,
+                max_count: 4
+            }))
+        );
+    }
+}
@@ -19,28 +19,66 @@ pub struct Sipai {
     pub moqie: bool,
 }
 
+/// Position of a sipai in a `He`; valid for the rest of the round because `He` only appends.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SipaiIndex(u8);
+
+impl SipaiIndex {
+    pub const fn value(self) -> usize {
+        self.0 as usize
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct He {
     sipai: Vec<Sipai, MAX_SIPAI_COUNT>,
+    called: Vec<bool, MAX_SIPAI_COUNT>,
 }
 
 impl He {
     pub(crate) const fn new() -> Self {
-        Self { sipai: Vec::new() }
+        Self {
+            sipai: Vec::new(),
+            called: Vec::new(),
+        }
     }
 
     pub fn last(&self) -> Option<&Sipai> {
         self.sipai.last()
     }
 
+    pub fn last_index(&self) -> Option<SipaiIndex> {
+        // The capacity of 27 fits in `u8`.
+        self.sipai
+            .len()
+            .checked_sub(1)
+            .map(|index| SipaiIndex(index as u8))
+    }
+
+    pub fn get(&self, index: SipaiIndex) -> Option<&Sipai> {
+        self.sipai.get(index.value())
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Sipai> {
         self.sipai.iter()
     }
 
+    pub fn called_flags(&self) -> impl Iterator<Item = bool> + '_ {
+        self.called.iter().copied()
+    }
+
     pub(crate) fn with_appended(mut self, sipai: Sipai) -> Result<Self, HeFull> {
         self.sipai.push(sipai).map_err(|_| HeFull)?;
+        self.called.push(false).map_err(|_| HeFull)?;
         Ok(self)
     }
+
+    pub(crate) fn with_last_called(mut self) -> Self {
+        if let Some(called) = self.called.last_mut() {
+            *called = true;
+        }
+        self
+    }
 }
 
 #[cfg(test)]
@@ -57,4 +95,59 @@ mod tests {
 
         assert_eq!(he.iter().collect::<std::vec::Vec<_>>(), vec![&sipai]);
     }
+
+    #[test]
+    fn last_index_points_at_last_appended_sipai() {
+        let he = [TileKind::M1, TileKind::M2]
+            .into_iter()
+            .try_fold(He::new(), |he, tile_kind| {
+                he.with_appended(Sipai {
+                    tile_kind,
+                    moqie: false,
+                })
+            })
+            .unwrap();
+        let index = he.last_index().unwrap();
+
+        assert_eq!(
+            (index.value(), he.get(index).map(|sipai| sipai.tile_kind)),
+            (1, Some(TileKind::M2))
+        );
+    }
+
+    #[test]
+    fn empty_he_has_no_last_index() {
+        assert_eq!(He::new().last_index(), None);
+    }
+
+    #[test]
+    fn appended_sipai_is_not_called() {
+        let he = He::new()
+            .with_appended(Sipai {
+                tile_kind: TileKind::M1,
+                moqie: false,
+            })
+            .unwrap();
+
+        assert_eq!(he.called_flags().collect::<std::vec::Vec<_>>(), [false]);
+    }
+
+    #[test]
+    fn marking_last_called_keeps_earlier_sipai_uncalled() {
+        let he = [TileKind::M1, TileKind::M2]
+            .into_iter()
+            .try_fold(He::new(), |he, tile_kind| {
+                he.with_appended(Sipai {
+                    tile_kind,
+                    moqie: true,
+                })
+            })
+            .unwrap()
+            .with_last_called();
+
+        assert_eq!(
+            he.called_flags().collect::<std::vec::Vec<_>>(),
+            [false, true]
+        );
+    }
 }
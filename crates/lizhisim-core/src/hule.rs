@@ -0,0 +1,162 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+mod in_house;
+
+use heapless::Vec;
+use thiserror::Error;
+
+use crate::bingpai::Bingpai;
+use crate::fulu::{Fulu, FuluKind, MAX_FULU_COUNT};
+use crate::tile::TileKind;
+
+pub use in_house::InHouseHule;
+
+/// One suit of 14 tiles reads as at most eight mianzi shapes, and qiduizi may add one more.
+pub(crate) const MAX_HULE_SHAPE_COUNT: usize = 9;
+
+/// The port that reads a complete hand as hule shapes.
+///
+/// [`InHouseHule`] covers every shape; an external adapter declares the shapes it supports so
+/// that a rule needing anything else is rejected before the table starts.
+pub trait Hule {
+    const CAPABILITIES: &'static [HuleCapability];
+
+    /// Every distinct reading of the hand, empty when the hand is not complete.
+    fn decompose<P>(&self, hand: &HuleHand<'_, P>) -> HuleShapes;
+}
+
+/// A shape family a [`Hule`] implementation can read.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HuleCapability {
+    Mianzi,
+    Qiduizi,
+    Guoshi,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum HuleCapabilityError {
+    #[error("hule implementation does not support {capability:?}")]
+    Missing { capability: HuleCapability },
+}
+
+/// Checks that `H` declares every capability in `required`.
+pub fn require_hule_capabilities<H: Hule>(
+    required: &[HuleCapability],
+) -> Result<(), HuleCapabilityError> {
+    match required
+        .iter()
+        .find(|capability| !H::CAPABILITIES.contains(capability))
+    {
+        Some(&capability) => Err(HuleCapabilityError::Missing { capability }),
+        None => Ok(()),
+    }
+}
+
+/// A hand at hule: bingpai without the hulepai, fulu and the hulepai itself.
+pub struct HuleHand<'a, P> {
+    bingpai: &'a Bingpai,
+    fulu: &'a [Fulu<P>],
+    hulepai: TileKind,
+}
+
+impl<'a, P> HuleHand<'a, P> {
+    pub fn new(bingpai: &'a Bingpai, fulu: &'a [Fulu<P>], hulepai: TileKind) -> Self {
+        Self {
+            bingpai,
+            fulu,
+            hulepai,
+        }
+    }
+
+    pub fn bingpai(&self) -> &Bingpai {
+        self.bingpai
+    }
+
+    pub fn fulu(&self) -> &[Fulu<P>] {
+        self.fulu
+    }
+
+    pub fn hulepai(&self) -> TileKind {
+        self.hulepai
+    }
+}
+
+pub type HuleShapes = Vec<HuleShape, MAX_HULE_SHAPE_COUNT>;
+
+/// One reading of a complete hand. Tiles are base tiles, so hong baopai are counted elsewhere.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum HuleShape {
+    /// Four mianzi and a quetou; bingpai mianzi come first in tile order, then fulu in call order.
+    Mianzi {
+        quetou: TileKind,
+        mianzi: Vec<Mianzi, MAX_FULU_COUNT>,
+        wait: HuleWait,
+    },
+    /// Seven distinct duizi in tile order.
+    Qiduizi { duizi: [TileKind; 7] },
+    /// The thirteen yaojiu kinds with `quetou` held twice.
+    Guoshi { quetou: TileKind },
+}
+
+/// A mianzi named by its lowest base tile.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Mianzi {
+    kind: MianziKind,
+    tile_kind: TileKind,
+    /// The fulu the mianzi comes from, or `None` when it is read from bingpai.
+    fulu_kind: Option<FuluKind>,
+}
+
+impl Mianzi {
+    pub fn kind(&self) -> MianziKind {
+        self.kind
+    }
+
+    pub fn tile_kind(&self) -> TileKind {
+        self.tile_kind
+    }
+
+    pub fn fulu_kind(&self) -> Option<FuluKind> {
+        self.fulu_kind
+    }
+
+    /// Angang stays closed like a bingpai mianzi.
+    pub fn is_open(&self) -> bool {
+        self.fulu_kind
+            .is_some_and(|fulu_kind| fulu_kind != FuluKind::Angang)
+    }
+
+    /// Whether a kezi or gangzi is concealed: read from bingpai or angang, except a bingpai kezi
+    /// completed by rong on a shuangpeng wait of `hulepai`.
+    pub fn is_anke(&self, hulepai: TileKind, wait: HuleWait, zimo: bool) -> bool {
+        match self.kind {
+            MianziKind::Shunzi => false,
+            MianziKind::Kezi => {
+                self.fulu_kind.is_none()
+                    && (zimo
+                        || wait != HuleWait::Shuangpeng
+                        || self.tile_kind != hulepai.base_tile())
+            }
+            MianziKind::Gangzi => self.fulu_kind == Some(FuluKind::Angang),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MianziKind {
+    Shunzi,
+    Kezi,
+    Gangzi,
+}
+
+/// Which part of the reading the hulepai completed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HuleWait {
+    Liangmian,
+    Qianzhang,
+    Bianzhang,
+    Shuangpeng,
+    Danqi,
+}
@@ -0,0 +1,639 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::fulu::{Fulu, FuluKind, MAX_FULU_COUNT};
+use crate::hule::{
+    Hule, HuleCapability, HuleHand, HuleShape, HuleShapes, HuleWait, Mianzi, MianziKind,
+};
+use crate::tile::TileKind;
+use crate::tingpai::{BASE_TILE_KIND_COUNT, YAOJIU_INDICES, base_counts};
+
+const SUIT_TILE_KIND_COUNT: usize = 27;
+
+/// Reads every mianzi arrangement, qiduizi and guoshi without an external crate.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct InHouseHule;
+
+impl Hule for InHouseHule {
+    const CAPABILITIES: &'static [HuleCapability] = &[
+        HuleCapability::Mianzi,
+        HuleCapability::Qiduizi,
+        HuleCapability::Guoshi,
+    ];
+
+    fn decompose<P>(&self, hand: &HuleHand<'_, P>) -> HuleShapes {
+        let mut shapes = HuleShapes::new();
+        let fulu = hand.fulu();
+        let hulepai = hand.hulepai().base_tile().index();
+        let mut counts = base_counts(hand.bingpai());
+        counts[hulepai] += 1;
+        let total: usize = counts.iter().map(|&count| usize::from(count)).sum();
+        if fulu.len() > MAX_FULU_COUNT || total != 3 * (MAX_FULU_COUNT - fulu.len()) + 2 {
+            return shapes;
+        }
+
+        for quetou in 0..BASE_TILE_KIND_COUNT {
+            if counts[quetou] < 2 {
+                continue;
+            }
+            counts[quetou] -= 2;
+            let mut blocks = Vec::new();
+            read_mianzi(&mut counts, 0, &mut blocks, &mut |blocks| {
+                push_mianzi_shapes(&mut shapes, quetou, blocks, fulu, hulepai);
+            });
+            counts[quetou] += 2;
+        }
+
+        if fulu.is_empty() {
+            if counts.iter().filter(|&&count| count == 2).count() == 7 {
+                let mut duizi = (0..BASE_TILE_KIND_COUNT)
+                    .filter(|&index| counts[index] == 2)
+                    .map(|index| TileKind::ALL[index]);
+                let duizi = core::array::from_fn(|_| duizi.next().unwrap_or(TileKind::M1));
+                push_unique(&mut shapes, HuleShape::Qiduizi { duizi });
+            }
+            if is_guoshi(&counts) {
+                let quetou = (0..BASE_TILE_KIND_COUNT)
+                    .find(|&index| counts[index] == 2)
+                    .map_or(TileKind::M1, |index| TileKind::ALL[index]);
+                push_unique(&mut shapes, HuleShape::Guoshi { quetou });
+            }
+        }
+        shapes
+    }
+}
+
+/// A bingpai mianzi as its kind and lowest base index.
+type Block = (MianziKind, usize);
+
+/// Visits each way to read `counts` as mianzi only. At the lowest held kind, at most one kezi is
+/// taken and every remaining copy must start a shunzi, so each arrangement is visited once.
+fn read_mianzi(
+    counts: &mut [u8; BASE_TILE_KIND_COUNT],
+    start: usize,
+    blocks: &mut Vec<Block, MAX_FULU_COUNT>,
+    visit: &mut impl FnMut(&[Block]),
+) {
+    let Some(index) = (start..BASE_TILE_KIND_COUNT).find(|&index| counts[index] > 0) else {
+        visit(blocks);
+        return;
+    };
+
+    let held = counts[index];
+    for kezi_count in 0..=u8::from(held >= 3) {
+        let shunzi_count = held - 3 * kezi_count;
+        if shunzi_count > 0
+            && (index >= SUIT_TILE_KIND_COUNT
+                || index % 9 > 6
+                || counts[index + 1] < shunzi_count
+                || counts[index + 2] < shunzi_count)
+        {
+            continue;
+        }
+        let block_count = blocks.len();
+        let taken = (0..kezi_count)
+            .map(|_| (MianziKind::Kezi, index))
+            .chain((0..shunzi_count).map(|_| (MianziKind::Shunzi, index)))
+            .all(|block| blocks.push(block).is_ok());
+        if taken {
+            counts[index] = 0;
+            if shunzi_count > 0 {
+                counts[index + 1] -= shunzi_count;
+                counts[index + 2] -= shunzi_count;
+            }
+            read_mianzi(counts, index + 1, blocks, visit);
+            counts[index] = held;
+            if shunzi_count > 0 {
+                counts[index + 1] += shunzi_count;
+                counts[index + 2] += shunzi_count;
+            }
+        }
+        blocks.truncate(block_count);
+    }
+}
+
+/// Emits one reading for each block the hulepai can have completed.
+fn push_mianzi_shapes<P>(
+    shapes: &mut HuleShapes,
+    quetou: usize,
+    blocks: &[Block],
+    fulu: &[Fulu<P>],
+    hulepai: usize,
+) {
+    let mianzi: Vec<Mianzi, MAX_FULU_COUNT> = blocks
+        .iter()
+        .map(|&(kind, index)| Mianzi {
+            kind,
+            tile_kind: TileKind::ALL[index],
+            fulu_kind: None,
+        })
+        .chain(fulu.iter().map(fulu_mianzi))
+        .collect();
+    let quetou_wait = (quetou == hulepai).then_some(HuleWait::Danqi);
+    let block_waits = blocks
+        .iter()
+        .filter_map(|&(kind, index)| block_wait(kind, index, hulepai));
+    for wait in quetou_wait.into_iter().chain(block_waits) {
+        push_unique(
+            shapes,
+            HuleShape::Mianzi {
+                quetou: TileKind::ALL[quetou],
+                mianzi: mianzi.clone(),
+                wait,
+            },
+        );
+    }
+}
+
+fn block_wait(kind: MianziKind, index: usize, hulepai: usize) -> Option<HuleWait> {
+    match kind {
+        MianziKind::Kezi if index == hulepai => Some(HuleWait::Shuangpeng),
+        MianziKind::Shunzi if (index..index + 3).contains(&hulepai) => {
+            Some(match (hulepai - index, index % 9) {
+                (1, _) => HuleWait::Qianzhang,
+                (0, 6) | (2, 0) => HuleWait::Bianzhang,
+                _ => HuleWait::Liangmian,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn fulu_mianzi<P>(fulu: &Fulu<P>) -> Mianzi {
+    let kind = match fulu.kind() {
+        FuluKind::Chi => MianziKind::Shunzi,
+        FuluKind::Peng => MianziKind::Kezi,
+        FuluKind::Daminggang | FuluKind::Angang | FuluKind::Jiagang => MianziKind::Gangzi,
+    };
+    let tile_kind = fulu
+        .tiles()
+        .map(TileKind::base_tile)
+        .min_by_key(|tile_kind| tile_kind.index())
+        .unwrap_or(TileKind::M1);
+    Mianzi {
+        kind,
+        tile_kind,
+        fulu_kind: Some(fulu.kind()),
+    }
+}
+
+fn is_guoshi(counts: &[u8; BASE_TILE_KIND_COUNT]) -> bool {
+    YAOJIU_INDICES.iter().all(|&index| counts[index] >= 1)
+        && YAOJIU_INDICES
+            .iter()
+            .map(|&index| counts[index])
+            .sum::<u8>()
+            == 14
+}
+
+fn push_unique(shapes: &mut HuleShapes, shape: HuleShape) {
+    if !shapes.contains(&shape) {
+        // One suit of 14 tiles has at most eight readings, so qiduizi still fits.
+        let _ = shapes.push(shape);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use crate::bingpai::Bingpai;
+    use crate::hule::{HuleCapabilityError, require_hule_capabilities};
+    use crate::player_set::FourPlayer;
+    use crate::seat::Seat;
+
+    use super::*;
+
+    use TileKind::*;
+
+    fn bingpai(tile_kinds: &[TileKind]) -> Bingpai {
+        tile_kinds
+            .iter()
+            .fold(Bingpai::red_three_four_player(), |bingpai, &tile_kind| {
+                bingpai.with_added(tile_kind).unwrap()
+            })
+    }
+
+    fn decompose(
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+    ) -> HuleShapes {
+        InHouseHule.decompose(&HuleHand::new(&bingpai(tile_kinds), fulu, hulepai))
+    }
+
+    fn closed(kind: MianziKind, tile_kind: TileKind) -> Mianzi {
+        Mianzi {
+            kind,
+            tile_kind,
+            fulu_kind: None,
+        }
+    }
+
+    fn mianzi_shape(quetou: TileKind, mianzi: &[Mianzi], wait: HuleWait) -> HuleShape {
+        HuleShape::Mianzi {
+            quetou,
+            mianzi: mianzi.iter().copied().collect(),
+            wait,
+        }
+    }
+
+    fn waits(shapes: &HuleShapes) -> Vec<HuleWait> {
+        shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                HuleShape::Mianzi { wait, .. } => Some(*wait),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn four_mianzi_and_quetou_read_with_liangmian() {
+        let shapes = decompose(
+            &[M2, M3, P4, P5, P6, S7, S8, S9, Z1, Z1, Z1, Z5, Z5],
+            &[],
+            M1,
+        );
+
+        assert_eq!(
+            shapes.as_slice(),
+            [mianzi_shape(
+                Z5,
+                &[
+                    closed(MianziKind::Shunzi, M1),
+                    closed(MianziKind::Shunzi, P4),
+                    closed(MianziKind::Shunzi, S7),
+                    closed(MianziKind::Kezi, Z1),
+                ],
+                HuleWait::Liangmian,
+            )]
+        );
+    }
+
+    #[test]
+    fn incomplete_hand_has_no_reading() {
+        let shapes = decompose(
+            &[M2, M3, P4, P5, P6, S7, S8, S9, Z1, Z1, Z1, Z5, Z6],
+            &[],
+            M1,
+        );
+
+        assert!(shapes.is_empty());
+    }
+
+    #[test]
+    fn wrong_tile_count_has_no_reading() {
+        let shapes = decompose(&[M2, M3, Z5, Z5], &[], M1);
+
+        assert!(shapes.is_empty());
+    }
+
+    #[test]
+    fn shunzi_does_not_wrap_between_suits() {
+        let shapes = decompose(
+            &[M8, M9, P2, P3, P4, S7, S8, S9, Z1, Z1, Z1, Z5, Z5],
+            &[],
+            P1,
+        );
+
+        assert!(shapes.is_empty());
+    }
+
+    #[test]
+    fn zipai_do_not_form_shunzi() {
+        let shapes = decompose(
+            &[Z1, Z2, M1, M2, M3, P4, P5, P6, S7, S8, S9, Z5, Z5],
+            &[],
+            Z3,
+        );
+
+        assert!(shapes.is_empty());
+    }
+
+    /// Waits of a hand completed by `a b` and the hulepai, with the rest fixed.
+    fn shunzi_waits(a: TileKind, b: TileKind, hulepai: TileKind) -> Vec<HuleWait> {
+        let mut hand = Vec::from([a, b]);
+        hand.extend([P4, P5, P6, S2, S3, S4, Z1, Z1, Z1, Z5, Z5]);
+        waits(&decompose(&hand, &[], hulepai))
+    }
+
+    #[test]
+    fn one_two_completed_by_three_is_bianzhang() {
+        assert_eq!(shunzi_waits(M1, M2, M3), [HuleWait::Bianzhang]);
+    }
+
+    #[test]
+    fn eight_nine_completed_by_seven_is_bianzhang() {
+        assert_eq!(shunzi_waits(M8, M9, M7), [HuleWait::Bianzhang]);
+    }
+
+    #[test]
+    fn open_ended_pair_completed_outside_is_liangmian() {
+        assert_eq!(shunzi_waits(M4, M5, M3), [HuleWait::Liangmian]);
+    }
+
+    #[test]
+    fn shunzi_completed_in_the_middle_is_qianzhang() {
+        assert_eq!(shunzi_waits(M1, M3, M2), [HuleWait::Qianzhang]);
+    }
+
+    #[test]
+    fn kezi_completed_from_a_duizi_is_shuangpeng() {
+        assert_eq!(
+            waits(&decompose(
+                &[M1, M2, M3, P4, P5, P6, S7, S8, S9, Z1, Z1, Z5, Z5],
+                &[],
+                Z1
+            )),
+            [HuleWait::Shuangpeng]
+        );
+    }
+
+    #[test]
+    fn quetou_completed_from_a_single_tile_is_danqi() {
+        assert_eq!(
+            waits(&decompose(
+                &[M1, M2, M3, P4, P5, P6, S7, S8, S9, Z1, Z1, Z1, Z5],
+                &[],
+                Z5
+            )),
+            [HuleWait::Danqi]
+        );
+    }
+
+    /// 1112 completes as quetou and bianzhang 123 on 3, and as kezi and danqi on 2.
+    const ONE_ONE_ONE_TWO: [TileKind; 13] = [M1, M1, M1, M2, P4, P5, P6, S7, S8, S9, Z1, Z1, Z1];
+
+    #[test]
+    fn one_one_one_two_completed_by_three_reads_quetou_and_bianzhang() {
+        assert_eq!(
+            decompose(&ONE_ONE_ONE_TWO, &[], M3).as_slice(),
+            [mianzi_shape(
+                M1,
+                &[
+                    closed(MianziKind::Shunzi, M1),
+                    closed(MianziKind::Shunzi, P4),
+                    closed(MianziKind::Shunzi, S7),
+                    closed(MianziKind::Kezi, Z1),
+                ],
+                HuleWait::Bianzhang,
+            )]
+        );
+    }
+
+    #[test]
+    fn one_one_one_two_completed_by_two_reads_kezi_and_danqi() {
+        assert_eq!(
+            decompose(&ONE_ONE_ONE_TWO, &[], M2).as_slice(),
+            [mianzi_shape(
+                M2,
+                &[
+                    closed(MianziKind::Kezi, M1),
+                    closed(MianziKind::Shunzi, P4),
+                    closed(MianziKind::Shunzi, S7),
+                    closed(MianziKind::Kezi, Z1),
+                ],
+                HuleWait::Danqi,
+            )]
+        );
+    }
+
+    #[test]
+    fn three_consecutive_kezi_also_read_as_shunzi() {
+        let shapes = decompose(
+            &[M1, M1, M1, M2, M2, M2, M3, M3, M3, P4, P5, P6, Z5],
+            &[],
+            Z5,
+        );
+
+        assert_eq!(
+            shapes.as_slice(),
+            [
+                mianzi_shape(
+                    Z5,
+                    &[
+                        closed(MianziKind::Shunzi, M1),
+                        closed(MianziKind::Shunzi, M1),
+                        closed(MianziKind::Shunzi, M1),
+                        closed(MianziKind::Shunzi, P4),
+                    ],
+                    HuleWait::Danqi,
+                ),
+                mianzi_shape(
+                    Z5,
+                    &[
+                        closed(MianziKind::Kezi, M1),
+                        closed(MianziKind::Kezi, M2),
+                        closed(MianziKind::Kezi, M3),
+                        closed(MianziKind::Shunzi, P4),
+                    ],
+                    HuleWait::Danqi,
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn identical_shunzi_containing_the_hulepai_yield_one_reading() {
+        let shapes = decompose(
+            &[M1, M1, M2, M2, M3, P4, P5, P6, S7, S8, S9, Z5, Z5],
+            &[],
+            M3,
+        );
+
+        assert_eq!(waits(&shapes), [HuleWait::Bianzhang]);
+    }
+
+    #[test]
+    fn qiduizi_coexists_with_mianzi_readings() {
+        let shapes = decompose(
+            &[M1, M1, M2, M2, M3, M3, P4, P4, P5, P5, P6, P6, Z5],
+            &[],
+            Z5,
+        );
+
+        assert_eq!(
+            shapes.as_slice(),
+            [
+                mianzi_shape(
+                    Z5,
+                    &[
+                        closed(MianziKind::Shunzi, M1),
+                        closed(MianziKind::Shunzi, M1),
+                        closed(MianziKind::Shunzi, P4),
+                        closed(MianziKind::Shunzi, P4),
+                    ],
+                    HuleWait::Danqi,
+                ),
+                HuleShape::Qiduizi {
+                    duizi: [M1, M2, M3, P4, P5, P6, Z5],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn four_copies_do_not_make_two_duizi() {
+        let shapes = decompose(
+            &[M1, M1, M1, M1, M2, M2, P4, P4, P5, P5, P6, P6, Z5],
+            &[],
+            Z5,
+        );
+
+        assert!(
+            !shapes
+                .iter()
+                .any(|shape| matches!(shape, HuleShape::Qiduizi { .. }))
+        );
+    }
+
+    #[test]
+    fn guoshi_completed_by_a_pair_reports_the_hulepai_as_quetou() {
+        let hand = [M1, M9, P1, P9, S1, S9, Z1, Z2, Z3, Z4, Z5, Z6, Z7];
+
+        assert_eq!(
+            decompose(&hand, &[], Z7).as_slice(),
+            [HuleShape::Guoshi { quetou: Z7 }]
+        );
+    }
+
+    #[test]
+    fn guoshi_completed_by_the_missing_kind_reports_the_held_pair_as_quetou() {
+        let hand = [M1, M1, P1, P9, S1, S9, Z1, Z2, Z3, Z4, Z5, Z6, Z7];
+
+        assert_eq!(
+            decompose(&hand, &[], M9).as_slice(),
+            [HuleShape::Guoshi { quetou: M1 }]
+        );
+    }
+
+    /// `M4 M0` waits on M3 and M6.
+    const HONG_BAOPAI_LIANGMIAN: [TileKind; 13] =
+        [M4, M0, P4, P5, P6, S7, S8, S9, Z1, Z1, Z1, Z5, Z5];
+
+    #[test]
+    fn hong_baopai_is_read_as_its_base_five() {
+        let shapes = decompose(&HONG_BAOPAI_LIANGMIAN, &[], M3);
+
+        assert_eq!(waits(&shapes), [HuleWait::Liangmian]);
+    }
+
+    #[test]
+    fn hong_baopai_completes_the_shunzi_of_its_base_five() {
+        let shapes = decompose(&HONG_BAOPAI_LIANGMIAN, &[], M3);
+
+        assert!(matches!(
+            &shapes[0],
+            HuleShape::Mianzi { mianzi, .. } if mianzi[0] == closed(MianziKind::Shunzi, M3)
+        ));
+    }
+
+    /// `M2 M3 Z5 Z5` waiting on M1 beside a peng, a chi with S0 and an angang.
+    fn fulu_shapes() -> HuleShapes {
+        let source = Seat::<FourPlayer>::ALL[1];
+        let fulu = [
+            Fulu::peng([Z6, Z6], Z6, source),
+            Fulu::chi([S0, S6], S4, source),
+            Fulu::angang([P9, P9, P9, P9]),
+        ];
+
+        decompose(&[M2, M3, Z5, Z5], &fulu, M1)
+    }
+
+    fn fulu_mianzi() -> Vec<Mianzi> {
+        let shapes = fulu_shapes();
+        let [HuleShape::Mianzi { mianzi, .. }] = shapes.as_slice() else {
+            panic!("expected one mianzi reading, got {shapes:?}");
+        };
+        mianzi.iter().copied().collect()
+    }
+
+    #[test]
+    fn fulu_mianzi_follow_bingpai_mianzi_in_call_order() {
+        assert_eq!(
+            fulu_shapes().as_slice(),
+            [mianzi_shape(
+                Z5,
+                &[
+                    closed(MianziKind::Shunzi, M1),
+                    Mianzi {
+                        kind: MianziKind::Kezi,
+                        tile_kind: Z6,
+                        fulu_kind: Some(FuluKind::Peng),
+                    },
+                    Mianzi {
+                        kind: MianziKind::Shunzi,
+                        tile_kind: S4,
+                        fulu_kind: Some(FuluKind::Chi),
+                    },
+                    Mianzi {
+                        kind: MianziKind::Gangzi,
+                        tile_kind: P9,
+                        fulu_kind: Some(FuluKind::Angang),
+                    },
+                ],
+                HuleWait::Liangmian,
+            )]
+        );
+    }
+
+    #[test]
+    fn called_fulu_mianzi_is_open() {
+        assert!(fulu_mianzi()[1].is_open());
+    }
+
+    #[test]
+    fn angang_mianzi_is_closed() {
+        assert!(!fulu_mianzi()[3].is_open());
+    }
+
+    #[test]
+    fn single_suit_hand_with_most_readings_fits_capacity() {
+        let shapes = decompose(
+            &[M1, M1, M1, M2, M2, M2, M3, M3, M3, M3, M4, M4, M4],
+            &[],
+            M2,
+        );
+
+        assert_eq!(shapes.len(), 8);
+    }
+
+    #[test]
+    fn in_house_engine_covers_every_capability() {
+        assert_eq!(
+            require_hule_capabilities::<InHouseHule>(&[
+                HuleCapability::Mianzi,
+                HuleCapability::Qiduizi,
+                HuleCapability::Guoshi,
+            ]),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn missing_capability_is_reported() {
+        struct MianziOnly;
+
+        impl Hule for MianziOnly {
+            const CAPABILITIES: &'static [HuleCapability] = &[HuleCapability::Mianzi];
+
+            fn decompose<P>(&self, _hand: &HuleHand<'_, P>) -> HuleShapes {
+                HuleShapes::new()
+            }
+        }
+
+        assert_eq!(
+            require_hule_capabilities::<MianziOnly>(&[
+                HuleCapability::Mianzi,
+                HuleCapability::Guoshi,
+            ]),
+            Err(HuleCapabilityError::Missing {
+                capability: HuleCapability::Guoshi,
+            })
+        );
+    }
+}
@@ -3,9 +3,13 @@
 // This file is part of https://github.com/Apricot-S/lizhisim
 
 mod action;
+mod bao;
 mod bingpai;
 mod bipai;
+mod fulu;
 mod he;
+mod hule;
+mod lizhi;
 mod player;
 mod player_set;
 mod round;
@@ -14,19 +18,50 @@ mod seat;
 mod table_match;
 mod tile;
 mod tile_set;
+mod tingpai;
+mod wall_source;
+mod yaku;
 
-pub use action::{Dapai, DapaiError};
+pub use action::{
+    BabeiError, CallResponse, CallWindowError, Dapai, DapaiError, GangError, HuleError,
+    JiuzhongJiupaiError, LizhiError, TurnAction, TurnActionError,
+};
+pub use bao::{Bao, BaoKind, BaoPolicy};
 pub use bingpai::{Bingpai, BingpaiError};
 pub use bipai::{Bipai, BipaiError, BipaiSpec, QipaiCompleted, QipaiPending};
-pub use he::{He, HeFull, Sipai};
+pub use fulu::{CalledTile, Fulu, FuluError, FuluKind};
+pub use he::{He, HeFull, Sipai, SipaiIndex};
+pub use hule::{
+    Hule, HuleCapability, HuleCapabilityError, HuleHand, HuleShape, HuleShapes, HuleWait,
+    InHouseHule, Mianzi, MianziKind, require_hule_capabilities,
+};
+pub use lizhi::LizhiState;
 pub use player::Player;
-pub use player_set::{FourPlayer, PlayerSet};
+pub use player_set::{FourPlayer, PlayerSet, ThreePlayer};
 pub use round::{
-    DapaiCompleted, FirstZimoOrigin, NoReactionResult, Round, RoundEnded, RoundOutcome,
-    ZimoCompleted, ZimoPending,
+    Babei, BabeiResolution, BabeiWindow, CallWindow, CallWindowResolution, DapaiCompleted,
+    FirstZimoOrigin, FuluCompleted, GangBaopaiTiming, HuleContext, LegalTurnActions, LiujuManguan,
+    NoReactionResult, QianggangResolution, QianggangWindow, RongResolution, Round, RoundEnded,
+    RoundOutcome, RoundPolicy, Shiti, SigangSanle, TurnActionResult, ZimoCompleted, ZimoPending,
+};
+pub use score::{
+    BaopaiFan, BasePoints, HuleScore, LeijiYiman, MissingSeatShare, Score, ScoreLimit, ScorePolicy,
 };
-pub use score::Score;
 pub use seat::{Seat, SeatIndexOutOfRange};
-pub use table_match::{Ben, Chang, Lizhibang, RoundIndex, RoundSettlement, TableMatchState};
+pub use table_match::{
+    Ben, BetweenRounds, Bust, Chang, Extension, FinishReason, LeftoverLizhibang, Lizhibang,
+    MatchResultPolicy, MatchRules, MatchTerminationPolicy, PingjuLianzhuang, Placement,
+    PlacementTie, RoundInPlay, RoundIndex, RoundSettled, RoundSettlement, SettlementError,
+    TableMatch, TableMatchError, TableMatchProgress, TableMatchResult, TableMatchState,
+    TableMatchStep, ThousandRounding, Transfer, TransferParty, TransferReason, Transfers,
+};
 pub use tile::TileKind;
 pub use tile_set::{TileSet, TileSetError};
+pub use wall_source::{
+    ExperimentSeed, RoundSeed, SeededWallSource, TableMatchSeed, WallGenerationError, WallRecord,
+    WallShuffle, WallSource,
+};
+pub use yaku::{
+    HuleSituation, Yaku, YakuEvaluation, YakuEvaluations, YakuPolicy, YakuRule, YakuRuleId,
+    YakuValue,
+};
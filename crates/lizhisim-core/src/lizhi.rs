@@ -0,0 +1,84 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::he::SipaiIndex;
+
+/// A player's lizhi progress; the declaration sipai is found through its [`SipaiIndex`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LizhiState {
+    Undeclared,
+    /// The declaration sipai is in `He` and its call window has not resolved yet.
+    Pending {
+        sipai_index: SipaiIndex,
+        liang_lizhi: bool,
+    },
+    /// The declaration sipai passed without rong and the deposit is owed.
+    Established {
+        sipai_index: SipaiIndex,
+        liang_lizhi: bool,
+        yifa: bool,
+    },
+}
+
+impl LizhiState {
+    pub const fn sipai_index(self) -> Option<SipaiIndex> {
+        match self {
+            Self::Undeclared => None,
+            Self::Pending { sipai_index, .. } | Self::Established { sipai_index, .. } => {
+                Some(sipai_index)
+            }
+        }
+    }
+
+    pub const fn is_declared(self) -> bool {
+        !matches!(self, Self::Undeclared)
+    }
+
+    pub const fn is_established(self) -> bool {
+        matches!(self, Self::Established { .. })
+    }
+
+    pub const fn liang_lizhi(self) -> bool {
+        match self {
+            Self::Undeclared => false,
+            Self::Pending { liang_lizhi, .. } | Self::Established { liang_lizhi, .. } => {
+                liang_lizhi
+            }
+        }
+    }
+
+    pub const fn yifa(self) -> bool {
+        matches!(self, Self::Established { yifa: true, .. })
+    }
+
+    /// Settles a pending declaration; yifa starts from the establishment.
+    pub(crate) const fn established(self) -> Self {
+        match self {
+            Self::Pending {
+                sipai_index,
+                liang_lizhi,
+            } => Self::Established {
+                sipai_index,
+                liang_lizhi,
+                yifa: true,
+            },
+            state => state,
+        }
+    }
+
+    pub(crate) const fn without_yifa(self) -> Self {
+        match self {
+            Self::Established {
+                sipai_index,
+                liang_lizhi,
+                ..
+            } => Self::Established {
+                sipai_index,
+                liang_lizhi,
+                yifa: false,
+            },
+            state => state,
+        }
+    }
+}
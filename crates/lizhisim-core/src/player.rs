@@ -3,12 +3,18 @@
 // This file is part of https://github.com/Apricot-S/lizhisim
 
 use crate::action::DapaiError;
-use crate::bingpai::Bingpai;
+use crate::bao::{Bao, BaoKind, BaoPolicy, fulu_bao};
+use crate::bingpai::{Bingpai, BingpaiError};
+use crate::fulu::{Fulu, FuluError, MAX_FULU_COUNT, check_tile_conservation};
 use crate::he::{He, Sipai};
-use crate::player_set::{FourPlayer, PlayerSet};
+use crate::hule::{Hule, HuleHand, HuleShapes, InHouseHule};
+use crate::lizhi::LizhiState;
+use crate::player_set::{FourPlayer, PlayerSet, ThreePlayer};
 use crate::score::Score;
 use crate::seat::Seat;
+use crate::table_match::Placement;
 use crate::tile::TileKind;
+use crate::tingpai::{BASE_TILE_KIND_COUNT, base_counts, hule_kinds, tingpai_kinds};
 
 pub(crate) enum PlayerDapai {
     Moqie(TileKind),
@@ -17,20 +23,41 @@ pub(crate) enum PlayerDapai {
         zimopai: TileKind,
     },
     ShouqieFromZimopai(TileKind),
+    ShouqieAfterFulu(TileKind),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Player<P> {
     seat: Seat<P>,
     bingpai: Bingpai,
+    fulu: heapless::Vec<Fulu<P>, MAX_FULU_COUNT>,
     he: He,
+    /// Copies of `Z4` set aside by babei.
+    babei_count: u8,
     first_turn_eligible: bool,
+    lizhi: LizhiState,
+    /// Set by a missed hule tile and cleared by the player's next zimo or fulu.
+    temporary_zhenting: bool,
+    /// Set by a missed hule tile after lizhi and kept for the rest of the round.
+    lizhi_zhenting: bool,
+    /// Liability for a yaku, kept for the rest of the round.
+    bao: Option<Bao<P>>,
+    /// Liability for the lingshang tile of a daminggang, cleared by the next gang, babei or dapai.
+    lingshang_bao: Option<Bao<P>>,
 }
 
 impl PlayerSet for FourPlayer {
     const PLAYER_COUNT: usize = 4;
     type Players = [Player<FourPlayer>; Self::PLAYER_COUNT];
     type Scores = [Score; Self::PLAYER_COUNT];
+    type Placements = [Placement; Self::PLAYER_COUNT];
+}
+
+impl PlayerSet for ThreePlayer {
+    const PLAYER_COUNT: usize = 3;
+    type Players = [Player<ThreePlayer>; Self::PLAYER_COUNT];
+    type Scores = [Score; Self::PLAYER_COUNT];
+    type Placements = [Placement; Self::PLAYER_COUNT];
 }
 
 impl<P> Player<P> {
@@ -38,8 +65,15 @@ impl<P> Player<P> {
         Self {
             seat,
             bingpai,
+            fulu: heapless::Vec::new(),
             he: He::new(),
+            babei_count: 0,
             first_turn_eligible: true,
+            lizhi: LizhiState::Undeclared,
+            temporary_zhenting: false,
+            lizhi_zhenting: false,
+            bao: None,
+            lingshang_bao: None,
         }
     }
 
@@ -51,29 +85,198 @@ impl<P> Player<P> {
         &self.bingpai
     }
 
+    pub fn fulu(&self) -> &[Fulu<P>] {
+        &self.fulu
+    }
+
     pub fn he(&self) -> &He {
         &self.he
     }
 
+    pub fn babei_count(&self) -> u8 {
+        self.babei_count
+    }
+
     pub fn first_turn_eligible(&self) -> bool {
         self.first_turn_eligible
     }
 
-    #[cfg_attr(
-        not(test),
-        expect(dead_code, reason = "will be called by fulu and babei transitions")
-    )]
+    pub fn lizhi(&self) -> LizhiState {
+        self.lizhi
+    }
+
+    /// Whether rong is forbidden by any kind of zhenting.
+    pub fn is_zhenting(&self) -> bool {
+        self.sipai_zhenting() || self.temporary_zhenting || self.lizhi_zhenting
+    }
+
+    /// Whether the player's own he holds a tingpai kind; called sipai stay in `He` and count.
+    pub fn sipai_zhenting(&self) -> bool {
+        let tingpai = self.tingpai_kinds();
+        self.he
+            .iter()
+            .any(|sipai| tingpai[sipai.tile_kind.base_tile().index()])
+    }
+
+    pub fn temporary_zhenting(&self) -> bool {
+        self.temporary_zhenting
+    }
+
+    pub fn lizhi_zhenting(&self) -> bool {
+        self.lizhi_zhenting
+    }
+
+    /// The seat liable for a yaku of this player's hule.
+    pub fn bao(&self) -> Option<&Bao<P>> {
+        self.bao.as_ref()
+    }
+
+    /// The liability that applies to a zimo hule on the current zimopai.
+    pub(crate) fn zimo_bao(&self) -> Option<Bao<P>> {
+        self.bao.or(self.lingshang_bao)
+    }
+
+    /// Whether every sipai so far is yaojiu and none was called, as liuju manguan requires.
+    pub fn liuju_manguan_eligible(&self) -> bool {
+        self.he.iter().next().is_some()
+            && self.he.iter().all(|sipai| sipai.tile_kind.is_yaojiu())
+            && !self.he.called_flags().any(|called| called)
+    }
+
+    /// Every reading of bingpai and fulu completed by `hulepai`, which is not yet in bingpai.
+    pub(crate) fn hule_shapes(&self, hulepai: TileKind) -> HuleShapes {
+        InHouseHule.decompose(&HuleHand::new(&self.bingpai, &self.fulu, hulepai))
+    }
+
+    pub(crate) fn tingpai_kinds(&self) -> [bool; BASE_TILE_KIND_COUNT] {
+        tingpai_kinds(&InHouseHule, &self.bingpai, &self.fulu)
+    }
+
+    /// The base kinds the player waits on at huangpai pingju, before any yaku is required.
+    ///
+    /// `shouzhong_kongting` decides whether a wait on a kind whose four copies the player holds
+    /// across bingpai and fulu still counts.
+    pub(crate) fn huangpai_tingpai_kinds(
+        &self,
+        shouzhong_kongting: bool,
+    ) -> [bool; BASE_TILE_KIND_COUNT] {
+        let mut held_counts = base_counts(&self.bingpai);
+        for tile_kind in self.fulu.iter().flat_map(Fulu::tiles) {
+            held_counts[tile_kind.base_tile().index()] += 1;
+        }
+        held_counts[TileKind::Z4.index()] += self.babei_count;
+        let hule_kinds = hule_kinds(&InHouseHule, &self.bingpai, &self.fulu);
+        core::array::from_fn(|index| {
+            hule_kinds[index] && (shouzhong_kongting || held_counts[index] < 4)
+        })
+    }
+
+    /// Records a hule tile left to pass, whether rong was declined or never offered.
+    pub(crate) fn with_missed_hulepai(mut self, tile_kind: TileKind) -> Self {
+        if self.tingpai_kinds()[tile_kind.base_tile().index()] {
+            self.temporary_zhenting = true;
+            self.lizhi_zhenting |= self.lizhi.is_declared();
+        }
+        self
+    }
+
+    pub(crate) fn clear_temporary_zhenting(mut self) -> Self {
+        self.temporary_zhenting = false;
+        self
+    }
+
     pub(crate) fn clear_first_turn_eligibility(mut self) -> Self {
         self.first_turn_eligible = false;
         self
     }
 
+    /// Marks the last sipai as the lizhi declaration awaiting its call window.
+    pub(crate) fn with_lizhi_declared(mut self, liang_lizhi: bool) -> Self {
+        if let Some(sipai_index) = self.he.last_index() {
+            self.lizhi = LizhiState::Pending {
+                sipai_index,
+                liang_lizhi,
+            };
+        }
+        self
+    }
+
+    pub(crate) fn with_lizhi_established(mut self) -> Self {
+        self.lizhi = self.lizhi.established();
+        self
+    }
+
+    pub(crate) fn clear_yifa(mut self) -> Self {
+        self.lizhi = self.lizhi.without_yifa();
+        self
+    }
+
+    pub(crate) fn with_called_sipai(mut self) -> Self {
+        self.he = self.he.with_last_called();
+        self
+    }
+
+    pub(crate) fn with_fulu(mut self, fulu: Fulu<P>) -> Result<Self, FuluError> {
+        for &tile_kind in fulu.bingpai_tiles() {
+            self.bingpai = self.bingpai.with_removed(tile_kind)?;
+        }
+        self.fulu.push(fulu).map_err(|_| FuluError::FuluFull)?;
+        check_tile_conservation(&self.bingpai, &self.fulu)?;
+        self.lingshang_bao = None;
+        Ok(self)
+    }
+
+    /// Records the liability the newest called fulu creates under `policy`.
+    pub(crate) fn with_fulu_bao(mut self, policy: BaoPolicy) -> Self {
+        match fulu_bao(&self.fulu, policy) {
+            Some(bao) if bao.kind() == BaoKind::DaminggangLingshang => {
+                self.lingshang_bao = Some(bao);
+            }
+            Some(bao) => self.bao = Some(bao),
+            None => {}
+        }
+        self
+    }
+
+    pub(crate) fn with_zimopai(mut self, zimopai: TileKind) -> Result<Self, BingpaiError> {
+        self.bingpai = self.bingpai.with_added(zimopai)?;
+        Ok(self)
+    }
+
+    /// Sets one `Z4` aside from bingpai, which already holds the zimopai.
+    pub(crate) fn with_babei(mut self) -> Result<Self, BingpaiError> {
+        self.bingpai = self.bingpai.with_removed(TileKind::Z4)?;
+        self.babei_count += 1;
+        self.lingshang_bao = None;
+        Ok(self)
+    }
+
+    pub(crate) fn with_jiagang(mut self, tile_kind: TileKind) -> Result<Self, FuluError> {
+        let fulu = self
+            .fulu
+            .iter_mut()
+            .find(|fulu| fulu.is_jiagang_target(tile_kind))
+            .ok_or(FuluError::JiagangTargetMissing { tile_kind })?;
+        self.bingpai = self.bingpai.with_removed(tile_kind)?;
+        fulu.extend_to_jiagang(tile_kind)?;
+        check_tile_conservation(&self.bingpai, &self.fulu)?;
+        self.lingshang_bao = None;
+        Ok(self)
+    }
+
     pub(crate) fn dapai(self, dapai: PlayerDapai) -> Result<Self, DapaiError> {
         let Self {
             seat,
             bingpai,
+            fulu,
             he,
+            babei_count,
             first_turn_eligible: _,
+            lizhi,
+            temporary_zhenting,
+            lizhi_zhenting,
+            bao,
+            lingshang_bao: _,
         } = self;
 
         let (bingpai, sipai) = match dapai {
@@ -98,14 +301,29 @@ impl<P> Player<P> {
                     moqie: false,
                 },
             ),
+            PlayerDapai::ShouqieAfterFulu(tile_kind) => (
+                bingpai.with_removed(tile_kind)?,
+                Sipai {
+                    tile_kind,
+                    moqie: false,
+                },
+            ),
         };
         let he = he.with_appended(sipai)?;
 
         Ok(Self {
             seat,
             bingpai,
+            fulu,
             he,
+            babei_count,
             first_turn_eligible: false,
+            // The first dapai after establishment ends the yifa chance.
+            lizhi: lizhi.without_yifa(),
+            temporary_zhenting,
+            lizhi_zhenting,
+            bao,
+            lingshang_bao: None,
         })
     }
 }
@@ -339,8 +557,15 @@ mod tests {
         let player = Player {
             seat: Seat::<FourPlayer>::ALL[0],
             bingpai: bingpai.into_iter().next().unwrap(),
+            fulu: heapless::Vec::new(),
             he,
+            babei_count: 0,
             first_turn_eligible: true,
+            lizhi: LizhiState::Undeclared,
+            temporary_zhenting: false,
+            lizhi_zhenting: false,
+            bao: None,
+            lingshang_bao: None,
         };
 
         assert_eq!(
@@ -348,4 +573,179 @@ mod tests {
             Err(DapaiError::HeFull(crate::he::HeFull))
         );
     }
+
+    fn seat_zero_player() -> Player<FourPlayer> {
+        use TileKind::*;
+
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(
+            [
+                [M1, M2, M5, M0, M9, M9, P1, P2, P3, S4, S5, S6, Z1],
+                [M3, M3, M3, M4, M4, M4, M6, M6, M6, M7, M7, M7, M8],
+                [P4, P4, P4, P5, P5, P5, P6, P6, P6, P7, P7, P7, P8],
+                [S1, S1, S1, S2, S2, S2, S3, S3, S3, S7, S7, S7, S8],
+            ],
+            &[],
+            &[],
+        );
+        let (_, bingpai) = bipai.qipai();
+        Player::from_qipai(
+            Seat::<FourPlayer>::ALL[0],
+            bingpai.into_iter().next().unwrap(),
+        )
+    }
+
+    #[test]
+    fn fulu_moves_bingpai_tiles_into_player_fulu() {
+        let fulu = Fulu::peng(
+            [TileKind::M9, TileKind::M9],
+            TileKind::M9,
+            Seat::<FourPlayer>::ALL[2],
+        );
+
+        let player = seat_zero_player().with_fulu(fulu.clone()).unwrap();
+
+        assert_eq!(
+            (
+                player.fulu(),
+                player.bingpai().counts()[TileKind::M9.index()]
+            ),
+            (&[fulu][..], 0)
+        );
+    }
+
+    #[test]
+    fn fulu_keeps_hong_baopai_copy_chosen_from_bingpai() {
+        let fulu = Fulu::peng(
+            [TileKind::M5, TileKind::M0],
+            TileKind::M5,
+            Seat::<FourPlayer>::ALL[3],
+        );
+
+        let player = seat_zero_player().with_fulu(fulu).unwrap();
+
+        assert_eq!(
+            [TileKind::M5, TileKind::M0]
+                .map(|tile_kind| player.bingpai().counts()[tile_kind.index()]),
+            [0, 0]
+        );
+    }
+
+    #[test]
+    fn fulu_rejects_tiles_missing_from_bingpai() {
+        let fulu = Fulu::chi(
+            [TileKind::P7, TileKind::P8],
+            TileKind::P9,
+            Seat::<FourPlayer>::ALL[3],
+        );
+
+        assert_eq!(
+            seat_zero_player().with_fulu(fulu).err(),
+            Some(FuluError::Bingpai(BingpaiError::TileNotPresent {
+                tile_kind: TileKind::P7
+            }))
+        );
+    }
+
+    /// Waits on M3 and M6 with `M4 M5`.
+    fn tingpai_player() -> Player<FourPlayer> {
+        use TileKind::*;
+
+        let mut counts = [0; 37];
+        for tile_kind in [M4, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1, Z2, Z2] {
+            counts[tile_kind.index()] += 1;
+        }
+        Player::from_qipai(
+            Seat::<FourPlayer>::ALL[0],
+            Bingpai::from_validated_counts(counts, TileSet::red_three_four_player()),
+        )
+    }
+
+    #[test]
+    fn own_sipai_of_tingpai_kind_is_sipai_zhenting() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::M6))
+            .unwrap();
+
+        assert!(player.sipai_zhenting());
+    }
+
+    #[test]
+    fn own_sipai_of_tingpai_kind_is_zhenting() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::M6))
+            .unwrap();
+
+        assert!(player.is_zhenting());
+    }
+
+    #[test]
+    fn called_sipai_still_counts_for_zhenting() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::M3))
+            .unwrap()
+            .with_called_sipai();
+
+        assert!(player.sipai_zhenting());
+    }
+
+    #[test]
+    fn missed_tile_outside_tingpai_kinds_is_not_zhenting() {
+        let player = tingpai_player().with_missed_hulepai(TileKind::M7);
+
+        assert!(!player.is_zhenting());
+    }
+
+    #[test]
+    fn missed_hulepai_sets_temporary_zhenting() {
+        let player = tingpai_player().with_missed_hulepai(TileKind::M3);
+
+        assert_eq!(
+            (player.temporary_zhenting(), player.lizhi_zhenting()),
+            (true, false)
+        );
+    }
+
+    #[test]
+    fn clearing_temporary_zhenting_ends_zhenting() {
+        let player = tingpai_player().with_missed_hulepai(TileKind::M3);
+
+        assert!(!player.clear_temporary_zhenting().is_zhenting());
+    }
+
+    #[test]
+    fn yaojiu_only_uncalled_sipai_is_liuju_manguan_eligible() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::Z7))
+            .unwrap()
+            .dapai(PlayerDapai::Moqie(TileKind::M9))
+            .unwrap();
+
+        assert!(player.liuju_manguan_eligible());
+    }
+
+    #[test]
+    fn liuju_manguan_needs_at_least_one_sipai() {
+        assert!(!tingpai_player().liuju_manguan_eligible());
+    }
+
+    #[test]
+    fn simple_sipai_breaks_liuju_manguan_eligibility() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::Z7))
+            .unwrap()
+            .dapai(PlayerDapai::Moqie(TileKind::M2))
+            .unwrap();
+
+        assert!(!player.liuju_manguan_eligible());
+    }
+
+    #[test]
+    fn called_sipai_breaks_liuju_manguan_eligibility() {
+        let player = tingpai_player()
+            .dapai(PlayerDapai::Moqie(TileKind::Z7))
+            .unwrap()
+            .with_called_sipai();
+
+        assert!(!player.liuju_manguan_eligible());
+    }
 }
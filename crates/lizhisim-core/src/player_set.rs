@@ -2,17 +2,28 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+use crate::player::Player;
+use crate::score::Score;
+use crate::seat::SeatArray;
+use crate::table_match::Placement;
+
 mod private {
     pub trait Sealed {}
 }
 
-pub trait PlayerSet: private::Sealed {
+pub trait PlayerSet: private::Sealed + Copy {
     const PLAYER_COUNT: usize;
-    type Players;
-    type Scores;
+    type Players: SeatArray<Player<Self>>;
+    type Scores: SeatArray<Score>;
+    type Placements: SeatArray<Placement>;
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct FourPlayer;
 
 impl private::Sealed for FourPlayer {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ThreePlayer;
+
+impl private::Sealed for ThreePlayer {}
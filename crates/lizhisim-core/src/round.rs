@@ -2,19 +2,58 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+mod babei;
+mod call_window;
+mod gang;
+mod hule;
+mod liuju;
+mod lizhi;
+mod policy;
+mod qianggang_window;
+mod turn_action;
+
+use core::convert::Infallible;
+
+use heapless::Vec;
+
 use crate::action::{Dapai, DapaiError};
+use crate::bao::Bao;
+use crate::bingpai::Bingpai;
 use crate::bipai::{Bipai, BipaiError, BipaiSpec, QipaiCompleted, QipaiPending};
+use crate::lizhi::LizhiState;
 use crate::player::{Player, PlayerDapai};
-use crate::player_set::{FourPlayer, PlayerSet};
-use crate::seat::Seat;
+use crate::player_set::PlayerSet;
+use crate::score::Score;
+use crate::seat::{Seat, SeatArray};
+use crate::table_match::TableMatchState;
 use crate::tile::TileKind;
 
+use hule::HuleScorer;
+
+pub use babei::{BabeiResolution, BabeiWindow};
+pub use call_window::{CallWindow, CallWindowResolution};
+pub use policy::{
+    Babei, GangBaopaiTiming, LiujuManguan, RongResolution, RoundPolicy, Shiti, SigangSanle,
+};
+pub use qianggang_window::{QianggangResolution, QianggangWindow};
+pub use turn_action::{LegalTurnActions, TurnActionResult};
+
+const MAX_HULE_COUNT: usize = 3;
+const MAX_PLAYER_COUNT: usize = 4;
+const MAX_SHITI_FORBIDDEN_COUNT: usize = 2;
+
 pub struct Round<P: PlayerSet + BipaiSpec, State> {
     bipai: Bipai<P, QipaiCompleted>,
     players: P::Players,
     actor: Seat<P>,
     zhuangjia: Seat<P>,
     first_zimo_origin: FirstZimoOrigin,
+    policy: RoundPolicy<P>,
+    /// The table match state the round is played in, which takes each lizhi deposit as the
+    /// lizhi is established and is settled once the round ends.
+    table_match_state: TableMatchState<P>,
+    /// Gang baopai indicators waiting for the gang actor's next dapai.
+    gang_baopai_pending_count: usize,
     state: State,
 }
 
@@ -28,22 +67,63 @@ pub struct ZimoPending;
 
 pub struct ZimoCompleted {
     zimopai: TileKind,
+    /// Whether the zimopai is a lingshang replacement rather than a live-wall draw.
+    lingshang: bool,
 }
 
-pub struct DapaiCompleted;
+pub struct DapaiCompleted {
+    dapai: TileKind,
+}
+
+pub struct FuluCompleted {
+    /// Base tiles the caller may not dapai under the shiti policy.
+    shiti_forbidden: Vec<TileKind, MAX_SHITI_FORBIDDEN_COUNT>,
+}
 
-pub struct RoundEnded {
+pub struct RoundEnded<P> {
     outcome: RoundOutcome,
+    hule: Vec<HuleContext<P>, MAX_HULE_COUNT>,
+    /// Seats confirmed tingpai under the policy; filled only at huangpai pingju.
+    tingpai: Vec<Seat<P>, MAX_PLAYER_COUNT>,
+    /// Seats eligible for liuju manguan when the policy uses it; filled only at huangpai pingju.
+    liuju_manguan: Vec<Seat<P>, MAX_PLAYER_COUNT>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum RoundOutcome {
     HuangpaiPingju,
+    Hule,
+    JiuzhongJiupai,
+    SifengLianda,
+    SijiaLizhi,
+    SigangSanle,
+    SanjiaHele,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HuleContext<P> {
+    seat: Seat<P>,
+    hulepai: TileKind,
+    rong_source: Option<Seat<P>>,
+    /// Whether the hulepai was robbed from a gang, as the qianggang yaku requires.
+    qianggang: bool,
+    /// Whether the hulepai is a lingshang replacement drawn by the winner.
+    lingshang: bool,
+    /// Whether the live wall was empty at the hulepai: the last zimo, or the dapai after it.
+    haidi: bool,
+    /// Whether the winner's first turn was still uninterrupted, as tianhu, dihu and renhu require.
+    first_turn: bool,
+    /// The seat liable for this hule under the policy.
+    bao: Option<Bao<P>>,
+    /// Whether this winner takes the lizhibang; at most one winner of a round does.
+    takes_lizhibang: bool,
+    /// Whether this winner is paid the ben.
+    takes_ben: bool,
 }
 
 pub enum NoReactionResult<P: PlayerSet + BipaiSpec> {
     NextZimo(Round<P, ZimoPending>),
-    RoundEnded(Round<P, RoundEnded>),
+    RoundEnded(Round<P, RoundEnded<P>>),
 }
 
 impl<P: PlayerSet + BipaiSpec> NoReactionResult<P> {
@@ -69,6 +149,48 @@ impl<P: PlayerSet + BipaiSpec> NoReactionResult<P> {
     }
 }
 
+impl<P> HuleContext<P> {
+    pub fn seat(&self) -> &Seat<P> {
+        &self.seat
+    }
+
+    pub fn hulepai(&self) -> TileKind {
+        self.hulepai
+    }
+
+    pub fn rong_source(&self) -> Option<&Seat<P>> {
+        self.rong_source.as_ref()
+    }
+
+    pub fn qianggang(&self) -> bool {
+        self.qianggang
+    }
+
+    pub fn lingshang(&self) -> bool {
+        self.lingshang
+    }
+
+    pub fn haidi(&self) -> bool {
+        self.haidi
+    }
+
+    pub fn first_turn(&self) -> bool {
+        self.first_turn
+    }
+
+    pub fn bao(&self) -> Option<&Bao<P>> {
+        self.bao.as_ref()
+    }
+
+    pub fn takes_lizhibang(&self) -> bool {
+        self.takes_lizhibang
+    }
+
+    pub fn takes_ben(&self) -> bool {
+        self.takes_ben
+    }
+}
+
 impl<P: PlayerSet + BipaiSpec, State> Round<P, State> {
     pub fn bipai(&self) -> &Bipai<P, QipaiCompleted> {
         &self.bipai
@@ -89,28 +211,47 @@ impl<P: PlayerSet + BipaiSpec, State> Round<P, State> {
     pub fn first_zimo_origin(&self) -> FirstZimoOrigin {
         self.first_zimo_origin
     }
+
+    pub fn policy(&self) -> &RoundPolicy<P> {
+        &self.policy
+    }
+
+    pub fn table_match_state(&self) -> &TableMatchState<P> {
+        &self.table_match_state
+    }
 }
 
-impl Round<FourPlayer, ZimoPending> {
-    #[cfg_attr(
-        not(test),
-        expect(dead_code, reason = "will be called by the Round creation boundary")
-    )]
+impl<P: PlayerSet + BipaiSpec, State> Round<P, State> {
+    pub fn baopai_indicators(&self) -> impl ExactSizeIterator<Item = TileKind> + '_ {
+        self.bipai.baopai_indicators()
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoPending> {
+    /// Deals `bipai` for the round of `table_match_state`, whose zhuangjia starts.
     pub(crate) fn new(
-        bipai: Bipai<FourPlayer, QipaiPending>,
-        zhuangjia: Seat<FourPlayer>,
+        bipai: Bipai<P, QipaiPending>,
         first_zimo_origin: FirstZimoOrigin,
+        policy: RoundPolicy<P>,
+        table_match_state: TableMatchState<P>,
     ) -> Self {
-        let (bipai, mut bingpai) = bipai.qipai();
-        bingpai.rotate_right(zhuangjia.index());
-        let [bingpai0, bingpai1, bingpai2, bingpai3] = bingpai;
-        let [seat0, seat1, seat2, seat3] = Seat::<FourPlayer>::ALL;
-        let players = [
-            Player::from_qipai(seat0, bingpai0),
-            Player::from_qipai(seat1, bingpai1),
-            Player::from_qipai(seat2, bingpai2),
-            Player::from_qipai(seat3, bingpai3),
-        ];
+        let zhuangjia = table_match_state.zhuangjia();
+        let bipai = if policy.babei == Babei::Disabled {
+            bipai
+        } else {
+            bipai.with_babei_lingshang()
+        };
+        let (mut bipai, mut bingpai) = bipai.qipai();
+        if policy.baopai {
+            bipai = bipai
+                .reveal_initial_baopai_indicator()
+                .expect("qipai leaves every baopai indicator hidden");
+        }
+        bingpai.as_mut().rotate_right(zhuangjia.index());
+        let players = P::Players::from_fn(|index| {
+            let seat = Seat::try_from(index).expect("qipai deals one bingpai per seat");
+            Player::from_qipai(seat, bingpai.as_ref()[index].clone())
+        });
 
         Self {
             bipai,
@@ -118,53 +259,79 @@ impl Round<FourPlayer, ZimoPending> {
             actor: zhuangjia,
             zhuangjia,
             first_zimo_origin,
+            policy,
+            table_match_state,
+            gang_baopai_pending_count: 0,
             state: ZimoPending,
         }
     }
 
-    pub fn zimo(self) -> Result<Round<FourPlayer, ZimoCompleted>, BipaiError> {
+    pub fn zimo(self) -> Result<Round<P, ZimoCompleted>, BipaiError> {
         let (bipai, zimopai) = self.bipai.zimo()?;
+        let players = update_player(self.players, self.actor, Player::clear_temporary_zhenting);
 
         Ok(Round {
             bipai,
-            players: self.players,
+            players,
             actor: self.actor,
             zhuangjia: self.zhuangjia,
             first_zimo_origin: self.first_zimo_origin,
-            state: ZimoCompleted { zimopai },
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: ZimoCompleted {
+                zimopai,
+                lingshang: false,
+            },
         })
     }
 }
 
-impl Round<FourPlayer, ZimoCompleted> {
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
     pub fn zimopai(&self) -> TileKind {
         self.state.zimopai
     }
 
-    pub fn dapai(self, dapai: Dapai) -> Result<Round<FourPlayer, DapaiCompleted>, DapaiError> {
+    pub fn dapai(self, dapai: Dapai) -> Result<Round<P, DapaiCompleted>, DapaiError> {
+        if self.babei_required() {
+            return Err(DapaiError::BabeiRequired);
+        }
         let Self {
             bipai,
             players,
             actor,
             zhuangjia,
             first_zimo_origin,
+            policy,
+            table_match_state,
+            gang_baopai_pending_count,
             state,
         } = self;
 
         let actor_index = actor.index();
+        let actor_player = &players[actor_index];
         let player_dapai = match dapai {
             Dapai::Moqie(_)
                 if first_zimo_origin == FirstZimoOrigin::InitialDeal
                     && actor == zhuangjia
-                    && players[actor_index].first_turn_eligible() =>
+                    && actor_player.first_turn_eligible() =>
             {
                 return Err(DapaiError::MoqieUnavailableForInitialDealFirstDapai);
             }
+            Dapai::Moqie(tile_kind) if tile_kind != state.zimopai => {
+                return Err(DapaiError::MoqieTileMismatch {
+                    tile_kind,
+                    zimopai: state.zimopai,
+                });
+            }
             Dapai::Moqie(tile_kind) => PlayerDapai::Moqie(tile_kind),
+            Dapai::Shouqie(_) if actor_player.lizhi().is_declared() => {
+                return Err(DapaiError::ShouqieUnavailableAfterLizhi);
+            }
             Dapai::Shouqie(tile_kind)
                 if first_zimo_origin == FirstZimoOrigin::InitialDeal
                     && actor == zhuangjia
-                    && players[actor_index].first_turn_eligible()
+                    && actor_player.first_turn_eligible()
                     && tile_kind == state.zimopai =>
             {
                 PlayerDapai::ShouqieFromZimopai(tile_kind)
@@ -175,14 +342,8 @@ impl Round<FourPlayer, ZimoCompleted> {
             },
         };
 
-        let [player0, player1, player2, player3] = players;
-        let players = match actor_index {
-            0 => [player0.dapai(player_dapai)?, player1, player2, player3],
-            1 => [player0, player1.dapai(player_dapai)?, player2, player3],
-            2 => [player0, player1, player2.dapai(player_dapai)?, player3],
-            3 => [player0, player1, player2, player3.dapai(player_dapai)?],
-            _ => return Err(DapaiError::ActorIndexOutOfRange { actor_index }),
-        };
+        let players = try_update_player(players, actor, |player| player.dapai(player_dapai))?;
+        let bipai = reveal_gang_baopai(bipai, gang_baopai_pending_count)?;
 
         Ok(Round {
             bipai,
@@ -190,57 +351,302 @@ impl Round<FourPlayer, ZimoCompleted> {
             actor,
             zhuangjia,
             first_zimo_origin,
-            state: DapaiCompleted,
+            policy,
+            table_match_state,
+            gang_baopai_pending_count: 0,
+            state: DapaiCompleted {
+                dapai: dapai.tile_kind(),
+            },
         })
     }
 }
 
-impl Round<FourPlayer, DapaiCompleted> {
-    pub fn no_reaction(self) -> NoReactionResult<FourPlayer> {
-        if self.bipai.remaining_count() == 0 {
+impl<P: PlayerSet + BipaiSpec> Round<P, FuluCompleted> {
+    pub fn dapai(self, dapai: Dapai) -> Result<Round<P, DapaiCompleted>, DapaiError> {
+        let tile_kind = match dapai {
+            Dapai::Moqie(_) => return Err(DapaiError::MoqieUnavailableAfterFulu),
+            Dapai::Shouqie(tile_kind) => tile_kind,
+        };
+        if self.state.shiti_forbidden.contains(&tile_kind.base_tile()) {
+            return Err(DapaiError::ShitiForbidden { tile_kind });
+        }
+        let players = try_update_player(self.players, self.actor, |player| {
+            player.dapai(PlayerDapai::ShouqieAfterFulu(tile_kind))
+        })?;
+        let bipai = reveal_gang_baopai(self.bipai, self.gang_baopai_pending_count)?;
+
+        Ok(Round {
+            bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: 0,
+            state: DapaiCompleted { dapai: tile_kind },
+        })
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, DapaiCompleted> {
+    pub fn dapai_tile_kind(&self) -> TileKind {
+        self.state.dapai
+    }
+
+    pub(crate) fn no_reaction(self) -> NoReactionResult<P> {
+        // No rong answered a lizhi declaration on the dapai.
+        let (players, table_match_state) = establish_lizhi(
+            self.players,
+            self.actor,
+            self.table_match_state,
+            self.policy.lizhi_deposit,
+        );
+        let players = pass_hulepai(players, self.actor, self.state.dapai);
+        let outcome = if self.bipai.remaining_count() == 0 {
+            Some(RoundOutcome::HuangpaiPingju)
+        } else {
+            liuju::passed_dapai_liuju(players.as_ref(), &self.policy)
+        };
+        if let Some(outcome) = outcome {
+            let (tingpai, liuju_manguan) = if outcome == RoundOutcome::HuangpaiPingju {
+                let scorer = HuleScorer {
+                    bipai: &self.bipai,
+                    players: &players,
+                    zhuangjia: self.zhuangjia,
+                    policy: &self.policy,
+                    table_match_state: &table_match_state,
+                };
+                (
+                    huangpai_tingpai_seats(&scorer),
+                    liuju_manguan_seats(players.as_ref(), &self.policy),
+                )
+            } else {
+                (Vec::new(), Vec::new())
+            };
             return NoReactionResult::RoundEnded(Round {
                 bipai: self.bipai,
-                players: self.players,
+                players,
                 actor: self.actor,
                 zhuangjia: self.zhuangjia,
                 first_zimo_origin: self.first_zimo_origin,
+                policy: self.policy,
+                table_match_state,
+                gang_baopai_pending_count: self.gang_baopai_pending_count,
                 state: RoundEnded {
-                    outcome: RoundOutcome::HuangpaiPingju,
+                    outcome,
+                    hule: Vec::new(),
+                    tingpai,
+                    liuju_manguan,
                 },
             });
         }
 
-        let actor_index = self.actor.index();
-        let next_actor = Seat::<FourPlayer>::ALL[(actor_index + 1) % Seat::<FourPlayer>::ALL.len()];
-
         NoReactionResult::NextZimo(Round {
             bipai: self.bipai,
-            players: self.players,
-            actor: next_actor,
+            players,
+            actor: self.actor.after(1),
             zhuangjia: self.zhuangjia,
             first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
             state: ZimoPending,
         })
     }
 }
 
-impl<P: PlayerSet + BipaiSpec> Round<P, RoundEnded> {
+impl<P: PlayerSet + BipaiSpec> Round<P, RoundEnded<P>> {
     pub fn round_outcome(&self) -> RoundOutcome {
         self.state.outcome
     }
+
+    pub fn hule(&self) -> &[HuleContext<P>] {
+        &self.state.hule
+    }
+
+    pub fn tingpai_seats(&self) -> &[Seat<P>] {
+        &self.state.tingpai
+    }
+
+    pub fn liuju_manguan_seats(&self) -> &[Seat<P>] {
+        &self.state.liuju_manguan
+    }
+
+    pub(crate) fn table_match_state_mut(&mut self) -> &mut TableMatchState<P> {
+        &mut self.table_match_state
+    }
+
+    #[cfg(test)]
+    pub(crate) fn policy_mut(&mut self) -> &mut RoundPolicy<P> {
+        &mut self.policy
+    }
+
+    pub(crate) fn into_table_match_state(self) -> TableMatchState<P> {
+        self.table_match_state
+    }
+}
+
+fn try_update_player<P: PlayerSet, E>(
+    players: P::Players,
+    seat: Seat<P>,
+    update: impl FnOnce(Player<P>) -> Result<Player<P>, E>,
+) -> Result<P::Players, E> {
+    players.try_update(seat.index(), update)
+}
+
+fn update_player<P: PlayerSet>(
+    players: P::Players,
+    seat: Seat<P>,
+    update: impl FnOnce(Player<P>) -> Player<P>,
+) -> P::Players {
+    let Ok(players) =
+        try_update_player(players, seat, |player| Ok::<_, Infallible>(update(player)));
+    players
+}
+
+/// Establishes the lizhi `actor` declared on a dapai that passed without rong and posts its
+/// deposit onto the lizhibang.
+fn establish_lizhi<P: PlayerSet>(
+    players: P::Players,
+    actor: Seat<P>,
+    mut table_match_state: TableMatchState<P>,
+    deposit: Score,
+) -> (P::Players, TableMatchState<P>) {
+    if matches!(players[actor.index()].lizhi(), LizhiState::Pending { .. }) {
+        table_match_state.post_lizhi_deposit(actor, deposit);
+    }
+    let players = update_player(players, actor, Player::with_lizhi_established);
+    (players, table_match_state)
+}
+
+/// Every seat other than the actor lets `tile_kind` pass, so it counts as a missed hulepai.
+fn pass_hulepai<P: PlayerSet>(
+    players: P::Players,
+    actor: Seat<P>,
+    tile_kind: TileKind,
+) -> P::Players {
+    players.map(|player| {
+        if *player.seat() == actor {
+            player
+        } else {
+            player.with_missed_hulepai(tile_kind)
+        }
+    })
+}
+
+/// The seats tingpai at huangpai pingju, each on a wait with yaku unless the policy counts
+/// xingshi tingpai.
+fn huangpai_tingpai_seats<P: PlayerSet + BipaiSpec>(
+    scorer: &HuleScorer<'_, P>,
+) -> Vec<Seat<P>, MAX_PLAYER_COUNT> {
+    let policy = scorer.policy;
+    scorer
+        .players
+        .as_ref()
+        .iter()
+        .filter(|player| {
+            let seat = *player.seat();
+            player
+                .huangpai_tingpai_kinds(policy.shouzhong_kongting)
+                .into_iter()
+                .zip(TileKind::ALL)
+                .any(|(waits, tile_kind)| {
+                    waits
+                        && (policy.xingshi_tingpai
+                            || scorer
+                                .hule_score(&HuleContext::tingpai(seat, tile_kind))
+                                .is_some())
+                })
+        })
+        .map(|player| *player.seat())
+        .collect()
+}
+
+fn liuju_manguan_seats<P>(
+    players: &[Player<P>],
+    policy: &RoundPolicy<P>,
+) -> Vec<Seat<P>, MAX_PLAYER_COUNT> {
+    if policy.liuju_manguan == LiujuManguan::Disabled {
+        return Vec::new();
+    }
+    players
+        .iter()
+        .filter(|player| player.liuju_manguan_eligible())
+        .map(|player| *player.seat())
+        .collect()
+}
+
+fn declare_gang_baopai<P: BipaiSpec>(
+    bipai: Bipai<P, QipaiCompleted>,
+    policy: &RoundPolicy<P>,
+    pending_count: usize,
+    timing: GangBaopaiTiming,
+) -> Result<(Bipai<P, QipaiCompleted>, usize), BipaiError> {
+    if !policy.baopai {
+        return Ok((bipai, pending_count));
+    }
+    match timing {
+        // Earlier pending indicators belong to earlier gangs, so they are revealed first.
+        GangBaopaiTiming::Immediate => Ok((reveal_gang_baopai(bipai, pending_count + 1)?, 0)),
+        GangBaopaiTiming::AfterDapai => Ok((bipai, pending_count + 1)),
+    }
+}
+
+fn reveal_gang_baopai<P: BipaiSpec>(
+    mut bipai: Bipai<P, QipaiCompleted>,
+    count: usize,
+) -> Result<Bipai<P, QipaiCompleted>, BipaiError> {
+    for _ in 0..count {
+        bipai = bipai.reveal_additional_baopai_indicator()?;
+    }
+    Ok(bipai)
+}
+
+// Base copies are taken before hong_baopai copies so the default call keeps red fives in bingpai.
+fn held_tiles<const N: usize>(bingpai: &Bingpai, base: TileKind) -> Option<[TileKind; N]> {
+    let hong_baopai = hong_baopai_of(base);
+    let base_count = usize::from(bingpai.counts()[base.index()]);
+    let hong_count = hong_baopai.map_or(0, |tile_kind| {
+        usize::from(bingpai.counts()[tile_kind.index()])
+    });
+    if base_count + hong_count < N {
+        return None;
+    }
+    Some(core::array::from_fn(|index| {
+        if index < base_count {
+            base
+        } else {
+            hong_baopai.unwrap_or(base)
+        }
+    }))
+}
+
+fn hong_baopai_of(base: TileKind) -> Option<TileKind> {
+    match base {
+        TileKind::M5 => Some(TileKind::M0),
+        TileKind::P5 => Some(TileKind::P0),
+        TileKind::S5 => Some(TileKind::S0),
+        _ => None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::action::CallResponse;
     use crate::bipai::Bipai;
     use crate::he::Sipai;
-    use crate::player_set::FourPlayer;
+    use crate::player_set::{FourPlayer, ThreePlayer};
+    use crate::score::Score;
     use crate::seat::Seat;
+    use crate::table_match::TableMatchState;
     use crate::tile::TileKind;
     use crate::tile_set::TileSet;
 
     use super::*;
 
+    use TileKind::*;
+
     fn red_three_tiles() -> ([TileKind; 136], TileSet) {
         let tile_set = TileSet::red_three_four_player();
         let mut tiles = [TileKind::M1; 136];
@@ -274,8 +680,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[0],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
         );
 
         assert_eq!(
@@ -289,7 +696,12 @@ mod tests {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let zhuangjia = Seat::<FourPlayer>::ALL[2];
-        let round = Round::new(bipai, zhuangjia, FirstZimoOrigin::InitialDeal);
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(zhuangjia, [Score::new(25_000); 4]),
+        );
 
         assert_eq!(round.actor(), &zhuangjia);
     }
@@ -300,8 +712,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         );
         let [seat0, seat1, seat2, seat3] = round.players();
 
@@ -322,19 +735,61 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[0],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
         );
 
         assert_eq!(round.bipai().remaining_count(), 70);
     }
 
+    #[test]
+    fn round_reveals_initial_baopai_indicator_when_policy_enables_baopai() {
+        let (mut tiles, tile_set) = red_three_tiles();
+        tiles.swap(131, 0);
+        let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+
+        assert_eq!(
+            round.baopai_indicators().collect::<std::vec::Vec<_>>(),
+            [TileKind::M1]
+        );
+    }
+
+    #[test]
+    fn round_reveals_no_baopai_indicator_when_policy_disables_baopai() {
+        let (tiles, tile_set) = red_three_tiles();
+        let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
+        let policy = RoundPolicy {
+            baopai: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::InitialDeal,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+
+        assert_eq!(round.baopai_indicators().len(), 0);
+    }
+
     #[test]
     fn zimo_consumes_pending_round_and_returns_completed_round() {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let zhuangjia = Seat::<FourPlayer>::ALL[2];
-        let round = Round::new(bipai, zhuangjia, FirstZimoOrigin::InitialDeal);
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(zhuangjia, [Score::new(25_000); 4]),
+        );
 
         let _: Round<FourPlayer, ZimoCompleted> = round.zimo().unwrap();
     }
@@ -345,8 +800,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         );
 
         let round = round.zimo().unwrap();
@@ -360,8 +816,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         );
 
         let round = round.zimo().unwrap();
@@ -376,8 +833,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         );
 
         let round = round.zimo().unwrap();
@@ -391,8 +849,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         );
 
         let round = round.zimo().unwrap();
@@ -406,8 +865,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         )
         .zimo()
         .unwrap();
@@ -438,8 +898,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         )
         .zimo()
         .unwrap();
@@ -457,8 +918,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let transition = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         )
         .zimo()
         .unwrap()
@@ -487,9 +949,14 @@ mod tests {
     fn live_wall_zimopai_dapai_is_moqie() {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let round = Round::new(bipai, Seat::<FourPlayer>::ALL[2], FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
 
         let sipai = round
             .dapai(Dapai::Moqie(TileKind::P5))
@@ -509,9 +976,14 @@ mod tests {
     fn moqie_preserves_actor_bingpai_counts() {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let round = Round::new(bipai, Seat::<FourPlayer>::ALL[2], FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
         let bingpai_counts = *round.players()[2].bingpai().counts();
 
         let result = round
@@ -525,9 +997,14 @@ mod tests {
     fn moqie_appends_zimopai_tile_kind_to_actor_he() {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let round = Round::new(bipai, Seat::<FourPlayer>::ALL[2], FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
 
         let result = round
             .dapai(Dapai::Moqie(TileKind::P5))
@@ -542,9 +1019,14 @@ mod tests {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let zhuangjia = Seat::<FourPlayer>::ALL[2];
-        let round = Round::new(bipai, zhuangjia, FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(zhuangjia, [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
 
         let result = round
             .dapai(Dapai::Moqie(TileKind::P5))
@@ -559,8 +1041,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let round = Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::InitialDeal,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         )
         .zimo()
         .unwrap();
@@ -582,14 +1065,19 @@ mod tests {
             let (tiles, tile_set) = red_three_tiles();
             let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
 
-            Round::new(bipai, zhuangjia, FirstZimoOrigin::LiveWall)
-                .zimo()
-                .unwrap()
-                .dapai(Dapai::Moqie(TileKind::P5))
-                .unwrap()
-                .no_reaction()
-                .next_zimo_pending()
-                .map(|round| *round.actor())
+            Round::new(
+                bipai,
+                FirstZimoOrigin::LiveWall,
+                RoundPolicy::mahjong_soul(),
+                TableMatchState::dong(zhuangjia, [Score::new(25_000); 4]),
+            )
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(TileKind::P5))
+            .unwrap()
+            .no_reaction()
+            .next_zimo_pending()
+            .map(|round| *round.actor())
         });
 
         assert_eq!(
@@ -607,11 +1095,16 @@ mod tests {
     fn no_reaction_preserves_bipai_and_players() {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let round = Round::new(bipai, Seat::<FourPlayer>::ALL[2], FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap()
-            .dapai(Dapai::Moqie(TileKind::P5))
-            .unwrap();
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(TileKind::P5))
+        .unwrap();
         let before = (round.bipai().clone(), round.players().clone());
         let after = match round.no_reaction() {
             NoReactionResult::NextZimo(round) => {
@@ -628,12 +1121,17 @@ mod tests {
         let (mut tiles, tile_set) = red_three_tiles();
         tiles.swap(53, 120);
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let result = Round::new(bipai, Seat::<FourPlayer>::ALL[2], FirstZimoOrigin::LiveWall)
-            .zimo()
-            .unwrap()
-            .dapai(Dapai::Moqie(TileKind::P5))
-            .unwrap()
-            .no_reaction();
+        let result = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(TileKind::P5))
+        .unwrap()
+        .no_reaction();
         let actor_and_zimopai = match result {
             NoReactionResult::NextZimo(round) => round
                 .zimo()
@@ -654,8 +1152,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let mut transition = NoReactionResult::NextZimo(Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         ));
 
         for _ in 0..70 {
@@ -677,8 +1176,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let mut transition = NoReactionResult::NextZimo(Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         ));
 
         for _ in 0..69 {
@@ -702,8 +1202,9 @@ mod tests {
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
         let mut transition = NoReactionResult::NextZimo(Round::new(
             bipai,
-            Seat::<FourPlayer>::ALL[2],
             FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[2], [Score::new(25_000); 4]),
         ));
 
         for _ in 0..69 {
@@ -727,4 +1228,273 @@ mod tests {
 
         assert_eq!(after, Some(before));
     }
+
+    /// Waits on P1 and P4.
+    const TINGPAI_SEAT0: [TileKind; 13] = [M1, M2, M3, M4, M5, M6, M7, M8, M9, P1, P1, P2, P3];
+    const BUTING_SEAT1: [TileKind; 13] = [M2, M5, M8, P2, P5, P8, S2, S5, S8, Z4, Z4, Z6, Z7];
+    /// Waits on Z6.
+    const TINGPAI_SEAT2: [TileKind; 13] = [M6, M7, M8, P4, P5, P6, S1, S2, S3, Z5, Z5, Z5, Z6];
+    const BUTING_SEAT3: [TileKind; 13] = [M4, M7, P3, P6, P9, S1, S4, S7, Z1, Z2, Z3, Z5, Z7];
+    /// Waits only on a fifth M1.
+    const SHOUZHONG_KONGTING: [TileKind; 13] = [M1, M1, M1, M1, P1, P2, P3, S1, S2, S3, Z1, Z1, Z1];
+
+    fn arranged_zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, &[]);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+    }
+
+    fn moqie_until_huangpai_pingju(
+        round: Round<FourPlayer, ZimoPending>,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let mut transition = NoReactionResult::NextZimo(round);
+        loop {
+            transition = match transition {
+                NoReactionResult::NextZimo(round) => zimo_moqie_no_reaction(round),
+                NoReactionResult::RoundEnded(round) => return round,
+            };
+        }
+    }
+
+    fn tingpai_huangpai_pingju() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = arranged_zimo_pending(
+            [TINGPAI_SEAT0, BUTING_SEAT1, TINGPAI_SEAT2, BUTING_SEAT3],
+            &[],
+            RoundPolicy::mahjong_soul(),
+        );
+        moqie_until_huangpai_pingju(round)
+    }
+
+    #[test]
+    fn moqie_to_the_last_tile_ends_by_huangpai_pingju() {
+        let round = tingpai_huangpai_pingju();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::HuangpaiPingju);
+    }
+
+    #[test]
+    fn huangpai_pingju_reports_tingpai_seats() {
+        let round = tingpai_huangpai_pingju();
+
+        assert_eq!(
+            round.tingpai_seats(),
+            [Seat::<FourPlayer>::ALL[0], Seat::<FourPlayer>::ALL[2]]
+        );
+    }
+
+    const SHOUZHONG_KONGTING_QIPAI: [[TileKind; 13]; 4] = [
+        SHOUZHONG_KONGTING,
+        BUTING_SEAT1,
+        TINGPAI_SEAT2,
+        BUTING_SEAT3,
+    ];
+
+    #[test]
+    fn wait_on_own_four_copies_is_not_tingpai_by_default() {
+        let round = moqie_until_huangpai_pingju(arranged_zimo_pending(
+            SHOUZHONG_KONGTING_QIPAI,
+            &[],
+            RoundPolicy::mahjong_soul(),
+        ));
+
+        assert_eq!(round.tingpai_seats(), [Seat::<FourPlayer>::ALL[2]]);
+    }
+
+    #[test]
+    fn wait_on_own_four_copies_is_tingpai_when_policy_allows_it() {
+        let policy = RoundPolicy {
+            shouzhong_kongting: true,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = moqie_until_huangpai_pingju(arranged_zimo_pending(
+            SHOUZHONG_KONGTING_QIPAI,
+            &[],
+            policy,
+        ));
+
+        assert_eq!(
+            round.tingpai_seats(),
+            [Seat::<FourPlayer>::ALL[0], Seat::<FourPlayer>::ALL[2]]
+        );
+    }
+
+    /// Seat 1 pengs P9, discards Z7 and waits on Z6 by danqi until huangpai pingju.
+    fn open_tingpai_seats(
+        seat1: [TileKind; 13],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> std::vec::Vec<Seat<FourPlayer>> {
+        let qipai = [BUTING_SEAT3, seat1, BUTING_SEAT1, SHOUZHONG_KONGTING];
+        let round = zimo_moqie(arranged_zimo_pending(qipai, &[P9], policy));
+        let window = round
+            .call_window()
+            .respond(
+                Seat::<FourPlayer>::ALL[1],
+                CallResponse::Peng {
+                    bingpai_tiles: [P9, P9],
+                },
+            )
+            .unwrap();
+        let CallWindowResolution::Fulu(round) = window.resolve().unwrap() else {
+            panic!("expected peng");
+        };
+        let round = round.dapai(Dapai::Shouqie(Z7)).unwrap();
+        let round = round.no_reaction().into_next_zimo_pending().unwrap();
+        moqie_until_huangpai_pingju(round).tingpai_seats().to_vec()
+    }
+
+    const OPEN_WITHOUT_YAKU: [TileKind; 13] = [P9, P9, M4, M5, M6, P4, P5, P6, S7, S8, S9, Z6, Z7];
+    /// Keeps a Z5 kezi for yipai.
+    const OPEN_WITH_YAKU: [TileKind; 13] = [P9, P9, M4, M5, M6, P4, P5, P6, Z5, Z5, Z5, Z6, Z7];
+
+    fn yaku_required() -> RoundPolicy<FourPlayer> {
+        RoundPolicy {
+            xingshi_tingpai: false,
+            ..RoundPolicy::mahjong_soul()
+        }
+    }
+
+    #[test]
+    fn open_tingpai_without_yaku_is_tingpai_under_xingshi_tingpai() {
+        let tingpai_seats = open_tingpai_seats(OPEN_WITHOUT_YAKU, RoundPolicy::mahjong_soul());
+
+        assert_eq!(tingpai_seats, [Seat::<FourPlayer>::ALL[1]]);
+    }
+
+    #[test]
+    fn open_tingpai_without_yaku_is_buting_without_xingshi_tingpai() {
+        let tingpai_seats = open_tingpai_seats(OPEN_WITHOUT_YAKU, yaku_required());
+
+        assert!(tingpai_seats.is_empty());
+    }
+
+    #[test]
+    fn open_tingpai_with_yaku_is_tingpai_without_xingshi_tingpai() {
+        let tingpai_seats = open_tingpai_seats(OPEN_WITH_YAKU, yaku_required());
+
+        assert_eq!(tingpai_seats, [Seat::<FourPlayer>::ALL[1]]);
+    }
+
+    /// Non-yaojiu bingpai far from tingpai.
+    const SIMPLE_QIPAI: [[TileKind; 13]; 4] = [
+        [M2, M2, M4, M6, M8, P2, P4, P6, P8, S2, S4, S6, S8],
+        [M3, M3, M5, M7, P3, P5, P7, S3, S5, S7, M2, P2, S2],
+        [M4, M6, M8, P4, P6, P8, S4, S6, S8, M3, P3, S3, M7],
+        [M5, M7, P5, P7, S5, S7, M8, P8, S8, M6, P6, S6, M4],
+    ];
+
+    fn liuju_manguan_round(
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let bipai = Bipai::<FourPlayer>::red_three_yaojiu_zimo(SIMPLE_QIPAI, 0);
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+        moqie_until_huangpai_pingju(round)
+    }
+
+    #[test]
+    fn liuju_manguan_ends_by_huangpai_pingju() {
+        let round = liuju_manguan_round(RoundPolicy::mahjong_soul());
+
+        assert_eq!(round.round_outcome(), RoundOutcome::HuangpaiPingju);
+    }
+
+    #[test]
+    fn huangpai_pingju_reports_liuju_manguan_seats() {
+        let round = liuju_manguan_round(RoundPolicy::mahjong_soul());
+
+        assert_eq!(round.liuju_manguan_seats(), [Seat::<FourPlayer>::ALL[0]]);
+    }
+
+    #[test]
+    fn disabled_liuju_manguan_reports_no_seats() {
+        let round = liuju_manguan_round(RoundPolicy {
+            liuju_manguan: LiujuManguan::Disabled,
+            ..RoundPolicy::mahjong_soul()
+        });
+
+        assert!(round.liuju_manguan_seats().is_empty());
+    }
+
+    const THREE_PLAYER_QIPAI: [[TileKind; 13]; 3] = [
+        [M1, M1, M1, M9, M9, M9, P1, P2, P3, P4, P5, P6, P7],
+        [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z1, Z2],
+        [Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, Z7, P9, P9, P9, P8],
+    ];
+
+    fn three_player_round(zhuangjia: usize, zimo: &[TileKind]) -> Round<ThreePlayer, ZimoPending> {
+        let bipai = Bipai::<ThreePlayer>::red_two_arranged(THREE_PLAYER_QIPAI, zimo, &[]);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<ThreePlayer>::ALL[zhuangjia], [Score::new(35_000); 3]),
+        )
+    }
+
+    #[test]
+    fn three_player_round_deals_from_zhuangjia_in_seat_order() {
+        let round = three_player_round(1, &[]);
+        let [seat0, seat1, seat2] = round.players();
+
+        assert_eq!(
+            (
+                [seat0, seat1, seat2].map(|player| *player.seat()),
+                [
+                    seat0.bingpai().counts()[Z5.index()],
+                    seat1.bingpai().counts()[M1.index()],
+                    seat2.bingpai().counts()[Z1.index()],
+                ],
+                round.bipai().remaining_count(),
+            ),
+            (Seat::<ThreePlayer>::ALL, [3, 3, 3], 55),
+        );
+    }
+
+    #[test]
+    fn three_player_no_reaction_passes_turn_from_last_seat_to_seat_0() {
+        let round = three_player_round(2, &[Z4]);
+
+        let next_actor = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(Z4))
+            .unwrap()
+            .no_reaction()
+            .next_zimo_pending()
+            .map(|round| *round.actor());
+
+        assert_eq!(next_actor, Some(Seat::<ThreePlayer>::ALL[0]));
+    }
+
+    #[test]
+    fn three_player_call_window_wraps_from_last_seat_to_seat_0() {
+        let round = three_player_round(2, &[Z1]);
+
+        let window = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(Z1))
+            .unwrap()
+            .call_window();
+
+        assert_eq!(
+            window
+                .eligible_seats()
+                .copied()
+                .collect::<std::vec::Vec<_>>(),
+            [Seat::<ThreePlayer>::ALL[0]],
+        );
+    }
 }
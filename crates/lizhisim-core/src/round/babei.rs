@@ -0,0 +1,537 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::{BabeiError, CallResponse, CallWindowError};
+use crate::bipai::BipaiSpec;
+use crate::player_set::PlayerSet;
+use crate::seat::{Seat, SeatArray};
+use crate::tile::TileKind;
+
+use super::call_window::{CallSlots, rong_responses};
+use super::hule::HuleScorer;
+use super::policy::Babei;
+use super::{Round, RoundEnded, RoundOutcome, ZimoCompleted, pass_hulepai, try_update_player};
+
+/// The window in which other seats may rong the `Z4` set aside before the lingshang zimo.
+pub struct BabeiWindow<P> {
+    slots: CallSlots<P>,
+}
+
+pub enum BabeiResolution<P: PlayerSet + BipaiSpec> {
+    LingshangZimo(Round<P, ZimoCompleted>),
+    Hule(Round<P, RoundEnded<P>>),
+    /// The responses ended the round by an abortive draw instead of hule.
+    Liuju(Round<P, RoundEnded<P>>),
+}
+
+impl<P: PlayerSet + BipaiSpec> BabeiResolution<P> {
+    pub fn into_lingshang_zimo(self) -> Option<Round<P, ZimoCompleted>> {
+        match self {
+            Self::LingshangZimo(round) => Some(round),
+            Self::Hule(_) | Self::Liuju(_) => None,
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Sets one held `Z4` aside, from bingpai or the zimopai.
+    ///
+    /// No baopai indicator is revealed. The lingshang zimo waits for the babei window, which
+    /// stays empty unless the policy allows rong on the `Z4`.
+    pub fn babei(self) -> Result<Round<P, BabeiWindow<P>>, BabeiError> {
+        self.babei_available()?;
+        let zimopai = self.state.zimopai;
+        let players = try_update_player(self.players, self.actor, |player| {
+            player.with_zimopai(zimopai)?.with_babei()
+        })?;
+        // Babei interrupts the first turn and yifa for every player.
+        let players = players.map(|player| player.clear_first_turn_eligibility().clear_yifa());
+
+        let mut slots = CallSlots::new();
+        if self.policy.babei_rong {
+            let scorer = HuleScorer {
+                bipai: &self.bipai,
+                players: &players,
+                zhuangjia: self.zhuangjia,
+                policy: &self.policy,
+                table_match_state: &self.table_match_state,
+            };
+            for offset in 1..P::PLAYER_COUNT {
+                let seat = self.actor.after(offset);
+                slots = slots.with_seat(
+                    seat,
+                    rong_responses(&scorer, seat, TileKind::Z4, self.actor, false),
+                );
+            }
+        }
+
+        Ok(Round {
+            bipai: self.bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: BabeiWindow { slots },
+        })
+    }
+
+    pub(super) fn babei_available(&self) -> Result<(), BabeiError> {
+        if self.policy.babei == Babei::Disabled {
+            return Err(BabeiError::Disabled);
+        }
+        if !self.bipai.lingshang_zimo_available() {
+            return Err(BabeiError::LingshangZimoUnavailable);
+        }
+        let player = &self.players[self.actor.index()];
+        let zimopai = self.state.zimopai;
+        if player.lizhi().is_declared() {
+            // Setting aside the zimopai leaves the waits unchanged.
+            if zimopai != TileKind::Z4 {
+                return Err(BabeiError::UnavailableAfterLizhi);
+            }
+        } else if zimopai != TileKind::Z4 && player.bingpai().counts()[TileKind::Z4.index()] == 0 {
+            return Err(BabeiError::BeiMissing);
+        }
+        Ok(())
+    }
+
+    /// Whether the policy forbids keeping the held `Z4` while babei remains available.
+    pub(super) fn babei_required(&self) -> bool {
+        self.policy.babei == Babei::Required && self.babei_available().is_ok()
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, BabeiWindow<P>> {
+    pub fn eligible_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.eligible_seats()
+    }
+
+    pub fn pending_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.pending_seats()
+    }
+
+    pub fn legal_responses(&self, seat: Seat<P>) -> Option<&[CallResponse]> {
+        self.state.slots.legal_responses(seat)
+    }
+
+    pub fn respond(
+        mut self,
+        seat: Seat<P>,
+        response: CallResponse,
+    ) -> Result<Self, CallWindowError> {
+        self.state.slots.respond(seat, response)?;
+        Ok(self)
+    }
+
+    pub fn resolve(self) -> Result<BabeiResolution<P>, CallWindowError> {
+        if !self.state.slots.is_complete() {
+            return Err(CallWindowError::ResponsesPending);
+        }
+
+        if self.policy.sanjia_hele && self.state.slots.is_sanjia_hele() {
+            return Ok(BabeiResolution::Liuju(Round {
+                bipai: self.bipai,
+                players: self.players,
+                actor: self.actor,
+                zhuangjia: self.zhuangjia,
+                first_zimo_origin: self.first_zimo_origin,
+                policy: self.policy,
+                table_match_state: self.table_match_state,
+                gang_baopai_pending_count: self.gang_baopai_pending_count,
+                state: RoundEnded {
+                    outcome: RoundOutcome::SanjiaHele,
+                    hule: Vec::new(),
+                    tingpai: Vec::new(),
+                    liuju_manguan: Vec::new(),
+                },
+            }));
+        }
+
+        // Robbing the `Z4` is a plain rong, not qianggang.
+        let hule = self.state.slots.rong_hule(
+            self.policy.rong_resolution,
+            TileKind::Z4,
+            self.actor,
+            false,
+            false,
+            &self.players,
+        );
+        if !hule.is_empty() {
+            return Ok(BabeiResolution::Hule(Round {
+                bipai: self.bipai,
+                players: self.players,
+                actor: self.actor,
+                zhuangjia: self.zhuangjia,
+                first_zimo_origin: self.first_zimo_origin,
+                policy: self.policy,
+                table_match_state: self.table_match_state,
+                gang_baopai_pending_count: self.gang_baopai_pending_count,
+                state: RoundEnded {
+                    outcome: RoundOutcome::Hule,
+                    hule,
+                    tingpai: Vec::new(),
+                    liuju_manguan: Vec::new(),
+                },
+            }));
+        }
+
+        let (bipai, zimopai) = self.bipai.lingshang_zimo()?;
+        // Without babei rong the `Z4` is never offered, so no wait misses it.
+        let players = if self.policy.babei_rong {
+            pass_hulepai(self.players, self.actor, TileKind::Z4)
+        } else {
+            self.players
+        };
+        Ok(BabeiResolution::LingshangZimo(Round {
+            bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: ZimoCompleted {
+                zimopai,
+                lingshang: true,
+            },
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{Dapai, DapaiError, TurnAction};
+    use crate::bipai::Bipai;
+    use crate::player_set::ThreePlayer;
+    use crate::round::{
+        CallWindowResolution, DapaiCompleted, FirstZimoOrigin, RoundPolicy, ZimoPending,
+    };
+    use crate::score::Score;
+    use crate::table_match::TableMatchState;
+
+    use super::*;
+
+    use TileKind::*;
+
+    const SEAT0: [TileKind; 13] = [M1, M1, M9, P1, P2, P3, S1, S2, S3, Z5, Z5, Z4, Z6];
+    /// Waits on Z2 and Z4.
+    const SEAT1: [TileKind; 13] = [P4, P5, P6, S7, S8, S9, Z1, Z1, Z1, Z2, Z2, Z4, Z4];
+    const SEAT2: [TileKind; 13] = [P7, P8, P9, S4, S6, M9, M9, Z3, Z3, Z3, Z7, Z7, P1];
+    const LINGSHANG: [TileKind; 2] = [P6, S6];
+
+    fn seat(index: usize) -> Seat<ThreePlayer> {
+        Seat::<ThreePlayer>::ALL[index]
+    }
+
+    fn policy(babei: Babei, babei_rong: bool) -> RoundPolicy<ThreePlayer> {
+        RoundPolicy {
+            babei,
+            babei_rong,
+            ..RoundPolicy::mahjong_soul()
+        }
+    }
+
+    fn zimo_pending(
+        policy: RoundPolicy<ThreePlayer>,
+        zhuangjia: usize,
+        zimo: &[TileKind],
+    ) -> Round<ThreePlayer, ZimoPending> {
+        let bipai = Bipai::<ThreePlayer>::red_two_arranged([SEAT0, SEAT1, SEAT2], zimo, &LINGSHANG);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(zhuangjia), [Score::new(35_000); 3]),
+        )
+    }
+
+    fn babei_ready(policy: RoundPolicy<ThreePlayer>) -> Round<ThreePlayer, ZimoCompleted> {
+        zimo_pending(policy, 0, &[M1]).zimo().unwrap()
+    }
+
+    fn pass_all(round: Round<ThreePlayer, DapaiCompleted>) -> Round<ThreePlayer, ZimoPending> {
+        let mut window = round.call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            window = window.respond(seat, CallResponse::Pass).unwrap();
+        }
+        let CallWindowResolution::NoCall(result) = window.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        result.into_next_zimo_pending().unwrap()
+    }
+
+    fn moqie_pass_all(round: Round<ThreePlayer, ZimoPending>) -> Round<ThreePlayer, ZimoPending> {
+        let round = round.zimo().unwrap();
+        let zimopai = round.zimopai();
+        pass_all(round.dapai(Dapai::Moqie(zimopai)).unwrap())
+    }
+
+    #[test]
+    fn babei_without_rong_policy_opens_no_window() {
+        let window = babei_ready(policy(Babei::Optional, false)).babei().unwrap();
+
+        assert_eq!(window.eligible_seats().count(), 0);
+    }
+
+    #[test]
+    fn babei_sets_bei_aside_and_draws_first_lingshang_tile() {
+        let round = babei_ready(policy(Babei::Optional, false))
+            .babei()
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap();
+        let player = &round.players()[0];
+
+        assert_eq!(
+            (
+                round.zimopai(),
+                player.babei_count(),
+                player.bingpai().counts()[Z4.index()],
+                player.bingpai().counts()[M1.index()],
+                round.bipai().remaining_count(),
+                round.baopai_indicators().count(),
+            ),
+            (P6, 1, 0, 3, 53, 1),
+        );
+    }
+
+    #[test]
+    fn babei_of_zimopai_keeps_held_bei() {
+        let round = zimo_pending(policy(Babei::Optional, false), 0, &[Z4])
+            .zimo()
+            .unwrap();
+
+        let round = round
+            .babei()
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap();
+        let player = &round.players()[0];
+
+        assert_eq!(
+            (player.babei_count(), player.bingpai().counts()[Z4.index()]),
+            (1, 1),
+        );
+    }
+
+    #[test]
+    fn babei_clears_first_turn_eligibility_for_every_player() {
+        let round = babei_ready(policy(Babei::Optional, false))
+            .babei()
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap();
+
+        assert!(
+            round
+                .players()
+                .iter()
+                .all(|player| !player.first_turn_eligible())
+        );
+    }
+
+    #[test]
+    fn babei_is_rejected_when_policy_disables_it() {
+        let round = babei_ready(RoundPolicy::mahjong_soul());
+
+        assert_eq!(round.babei().err(), Some(BabeiError::Disabled));
+    }
+
+    #[test]
+    fn babei_is_rejected_without_held_bei() {
+        let mut round = zimo_pending(policy(Babei::Optional, false), 0, &[M1, M9, S1]);
+        for _ in 0..2 {
+            round = moqie_pass_all(round);
+        }
+        let round = round.zimo().unwrap();
+
+        assert_eq!(round.babei().err(), Some(BabeiError::BeiMissing));
+    }
+
+    #[test]
+    fn babei_after_lizhi_is_rejected_unless_zimopai_is_bei() {
+        let round = zimo_pending(policy(Babei::Optional, false), 0, &[M1, M9, S1, S1, P9]);
+        let round = moqie_pass_all(round).zimo().unwrap();
+        let mut round = pass_all(round.lizhi(Dapai::Moqie(M9)).unwrap());
+        for _ in 0..2 {
+            round = moqie_pass_all(round);
+        }
+        let round = round.zimo().unwrap();
+
+        assert_eq!(round.babei().err(), Some(BabeiError::UnavailableAfterLizhi));
+    }
+
+    #[test]
+    fn babei_rong_offers_rong_to_bei_wait() {
+        let window = babei_ready(policy(Babei::Optional, true)).babei().unwrap();
+
+        assert_eq!(
+            window.legal_responses(seat(1)),
+            Some([CallResponse::Pass, CallResponse::Rong].as_slice()),
+        );
+    }
+
+    #[test]
+    fn babei_rong_offers_nothing_to_other_waits() {
+        let window = babei_ready(policy(Babei::Optional, true)).babei().unwrap();
+
+        assert_eq!(window.legal_responses(seat(2)), None);
+    }
+
+    #[test]
+    fn rong_on_babei_ends_round_without_qianggang() {
+        let window = babei_ready(policy(Babei::Optional, true))
+            .babei()
+            .unwrap()
+            .respond(seat(1), CallResponse::Rong)
+            .unwrap();
+
+        let BabeiResolution::Hule(round) = window.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+        let hule = &round.hule()[0];
+
+        assert_eq!(
+            (
+                *hule.seat(),
+                hule.hulepai(),
+                hule.rong_source().copied(),
+                hule.qianggang(),
+            ),
+            (seat(1), Z4, Some(seat(0)), false),
+        );
+    }
+
+    #[test]
+    fn passed_babei_rong_sets_temporary_zhenting() {
+        let round = babei_ready(policy(Babei::Optional, true))
+            .babei()
+            .unwrap()
+            .respond(seat(1), CallResponse::Pass)
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap();
+
+        assert!(round.players()[1].temporary_zhenting());
+    }
+
+    #[test]
+    fn babei_without_rong_policy_leaves_bei_wait_unaffected() {
+        let round = babei_ready(policy(Babei::Optional, false))
+            .babei()
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap();
+
+        assert!(!round.players()[1].temporary_zhenting());
+    }
+
+    #[test]
+    fn optional_babei_is_legal() {
+        let legal = babei_ready(policy(Babei::Optional, false)).legal_turn_actions();
+
+        assert!(legal.contains(TurnAction::Babei));
+    }
+
+    #[test]
+    fn optional_babei_keeps_dapai_legal() {
+        let legal = babei_ready(policy(Babei::Optional, false)).legal_turn_actions();
+
+        assert!(legal.contains(TurnAction::Dapai(Dapai::Shouqie(Z4))));
+    }
+
+    #[test]
+    fn required_babei_is_the_only_legal_turn_action() {
+        let round = babei_ready(policy(Babei::Required, false));
+
+        assert_eq!(round.legal_turn_actions().as_slice(), [TurnAction::Babei]);
+    }
+
+    #[test]
+    fn required_babei_rejects_dapai() {
+        let round = babei_ready(policy(Babei::Required, false));
+
+        assert_eq!(
+            round.dapai(Dapai::Shouqie(Z4)).err(),
+            Some(DapaiError::BabeiRequired),
+        );
+    }
+
+    #[test]
+    fn babei_policy_raises_lingshang_zimo_capacity_to_eight() {
+        let lingshang_zimo_count = |policy| {
+            let mut bipai = zimo_pending(policy, 0, &[]).bipai().clone();
+            let mut count = 0;
+            while let Ok((next, _)) = bipai.lingshang_zimo() {
+                bipai = next;
+                count += 1;
+            }
+            count
+        };
+
+        assert_eq!(
+            [
+                lingshang_zimo_count(RoundPolicy::mahjong_soul()),
+                lingshang_zimo_count(policy(Babei::Optional, false)),
+            ],
+            [4, 8],
+        );
+    }
+
+    #[test]
+    fn zimo_hule_after_babei_counts_the_bei_as_babei_baopai() {
+        let bipai = Bipai::<ThreePlayer>::red_two_arranged(
+            [
+                [P1, P2, P3, S1, S2, S3, S7, S8, S9, Z5, Z5, Z5, Z4],
+                SEAT1,
+                SEAT2,
+            ],
+            &[S5],
+            &[S5],
+        );
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy(Babei::Optional, false),
+            TableMatchState::dong(seat(0), [Score::new(35_000); 3]),
+        )
+        .zimo()
+        .unwrap()
+        .babei()
+        .unwrap()
+        .resolve()
+        .unwrap()
+        .into_lingshang_zimo()
+        .unwrap()
+        .zimo_hule()
+        .unwrap();
+
+        assert_eq!(
+            round
+                .hule_score(&round.hule()[0])
+                .map(|score| score.baopai().babei_baopai),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,1525 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::{CallResponse, CallWindowError};
+use crate::bingpai::Bingpai;
+use crate::bipai::BipaiSpec;
+use crate::fulu::Fulu;
+use crate::hule::HuleShape;
+use crate::player::Player;
+use crate::player_set::PlayerSet;
+use crate::seat::{Seat, SeatArray};
+use crate::tile::TileKind;
+
+use super::hule::HuleScorer;
+use super::{
+    DapaiCompleted, FuluCompleted, HuleContext, MAX_HULE_COUNT, MAX_SHITI_FORBIDDEN_COUNT,
+    NoReactionResult, RongResolution, Round, RoundEnded, RoundOutcome, Shiti, ZimoCompleted,
+    declare_gang_baopai, establish_lizhi, held_tiles, hong_baopai_of, liuju, pass_hulepai,
+    try_update_player,
+};
+
+const MAX_CALL_SLOT_COUNT: usize = 3;
+/// Rong seats that make sanjia hele, every seat other than the actor at a four-player table.
+const SANJIA_HELE_RONG_COUNT: usize = 3;
+/// Pass, five chi with hong baopai variants, peng, daminggang and rong.
+const MAX_LEGAL_RESPONSE_COUNT: usize = 9;
+
+pub struct CallWindow<P> {
+    dapai: TileKind,
+    slots: CallSlots<P>,
+}
+
+/// Response slots shared by the windows that other seats answer at once.
+pub(super) struct CallSlots<P> {
+    slots: Vec<CallSlot<P>, MAX_CALL_SLOT_COUNT>,
+}
+
+struct CallSlot<P> {
+    seat: Seat<P>,
+    legal_responses: Vec<CallResponse, MAX_LEGAL_RESPONSE_COUNT>,
+    response: Option<CallResponse>,
+}
+
+pub enum CallWindowResolution<P: PlayerSet + BipaiSpec> {
+    NoCall(NoReactionResult<P>),
+    Fulu(Round<P, FuluCompleted>),
+    Daminggang(Round<P, ZimoCompleted>),
+    Hule(Round<P, RoundEnded<P>>),
+    /// The responses ended the round by an abortive draw instead of hule.
+    Liuju(Round<P, RoundEnded<P>>),
+}
+
+impl<P> CallSlots<P> {
+    pub(super) fn new() -> Self {
+        Self { slots: Vec::new() }
+    }
+
+    /// Adds a slot only when the seat has a response other than pass.
+    pub(super) fn with_seat(
+        mut self,
+        seat: Seat<P>,
+        legal_responses: Vec<CallResponse, MAX_LEGAL_RESPONSE_COUNT>,
+    ) -> Self {
+        if legal_responses.len() > 1 {
+            // At most three seats other than the actor can respond.
+            let _ = self.slots.push(CallSlot {
+                seat,
+                legal_responses,
+                response: None,
+            });
+        }
+        self
+    }
+
+    pub(super) fn eligible_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.slots.iter().map(|slot| &slot.seat)
+    }
+
+    pub(super) fn pending_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.response.is_none())
+            .map(|slot| &slot.seat)
+    }
+
+    pub(super) fn is_complete(&self) -> bool {
+        self.slots.iter().all(|slot| slot.response.is_some())
+    }
+
+    /// Non-pass responses in slot order, which starts from the seat after the actor.
+    pub(super) fn calls(&self) -> impl Iterator<Item = (Seat<P>, CallResponse)> + '_ {
+        self.slots.iter().filter_map(|slot| {
+            slot.response
+                .filter(|response| *response != CallResponse::Pass)
+                .map(|response| (slot.seat, response))
+        })
+    }
+
+    /// The winners among the rong responses under `resolution`, in seat order from the seat
+    /// after the actor.
+    pub(super) fn rong_hule(
+        &self,
+        resolution: RongResolution,
+        hulepai: TileKind,
+        rong_source: Seat<P>,
+        qianggang: bool,
+        haidi: bool,
+        players: &P::Players,
+    ) -> Vec<HuleContext<P>, MAX_HULE_COUNT>
+    where
+        P: PlayerSet,
+    {
+        let winner_count = match resolution {
+            RongResolution::Multiple => MAX_HULE_COUNT,
+            RongResolution::FirstInSeatOrder => 1,
+        };
+        self.calls()
+            .filter(|(_, response)| *response == CallResponse::Rong)
+            .take(winner_count)
+            .enumerate()
+            .map(|(order, (seat, _))| {
+                HuleContext::rong(
+                    players,
+                    seat,
+                    hulepai,
+                    rong_source,
+                    qianggang,
+                    haidi,
+                    order == 0,
+                )
+            })
+            .collect()
+    }
+
+    /// Whether enough seats answered rong for sanjia hele.
+    pub(super) fn is_sanjia_hele(&self) -> bool {
+        self.calls()
+            .filter(|(_, response)| *response == CallResponse::Rong)
+            .count()
+            == SANJIA_HELE_RONG_COUNT
+    }
+
+    pub(super) fn legal_responses(&self, seat: Seat<P>) -> Option<&[CallResponse]> {
+        self.slots
+            .iter()
+            .find(|slot| slot.seat == seat)
+            .map(|slot| slot.legal_responses.as_slice())
+    }
+
+    pub(super) fn respond(
+        &mut self,
+        seat: Seat<P>,
+        response: CallResponse,
+    ) -> Result<(), CallWindowError> {
+        let slot = self
+            .slots
+            .iter_mut()
+            .find(|slot| slot.seat == seat)
+            .ok_or(CallWindowError::SeatNotEligible)?;
+        if slot.response.is_some() {
+            return Err(CallWindowError::ResponseAlreadyRecorded);
+        }
+        if !slot.legal_responses.contains(&response) {
+            return Err(CallWindowError::IllegalResponse { response });
+        }
+        slot.response = Some(response);
+        Ok(())
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, DapaiCompleted> {
+    pub fn call_window(self) -> Round<P, CallWindow<P>> {
+        let dapai = self.state.dapai;
+        // A dapai that ends the round by sigang sanle once it passes only offers rong.
+        let fulu_available = self.bipai.remaining_count() > 0
+            && !liuju::sigang_sanle_pending(self.players.as_ref(), &self.policy);
+        let gang_available = fulu_available && self.bipai.lingshang_zimo_available();
+        let haidi = self.bipai.remaining_count() == 0;
+
+        let mut slots = CallSlots::new();
+        for offset in 1..P::PLAYER_COUNT {
+            let seat = self.actor.after(offset);
+            let legal_responses = legal_responses(
+                &self.players[seat.index()],
+                dapai,
+                self.policy.shiti,
+                fulu_available && offset == 1,
+                fulu_available,
+                gang_available,
+                self.hule_scorer()
+                    .rong_available(seat, dapai, self.actor, false, haidi),
+            );
+            slots = slots.with_seat(seat, legal_responses);
+        }
+
+        Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: CallWindow { dapai, slots },
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, CallWindow<P>> {
+    pub fn dapai_tile_kind(&self) -> TileKind {
+        self.state.dapai
+    }
+
+    pub fn eligible_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.eligible_seats()
+    }
+
+    pub fn pending_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.pending_seats()
+    }
+
+    pub fn legal_responses(&self, seat: Seat<P>) -> Option<&[CallResponse]> {
+        self.state.slots.legal_responses(seat)
+    }
+
+    pub fn respond(
+        mut self,
+        seat: Seat<P>,
+        response: CallResponse,
+    ) -> Result<Self, CallWindowError> {
+        self.state.slots.respond(seat, response)?;
+        Ok(self)
+    }
+
+    pub fn resolve(self) -> Result<CallWindowResolution<P>, CallWindowError> {
+        if !self.state.slots.is_complete() {
+            return Err(CallWindowError::ResponsesPending);
+        }
+
+        if self.policy.sanjia_hele && self.state.slots.is_sanjia_hele() {
+            return Ok(CallWindowResolution::Liuju(self.sanjia_hele()));
+        }
+
+        // Slots are ordered from the seat after the dapai actor, so the first match of each
+        // priority class is also the seat-order winner within that class.
+        let calls = || self.state.slots.calls();
+        let selected = calls()
+            .find(|(_, response)| *response == CallResponse::Rong)
+            .or_else(|| {
+                calls().find(|(_, response)| {
+                    matches!(
+                        response,
+                        CallResponse::Peng { .. } | CallResponse::Daminggang { .. }
+                    )
+                })
+            })
+            .or_else(|| calls().next());
+
+        match selected {
+            None | Some((_, CallResponse::Pass)) => {
+                Ok(CallWindowResolution::NoCall(self.into_no_call()))
+            }
+            Some((_, CallResponse::Rong)) => Ok(CallWindowResolution::Hule(self.rong())),
+            Some((seat, CallResponse::Chi { bingpai_tiles })) => {
+                let source = self.actor;
+                let fulu = Fulu::chi(bingpai_tiles, self.state.dapai, source);
+                let shiti_forbidden =
+                    chi_shiti_forbidden(self.policy.shiti, bingpai_tiles, self.state.dapai);
+                self.fulu(seat, fulu, shiti_forbidden)
+                    .map(CallWindowResolution::Fulu)
+            }
+            Some((seat, CallResponse::Peng { bingpai_tiles })) => {
+                let source = self.actor;
+                let fulu = Fulu::peng(bingpai_tiles, self.state.dapai, source);
+                let shiti_forbidden = peng_shiti_forbidden(self.policy.shiti, self.state.dapai);
+                self.fulu(seat, fulu, shiti_forbidden)
+                    .map(CallWindowResolution::Fulu)
+            }
+            Some((seat, CallResponse::Daminggang { bingpai_tiles })) => {
+                let source = self.actor;
+                let fulu = Fulu::daminggang(bingpai_tiles, self.state.dapai, source);
+                let round = self.fulu(seat, fulu, Vec::new())?;
+                let (bipai, gang_baopai_pending_count) = declare_gang_baopai(
+                    round.bipai,
+                    &round.policy,
+                    round.gang_baopai_pending_count,
+                    round.policy.minggang_baopai_timing,
+                )?;
+                let (bipai, zimopai) = bipai.lingshang_zimo()?;
+
+                Ok(CallWindowResolution::Daminggang(Round {
+                    bipai,
+                    players: round.players,
+                    actor: round.actor,
+                    zhuangjia: round.zhuangjia,
+                    first_zimo_origin: round.first_zimo_origin,
+                    policy: round.policy,
+                    table_match_state: round.table_match_state,
+                    gang_baopai_pending_count,
+                    state: ZimoCompleted {
+                        zimopai,
+                        lingshang: true,
+                    },
+                }))
+            }
+        }
+    }
+
+    fn into_no_call(self) -> NoReactionResult<P> {
+        Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: DapaiCompleted {
+                dapai: self.state.dapai,
+            },
+        }
+        .no_reaction()
+    }
+
+    fn sanjia_hele(self) -> Round<P, RoundEnded<P>> {
+        Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: RoundEnded {
+                outcome: RoundOutcome::SanjiaHele,
+                hule: Vec::new(),
+                tingpai: Vec::new(),
+                liuju_manguan: Vec::new(),
+            },
+        }
+    }
+
+    fn rong(self) -> Round<P, RoundEnded<P>> {
+        let hule = self.state.slots.rong_hule(
+            self.policy.rong_resolution,
+            self.state.dapai,
+            self.actor,
+            false,
+            self.bipai.remaining_count() == 0,
+            &self.players,
+        );
+
+        Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: RoundEnded {
+                outcome: RoundOutcome::Hule,
+                hule,
+                tingpai: Vec::new(),
+                liuju_manguan: Vec::new(),
+            },
+        }
+    }
+
+    fn fulu(
+        self,
+        seat: Seat<P>,
+        fulu: Fulu<P>,
+        shiti_forbidden: Vec<TileKind, MAX_SHITI_FORBIDDEN_COUNT>,
+    ) -> Result<Round<P, FuluCompleted>, CallWindowError> {
+        let players = try_update_player(self.players, self.actor, |player| {
+            Ok::<_, CallWindowError>(player.with_called_sipai())
+        })?;
+        let players = pass_hulepai(players, self.actor, self.state.dapai);
+        let players = try_update_player(players, seat, |player| {
+            Ok::<_, CallWindowError>(
+                player
+                    .with_fulu(fulu)?
+                    .with_fulu_bao(self.policy.bao)
+                    .clear_temporary_zhenting(),
+            )
+        })?;
+        // The called declaration sipai still establishes its lizhi, and a fulu interrupts the
+        // first turn and yifa for every player.
+        let (players, table_match_state) = establish_lizhi(
+            players,
+            self.actor,
+            self.table_match_state,
+            self.policy.lizhi_deposit,
+        );
+        let players = players.map(|player| player.clear_first_turn_eligibility().clear_yifa());
+
+        Ok(Round {
+            bipai: self.bipai,
+            players,
+            actor: seat,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: FuluCompleted { shiti_forbidden },
+        })
+    }
+}
+
+fn legal_responses<P>(
+    player: &Player<P>,
+    dapai: TileKind,
+    shiti: Shiti,
+    chi_available: bool,
+    peng_available: bool,
+    gang_available: bool,
+    rong_available: bool,
+) -> Vec<CallResponse, MAX_LEGAL_RESPONSE_COUNT> {
+    let bingpai = player.bingpai();
+    let base = dapai.base_tile();
+    let mut responses = Vec::new();
+    let _ = responses.push(CallResponse::Pass);
+    // A lizhi player keeps the bingpai closed and may only answer with rong.
+    let fulu_allowed = !player.lizhi().is_declared();
+    let (chi_available, peng_available, gang_available) = (
+        chi_available && fulu_allowed,
+        peng_available && fulu_allowed,
+        gang_available && fulu_allowed,
+    );
+
+    if chi_available && base.index() < 27 {
+        let number = base.index() % 9;
+        for offsets in [[-2, -1], [-1, 1], [1, 2]] {
+            let neighbors = offsets.map(|offset| {
+                number
+                    .checked_add_signed(offset)
+                    .filter(|&neighbor| neighbor < 9)
+                    .map(|neighbor| TileKind::ALL[base.index() - number + neighbor])
+            });
+            let [Some(first), Some(second)] = neighbors else {
+                continue;
+            };
+            // Each held spelling of a neighbor, base before hong baopai, is its own decomposition.
+            for first in held_spellings(bingpai, first) {
+                for second in held_spellings(bingpai, second) {
+                    let bingpai_tiles = [first, second];
+                    let shiti_forbidden = chi_shiti_forbidden(shiti, bingpai_tiles, dapai);
+                    if dapai_left_after_call(bingpai, &bingpai_tiles, &shiti_forbidden) {
+                        let _ = responses.push(CallResponse::Chi { bingpai_tiles });
+                    }
+                }
+            }
+        }
+    }
+    if peng_available
+        && let Some(bingpai_tiles) = held_tiles(bingpai, base)
+        && dapai_left_after_call(bingpai, &bingpai_tiles, &peng_shiti_forbidden(shiti, dapai))
+    {
+        let _ = responses.push(CallResponse::Peng { bingpai_tiles });
+    }
+    if gang_available && let Some(bingpai_tiles) = held_tiles(bingpai, base) {
+        let _ = responses.push(CallResponse::Daminggang { bingpai_tiles });
+    }
+    if rong_available {
+        let _ = responses.push(CallResponse::Rong);
+    }
+
+    responses
+}
+
+/// The spellings of `base` held in bingpai, base tile first.
+fn held_spellings(bingpai: &Bingpai, base: TileKind) -> impl Iterator<Item = TileKind> + '_ {
+    [Some(base), hong_baopai_of(base)]
+        .into_iter()
+        .flatten()
+        .filter(|tile_kind| bingpai.counts()[tile_kind.index()] > 0)
+}
+
+fn chi_shiti_forbidden(
+    shiti: Shiti,
+    bingpai_tiles: [TileKind; 2],
+    called_tile: TileKind,
+) -> Vec<TileKind, MAX_SHITI_FORBIDDEN_COUNT> {
+    let mut forbidden = peng_shiti_forbidden(shiti, called_tile);
+    if shiti == Shiti::ForbidSameKindAndSuji {
+        let called = called_tile.base_tile().index();
+        let [low, high] = {
+            let [first, second] = bingpai_tiles.map(|tile_kind| tile_kind.base_tile().index());
+            [first.min(second), first.max(second)]
+        };
+        // Only a call at either end of the sequence has a suji on the other side.
+        let suji = if called < low && high % 9 < 8 {
+            Some(high + 1)
+        } else if called > high && low % 9 > 0 {
+            Some(low - 1)
+        } else {
+            None
+        };
+        if let Some(index) = suji {
+            let _ = forbidden.push(TileKind::ALL[index]);
+        }
+    }
+    forbidden
+}
+
+fn peng_shiti_forbidden(
+    shiti: Shiti,
+    called_tile: TileKind,
+) -> Vec<TileKind, MAX_SHITI_FORBIDDEN_COUNT> {
+    let mut forbidden = Vec::new();
+    if shiti != Shiti::Allowed {
+        let _ = forbidden.push(called_tile.base_tile());
+    }
+    forbidden
+}
+
+/// Whether the bingpai left by a call still holds a tile that the shiti policy lets the caller
+/// dapai.
+fn dapai_left_after_call(
+    bingpai: &Bingpai,
+    bingpai_tiles: &[TileKind],
+    shiti_forbidden: &[TileKind],
+) -> bool {
+    let mut counts = *bingpai.counts();
+    for tile_kind in bingpai_tiles {
+        counts[tile_kind.index()] -= 1;
+    }
+    TileKind::ALL.into_iter().any(|tile_kind| {
+        counts[tile_kind.index()] > 0 && !shiti_forbidden.contains(&tile_kind.base_tile())
+    })
+}
+
+/// Legal responses for a window whose only call is rong of `tile_kind` from `rong_source`,
+/// such as qianggang.
+pub(super) fn rong_responses<P: PlayerSet + BipaiSpec>(
+    scorer: &HuleScorer<'_, P>,
+    seat: Seat<P>,
+    tile_kind: TileKind,
+    rong_source: Seat<P>,
+    qianggang: bool,
+) -> Vec<CallResponse, MAX_LEGAL_RESPONSE_COUNT> {
+    let mut responses = Vec::new();
+    let _ = responses.push(CallResponse::Pass);
+    if scorer.rong_available(seat, tile_kind, rong_source, qianggang, false) {
+        let _ = responses.push(CallResponse::Rong);
+    }
+    responses
+}
+
+/// Legal responses for the qianggang window of an angang, which only a guoshi wait may rob.
+pub(super) fn guoshi_rong_responses<P: PlayerSet + BipaiSpec>(
+    scorer: &HuleScorer<'_, P>,
+    seat: Seat<P>,
+    tile_kind: TileKind,
+    rong_source: Seat<P>,
+) -> Vec<CallResponse, MAX_LEGAL_RESPONSE_COUNT> {
+    let mut responses = Vec::new();
+    let _ = responses.push(CallResponse::Pass);
+    if scorer.players[seat.index()]
+        .hule_shapes(tile_kind)
+        .iter()
+        .any(|shape| matches!(shape, HuleShape::Guoshi { .. }))
+        && scorer.rong_available(seat, tile_kind, rong_source, true, false)
+    {
+        let _ = responses.push(CallResponse::Rong);
+    }
+    responses
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{Dapai, DapaiError};
+    use crate::bipai::Bipai;
+    use crate::player_set::FourPlayer;
+    use crate::round::{FirstZimoOrigin, RoundPolicy, ZimoPending};
+    use crate::score::Score;
+    use crate::table_match::TableMatchState;
+    use crate::tile_set::TileSet;
+
+    use super::*;
+
+    use TileKind::*;
+
+    const SEAT1_CHI: [TileKind; 13] = [M1, M2, M4, M5, S3, S4, S5, S6, S7, S8, Z5, Z5, Z6];
+    const SEAT1_NONE: [TileKind; 13] = [M7, M7, M8, M8, S3, S4, S5, S6, S7, S8, Z5, Z5, Z6];
+    const SEAT2_PENG: [TileKind; 13] = [M3, M3, S9, S9, S9, Z6, Z6, Z7, Z7, Z7, P1, P1, P2];
+    const SEAT2_GANG: [TileKind; 13] = [M3, M3, M3, S9, S9, S9, Z6, Z6, Z7, Z7, Z7, P1, P1];
+    const SEAT2_NONE: [TileKind; 13] = [M6, M6, S9, S9, S9, Z6, Z6, Z7, Z7, Z7, P1, P1, P2];
+    const SEAT3_RONG: [TileKind; 13] = [M1, M2, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+    const SEAT3_NONE: [TileKind; 13] = [M9, M9, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+    const SEAT0: [TileKind; 13] = [Z2, Z2, Z2, Z3, Z3, Z3, Z4, Z4, Z4, S1, S1, S1, S2];
+
+    fn call_window(qipai: [[TileKind; 13]; 4]) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, &[M3], &[Z1]);
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+        round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(M3))
+            .unwrap()
+            .call_window()
+    }
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn respond_all(
+        mut round: Round<FourPlayer, CallWindow<FourPlayer>>,
+        responses: &[(usize, CallResponse)],
+    ) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        for &(index, response) in responses {
+            round = round.respond(seat(index), response).unwrap();
+        }
+        round
+    }
+
+    fn unwrap_fulu(
+        resolution: CallWindowResolution<FourPlayer>,
+    ) -> Round<FourPlayer, FuluCompleted> {
+        match resolution {
+            CallWindowResolution::Fulu(round) => round,
+            _ => panic!("expected fulu resolution"),
+        }
+    }
+
+    #[test]
+    fn call_window_lists_only_seats_with_calls_in_seat_order() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]);
+
+        assert_eq!(
+            round
+                .eligible_seats()
+                .copied()
+                .collect::<std::vec::Vec<_>>(),
+            [seat(1), seat(2)]
+        );
+    }
+
+    #[test]
+    fn call_window_holds_the_dapai_tile_kind() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]);
+
+        assert_eq!(round.dapai_tile_kind(), M3);
+    }
+
+    #[test]
+    fn chi_is_offered_to_next_seat() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_RONG]);
+
+        assert_eq!(
+            round.legal_responses(seat(1)).unwrap(),
+            [
+                CallResponse::Pass,
+                CallResponse::Chi {
+                    bingpai_tiles: [M1, M2]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M2, M4]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M4, M5]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn chi_is_not_offered_to_other_seats() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_RONG]);
+
+        assert_eq!(
+            round.legal_responses(seat(3)).unwrap(),
+            [CallResponse::Pass, CallResponse::Rong]
+        );
+    }
+
+    #[test]
+    fn two_held_copies_offer_peng() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]);
+
+        assert_eq!(
+            round.legal_responses(seat(2)).unwrap(),
+            [
+                CallResponse::Pass,
+                CallResponse::Peng {
+                    bingpai_tiles: [M3, M3]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn three_held_copies_offer_peng_and_daminggang() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_GANG, SEAT3_NONE]);
+
+        assert_eq!(
+            round.legal_responses(seat(2)).unwrap(),
+            [
+                CallResponse::Pass,
+                CallResponse::Peng {
+                    bingpai_tiles: [M3, M3]
+                },
+                CallResponse::Daminggang {
+                    bingpai_tiles: [M3, M3, M3]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn respond_rejects_ineligible_seat() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]);
+
+        assert_eq!(
+            round.respond(seat(3), CallResponse::Pass).err(),
+            Some(CallWindowError::SeatNotEligible)
+        );
+    }
+
+    #[test]
+    fn respond_rejects_response_outside_legal_set() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]);
+
+        assert_eq!(
+            round.respond(seat(1), CallResponse::Rong).err(),
+            Some(CallWindowError::IllegalResponse {
+                response: CallResponse::Rong
+            })
+        );
+    }
+
+    #[test]
+    fn respond_rejects_second_response_from_same_seat() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE])
+            .respond(seat(1), CallResponse::Pass)
+            .unwrap();
+
+        assert_eq!(
+            round.respond(seat(1), CallResponse::Pass).err(),
+            Some(CallWindowError::ResponseAlreadyRecorded)
+        );
+    }
+
+    #[test]
+    fn pending_seats_list_seats_yet_to_respond() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE])
+            .respond(seat(2), CallResponse::Pass)
+            .unwrap();
+
+        assert_eq!(
+            round.pending_seats().copied().collect::<std::vec::Vec<_>>(),
+            [seat(1)]
+        );
+    }
+
+    #[test]
+    fn resolve_requires_every_eligible_response() {
+        let round = call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE])
+            .respond(seat(2), CallResponse::Pass)
+            .unwrap();
+
+        assert!(matches!(
+            round.resolve(),
+            Err(CallWindowError::ResponsesPending)
+        ));
+    }
+
+    #[test]
+    fn all_passes_advance_to_next_zimo() {
+        let round = respond_all(
+            call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]),
+            &[(1, CallResponse::Pass), (2, CallResponse::Pass)],
+        );
+
+        let CallWindowResolution::NoCall(result) = round.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        assert_eq!(result.next_zimo_pending().unwrap().actor(), &seat(1));
+    }
+
+    #[test]
+    fn window_without_calls_has_no_eligible_seats() {
+        let round = call_window([SEAT0, SEAT1_NONE, SEAT2_NONE, SEAT3_NONE]);
+
+        assert_eq!(round.eligible_seats().count(), 0);
+    }
+
+    #[test]
+    fn window_without_eligible_seats_resolves_to_next_zimo() {
+        let round = call_window([SEAT0, SEAT1_NONE, SEAT2_NONE, SEAT3_NONE]);
+
+        assert!(matches!(
+            round.resolve().unwrap(),
+            CallWindowResolution::NoCall(_)
+        ));
+    }
+
+    #[test]
+    fn peng_beats_chi_regardless_of_response_order() {
+        let chi = CallResponse::Chi {
+            bingpai_tiles: [M1, M2],
+        };
+        let peng = CallResponse::Peng {
+            bingpai_tiles: [M3, M3],
+        };
+
+        let actors = [[(1, chi), (2, peng)], [(2, peng), (1, chi)]].map(|responses| {
+            let round = respond_all(
+                call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]),
+                &responses,
+            );
+            *unwrap_fulu(round.resolve().unwrap()).actor()
+        });
+
+        assert_eq!(actors, [seat(2), seat(2)]);
+    }
+
+    fn peng_by_seat2() -> Round<FourPlayer, FuluCompleted> {
+        let round = respond_all(
+            call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]),
+            &[
+                (1, CallResponse::Pass),
+                (
+                    2,
+                    CallResponse::Peng {
+                        bingpai_tiles: [M3, M3],
+                    },
+                ),
+            ],
+        );
+        unwrap_fulu(round.resolve().unwrap())
+    }
+
+    #[test]
+    fn peng_moves_held_copies_out_of_bingpai() {
+        let round = peng_by_seat2();
+
+        assert_eq!(round.players()[2].bingpai().counts()[M3.index()], 0);
+    }
+
+    fn rong_over_peng_and_chi() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = respond_all(
+            call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_RONG]),
+            &[
+                (3, CallResponse::Rong),
+                (
+                    2,
+                    CallResponse::Peng {
+                        bingpai_tiles: [M3, M3],
+                    },
+                ),
+                (
+                    1,
+                    CallResponse::Chi {
+                        bingpai_tiles: [M1, M2],
+                    },
+                ),
+            ],
+        );
+        let CallWindowResolution::Hule(round) = round.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+        round
+    }
+
+    #[test]
+    fn rong_beats_peng_and_chi_and_ends_round() {
+        let round = rong_over_peng_and_chi();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::Hule);
+    }
+
+    #[test]
+    fn rong_hule_is_won_by_the_rong_seat_alone() {
+        let round = rong_over_peng_and_chi();
+
+        assert_eq!(
+            round
+                .hule()
+                .iter()
+                .map(|hule| *hule.seat())
+                .collect::<std::vec::Vec<_>>(),
+            [seat(3)]
+        );
+    }
+
+    #[test]
+    fn rong_hule_takes_the_dapai_as_hulepai() {
+        let round = rong_over_peng_and_chi();
+
+        assert_eq!(round.hule()[0].hulepai(), M3);
+    }
+
+    #[test]
+    fn rong_hule_records_the_dapai_seat_as_source() {
+        let round = rong_over_peng_and_chi();
+
+        assert_eq!(round.hule()[0].rong_source(), Some(&seat(0)));
+    }
+
+    fn chi_by_seat1_over_pass() -> Round<FourPlayer, FuluCompleted> {
+        let round = respond_all(
+            call_window([SEAT0, SEAT1_CHI, SEAT2_PENG, SEAT3_NONE]),
+            &[
+                (
+                    1,
+                    CallResponse::Chi {
+                        bingpai_tiles: [M2, M4],
+                    },
+                ),
+                (2, CallResponse::Pass),
+            ],
+        );
+        unwrap_fulu(round.resolve().unwrap())
+    }
+
+    #[test]
+    fn chi_gives_the_turn_to_the_calling_seat() {
+        let round = chi_by_seat1_over_pass();
+
+        assert_eq!(round.actor(), &seat(1));
+    }
+
+    #[test]
+    fn chi_moves_called_tiles_out_of_bingpai() {
+        let round = chi_by_seat1_over_pass();
+        let counts = round.players()[1].bingpai().counts();
+
+        assert_eq!([counts[M2.index()], counts[M4.index()]], [0, 0]);
+    }
+
+    #[test]
+    fn chi_marks_the_called_sipai_in_the_source_he() {
+        let round = chi_by_seat1_over_pass();
+
+        assert_eq!(
+            round.players()[0]
+                .he()
+                .called_flags()
+                .collect::<std::vec::Vec<_>>(),
+            [true]
+        );
+    }
+
+    #[test]
+    fn chi_ends_the_first_turn_of_every_player() {
+        let round = chi_by_seat1_over_pass();
+
+        assert!(
+            round
+                .players()
+                .iter()
+                .all(|player| !player.first_turn_eligible())
+        );
+    }
+
+    #[test]
+    fn chi_requires_shouqie() {
+        let round = chi_by_seat1_over_pass();
+
+        assert_eq!(
+            round.dapai(Dapai::Moqie(Z6)).err(),
+            Some(DapaiError::MoqieUnavailableAfterFulu)
+        );
+    }
+
+    #[test]
+    fn fulu_dapai_keeps_the_turn_with_the_calling_seat() {
+        let round = peng_by_seat2().dapai(Dapai::Shouqie(P2)).unwrap();
+
+        assert_eq!(round.actor(), &seat(2));
+    }
+
+    #[test]
+    fn fulu_dapai_discards_the_shouqie_tile() {
+        let round = peng_by_seat2().dapai(Dapai::Shouqie(P2)).unwrap();
+
+        assert_eq!(round.dapai_tile_kind(), P2);
+    }
+
+    #[test]
+    fn fulu_dapai_removes_shouqie_tile_from_bingpai() {
+        let round = peng_by_seat2().dapai(Dapai::Shouqie(P2)).unwrap();
+
+        assert_eq!(round.players()[2].bingpai().counts()[P2.index()], 0);
+    }
+
+    fn daminggang_by_seat2() -> Round<FourPlayer, ZimoCompleted> {
+        let round = respond_all(
+            call_window([SEAT0, SEAT1_CHI, SEAT2_GANG, SEAT3_NONE]),
+            &[
+                (1, CallResponse::Pass),
+                (
+                    2,
+                    CallResponse::Daminggang {
+                        bingpai_tiles: [M3, M3, M3],
+                    },
+                ),
+            ],
+        );
+        let CallWindowResolution::Daminggang(round) = round.resolve().unwrap() else {
+            panic!("expected daminggang");
+        };
+        round
+    }
+
+    #[test]
+    fn daminggang_gives_the_turn_to_the_calling_seat() {
+        let round = daminggang_by_seat2();
+
+        assert_eq!(round.actor(), &seat(2));
+    }
+
+    #[test]
+    fn daminggang_draws_lingshang_tile() {
+        let round = daminggang_by_seat2();
+
+        assert_eq!(round.zimopai(), Z1);
+    }
+
+    #[test]
+    fn daminggang_moves_held_copies_out_of_bingpai() {
+        let round = daminggang_by_seat2();
+
+        assert_eq!(round.players()[2].bingpai().counts()[M3.index()], 0);
+    }
+
+    #[test]
+    fn daminggang_baopai_waits_for_next_dapai_under_after_dapai_timing() {
+        let round = daminggang_by_seat2();
+        let indicator_count_after_gang = round.baopai_indicators().len();
+
+        let round = round.dapai(Dapai::Moqie(Z1)).unwrap();
+
+        assert_eq!(
+            (indicator_count_after_gang, round.baopai_indicators().len()),
+            (1, 2)
+        );
+    }
+
+    fn zimo_pending(zimo: &[TileKind]) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(
+            [SEAT0, SEAT1_NONE, SEAT2_NONE, SEAT3_RONG],
+            zimo,
+            &[Z1],
+        );
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        )
+    }
+
+    fn moqie_window(
+        round: Round<FourPlayer, ZimoPending>,
+    ) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let round = round.zimo().unwrap();
+        let zimopai = round.zimopai();
+        round.dapai(Dapai::Moqie(zimopai)).unwrap().call_window()
+    }
+
+    fn pass_all(
+        mut round: Round<FourPlayer, CallWindow<FourPlayer>>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        for seat in round
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            round = round.respond(seat, CallResponse::Pass).unwrap();
+        }
+        let CallWindowResolution::NoCall(result) = round.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        result.into_next_zimo_pending().unwrap()
+    }
+
+    /// Seat 3 discards M3, a kind it waits on, and then sees M3 again.
+    fn own_sipai_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let mut round = zimo_pending(&[M5, M5, M5, M3, M3]);
+        for _ in 0..4 {
+            round = pass_all(moqie_window(round));
+        }
+        moqie_window(round)
+    }
+
+    #[test]
+    fn own_sipai_of_tingpai_kind_sets_sipai_zhenting() {
+        let window = own_sipai_window();
+
+        assert!(window.players()[3].sipai_zhenting());
+    }
+
+    #[test]
+    fn own_sipai_of_tingpai_kind_excludes_rong() {
+        let window = own_sipai_window();
+
+        assert_eq!(window.legal_responses(seat(3)), None);
+    }
+
+    /// Seat 0 discards M3, which seat 3 waits on.
+    fn rong_offered_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        moqie_window(zimo_pending(&[M3, M3, M5, M5, M3]))
+    }
+
+    /// Seat 1 discards M3 again after seat 3 passed rong on seat 0's M3.
+    fn passed_rong_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        moqie_window(pass_all(rong_offered_window()))
+    }
+
+    /// Seat 0 discards M3 once more after seat 3's own next zimo.
+    fn after_own_next_zimo_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let mut round = pass_all(passed_rong_window());
+        for _ in 0..2 {
+            round = pass_all(moqie_window(round));
+        }
+        moqie_window(round)
+    }
+
+    #[test]
+    fn dapai_of_the_wait_offers_rong() {
+        let window = rong_offered_window();
+
+        assert_eq!(
+            window.legal_responses(seat(3)).unwrap(),
+            [CallResponse::Pass, CallResponse::Rong]
+        );
+    }
+
+    #[test]
+    fn passed_rong_sets_temporary_zhenting() {
+        let window = passed_rong_window();
+
+        assert!(window.players()[3].temporary_zhenting());
+    }
+
+    #[test]
+    fn passed_rong_excludes_rong_until_own_next_zimo() {
+        let window = passed_rong_window();
+
+        assert_eq!(window.legal_responses(seat(3)), None);
+    }
+
+    #[test]
+    fn own_next_zimo_clears_temporary_zhenting() {
+        let window = after_own_next_zimo_window();
+
+        assert!(!window.players()[3].is_zhenting());
+    }
+
+    #[test]
+    fn own_next_zimo_offers_rong_again() {
+        let window = after_own_next_zimo_window();
+
+        assert_eq!(
+            window.legal_responses(seat(3)).unwrap(),
+            [CallResponse::Pass, CallResponse::Rong]
+        );
+    }
+
+    /// Seat 1 discards M3, which seat 3 waits on after its lizhi.
+    fn lizhi_rong_offered_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let mut round = zimo_pending(&[M5, M5, M5, Z7, M3, M4, M4, M4, M3]);
+        for _ in 0..3 {
+            round = pass_all(moqie_window(round));
+        }
+        let round = round
+            .zimo()
+            .unwrap()
+            .lizhi(Dapai::Moqie(Z7))
+            .unwrap()
+            .call_window();
+        moqie_window(pass_all(round))
+    }
+
+    /// Seat 1 discards M3 again after seat 3 passed rong and drew its own next zimo.
+    fn passed_rong_after_lizhi_window() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let mut round = pass_all(lizhi_rong_offered_window());
+        for _ in 0..3 {
+            round = pass_all(moqie_window(round));
+        }
+        moqie_window(round)
+    }
+
+    #[test]
+    fn dapai_of_the_wait_offers_rong_after_lizhi() {
+        let window = lizhi_rong_offered_window();
+
+        assert_eq!(
+            window.legal_responses(seat(3)).unwrap(),
+            [CallResponse::Pass, CallResponse::Rong]
+        );
+    }
+
+    #[test]
+    fn passed_rong_after_lizhi_keeps_zhenting_after_own_zimo() {
+        let window = passed_rong_after_lizhi_window();
+
+        assert!(window.players()[3].lizhi_zhenting());
+    }
+
+    #[test]
+    fn passed_rong_after_lizhi_is_not_temporary_zhenting() {
+        let window = passed_rong_after_lizhi_window();
+
+        assert!(!window.players()[3].temporary_zhenting());
+    }
+
+    #[test]
+    fn passed_rong_after_lizhi_excludes_rong_after_own_zimo() {
+        let window = passed_rong_after_lizhi_window();
+
+        assert_eq!(window.legal_responses(seat(3)), None);
+    }
+
+    const SEAT1_CHI_HONG: [TileKind; 13] = [M2, M3, M5, M0, M6, S3, S4, S5, S6, S7, S8, Z5, Z5];
+    const SEAT1_SHITI: [TileKind; 13] = [M1, M4, M5, M6, M7, S3, S4, S5, S6, S7, S8, Z5, Z5];
+
+    fn shiti_window(
+        seat1: [TileKind; 13],
+        dapai: TileKind,
+        shiti: Shiti,
+    ) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(
+            [SEAT0, seat1, SEAT2_NONE, SEAT3_NONE],
+            &[dapai],
+            &[Z1],
+        );
+        let policy = RoundPolicy {
+            shiti,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        );
+        moqie_window(round)
+    }
+
+    fn chi_by_seat1(
+        round: Round<FourPlayer, CallWindow<FourPlayer>>,
+        bingpai_tiles: [TileKind; 2],
+    ) -> Round<FourPlayer, FuluCompleted> {
+        let round = respond_all(round, &[(1, CallResponse::Chi { bingpai_tiles })]);
+        unwrap_fulu(round.resolve().unwrap())
+    }
+
+    #[test]
+    fn chi_lists_each_decomposition_with_hong_baopai_spelling() {
+        let round = shiti_window(SEAT1_CHI_HONG, M4, Shiti::Allowed);
+
+        assert_eq!(
+            round.legal_responses(seat(1)).unwrap(),
+            [
+                CallResponse::Pass,
+                CallResponse::Chi {
+                    bingpai_tiles: [M2, M3]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M3, M5]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M3, M0]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M5, M6]
+                },
+                CallResponse::Chi {
+                    bingpai_tiles: [M0, M6]
+                },
+            ]
+        );
+    }
+
+    fn hong_baopai_chi() -> Round<FourPlayer, FuluCompleted> {
+        chi_by_seat1(shiti_window(SEAT1_CHI_HONG, M4, Shiti::Allowed), [M0, M6])
+    }
+
+    #[test]
+    fn chi_with_hong_baopai_moves_that_tile_into_fulu() {
+        let round = hong_baopai_chi();
+
+        assert_eq!(round.players()[1].fulu()[0].bingpai_tiles(), [M0, M6]);
+    }
+
+    #[test]
+    fn chi_with_hong_baopai_takes_it_out_of_bingpai() {
+        let round = hong_baopai_chi();
+
+        assert_eq!(round.players()[1].bingpai().counts()[M0.index()], 0);
+    }
+
+    #[test]
+    fn chi_with_hong_baopai_keeps_the_plain_five_in_bingpai() {
+        let round = hong_baopai_chi();
+
+        assert_eq!(round.players()[1].bingpai().counts()[M5.index()], 1);
+    }
+
+    /// Seat 1 chis M4 with M5 and M6, so M7 is its suji.
+    fn suji_chi(shiti: Shiti) -> Round<FourPlayer, FuluCompleted> {
+        chi_by_seat1(shiti_window(SEAT1_SHITI, M4, shiti), [M5, M6])
+    }
+
+    #[test]
+    fn shiti_forbids_called_kind_after_chi() {
+        let round = suji_chi(Shiti::ForbidSameKindAndSuji);
+
+        assert_eq!(
+            round.dapai(Dapai::Shouqie(M4)).err(),
+            Some(DapaiError::ShitiForbidden { tile_kind: M4 })
+        );
+    }
+
+    #[test]
+    fn shiti_forbids_suji_after_chi() {
+        let round = suji_chi(Shiti::ForbidSameKindAndSuji);
+
+        assert_eq!(
+            round.dapai(Dapai::Shouqie(M7)).err(),
+            Some(DapaiError::ShitiForbidden { tile_kind: M7 })
+        );
+    }
+
+    #[test]
+    fn shiti_allows_other_tiles_after_chi() {
+        let round = suji_chi(Shiti::ForbidSameKindAndSuji);
+
+        assert!(round.dapai(Dapai::Shouqie(M1)).is_ok());
+    }
+
+    #[test]
+    fn middle_chi_has_no_suji() {
+        let round = chi_by_seat1(
+            shiti_window(SEAT1_SHITI, M5, Shiti::ForbidSameKindAndSuji),
+            [M4, M6],
+        );
+
+        assert!(round.dapai(Dapai::Shouqie(M7)).is_ok());
+    }
+
+    #[test]
+    fn same_kind_shiti_allows_suji_after_chi() {
+        let round = suji_chi(Shiti::ForbidSameKind);
+
+        assert!(round.dapai(Dapai::Shouqie(M7)).is_ok());
+    }
+
+    #[test]
+    fn same_kind_shiti_forbids_called_kind_after_chi() {
+        let round = suji_chi(Shiti::ForbidSameKind);
+
+        assert_eq!(
+            round.dapai(Dapai::Shouqie(M4)).err(),
+            Some(DapaiError::ShitiForbidden { tile_kind: M4 })
+        );
+    }
+
+    #[test]
+    fn allowed_shiti_accepts_called_kind_after_chi() {
+        let round = suji_chi(Shiti::Allowed);
+
+        assert!(round.dapai(Dapai::Shouqie(M4)).is_ok());
+    }
+
+    #[test]
+    fn shiti_after_peng_forbids_hong_baopai_of_called_kind() {
+        let seat1 = [M5, M5, M0, M1, M2, S3, S4, S5, S6, S7, S8, Z5, Z5];
+        let round = respond_all(
+            shiti_window(seat1, M5, Shiti::ForbidSameKind),
+            &[(
+                1,
+                CallResponse::Peng {
+                    bingpai_tiles: [M5, M5],
+                },
+            )],
+        );
+        let round = unwrap_fulu(round.resolve().unwrap());
+
+        assert_eq!(
+            round.dapai(Dapai::Shouqie(M0)).err(),
+            Some(DapaiError::ShitiForbidden { tile_kind: M0 })
+        );
+    }
+
+    /// `M2 M3 M4 M1`, which chis M4 with M2 and M3.
+    fn chi_bingpai() -> Bingpai {
+        [M2, M3, M4, M1]
+            .into_iter()
+            .try_fold(
+                Bingpai::empty(TileSet::red_three_four_player()),
+                Bingpai::with_added,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn chi_shiti_forbids_called_kind_and_suji() {
+        assert_eq!(
+            chi_shiti_forbidden(Shiti::ForbidSameKindAndSuji, [M2, M3], M4),
+            [M4, M1]
+        );
+    }
+
+    #[test]
+    fn call_leaving_only_forbidden_tiles_is_not_offered() {
+        let forbidden = chi_shiti_forbidden(Shiti::ForbidSameKindAndSuji, [M2, M3], M4);
+
+        assert!(!dapai_left_after_call(
+            &chi_bingpai(),
+            &[M2, M3],
+            &forbidden
+        ));
+    }
+
+    #[test]
+    fn call_leaving_a_tile_allowed_by_the_policy_is_offered() {
+        let forbidden = chi_shiti_forbidden(Shiti::ForbidSameKind, [M2, M3], M4);
+
+        assert!(dapai_left_after_call(&chi_bingpai(), &[M2, M3], &forbidden));
+    }
+
+    /// Seats 1, 2 and 3 all wait on the M3 that seat 0 discards.
+    fn rong_window(policy: RoundPolicy<FourPlayer>) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let seat0 = [M3, M7, M8, M9, P1, P2, P3, S1, S2, S3, Z7, Z7, Z7];
+        let seat1 = [M1, M2, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+        let seat2 = [M4, M5, S1, S2, S3, S4, S5, S6, S7, S8, S9, Z2, Z2];
+        let seat3 = [M3, M3, Z3, Z3, Z3, Z4, Z4, Z4, Z5, Z5, Z5, Z6, Z6];
+        let bipai =
+            Bipai::<FourPlayer>::red_three_arranged([seat0, seat1, seat2, seat3], &[Z1], &[]);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Shouqie(M3))
+        .unwrap()
+        .call_window()
+    }
+
+    fn rong_winners(
+        policy: RoundPolicy<FourPlayer>,
+        responses: &[(usize, CallResponse)],
+    ) -> std::vec::Vec<(Seat<FourPlayer>, bool)> {
+        let CallWindowResolution::Hule(round) = respond_all(rong_window(policy), responses)
+            .resolve()
+            .unwrap()
+        else {
+            panic!("expected hule");
+        };
+        round
+            .hule()
+            .iter()
+            .map(|hule| {
+                assert!(hule.takes_ben());
+                (*hule.seat(), hule.takes_lizhibang())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn multiple_rong_lets_every_rong_seat_win_independent_of_response_order() {
+        let rong = CallResponse::Rong;
+        let pass = CallResponse::Pass;
+
+        for responses in [
+            [(1, rong), (2, pass), (3, rong)],
+            [(3, rong), (2, pass), (1, rong)],
+        ] {
+            assert_eq!(
+                rong_winners(RoundPolicy::mahjong_soul(), &responses),
+                [(seat(1), true), (seat(3), false)]
+            );
+        }
+    }
+
+    #[test]
+    fn multiple_rong_without_sanjia_hele_lets_three_seats_win() {
+        let policy = RoundPolicy {
+            sanjia_hele: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let rong = CallResponse::Rong;
+
+        assert_eq!(
+            rong_winners(policy, &[(3, rong), (1, rong), (2, rong)]),
+            [(seat(1), true), (seat(2), false), (seat(3), false)]
+        );
+    }
+
+    #[test]
+    fn first_in_seat_order_lets_only_the_nearest_rong_seat_win() {
+        let policy = RoundPolicy {
+            rong_resolution: RongResolution::FirstInSeatOrder,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let rong = CallResponse::Rong;
+
+        assert_eq!(
+            rong_winners(policy, &[(3, rong), (2, rong), (1, CallResponse::Pass)]),
+            [(seat(2), true)]
+        );
+    }
+}
@@ -0,0 +1,573 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::action::GangError;
+use crate::bipai::BipaiSpec;
+use crate::fulu::{Fulu, FuluKind};
+use crate::player::Player;
+use crate::player_set::PlayerSet;
+use crate::seat::{Seat, SeatArray};
+use crate::tile::TileKind;
+
+use super::call_window::{CallSlots, guoshi_rong_responses, rong_responses};
+use super::hule::HuleScorer;
+use super::qianggang_window::QianggangWindow;
+use super::{Round, ZimoCompleted, declare_gang_baopai, held_tiles, try_update_player};
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Declares angang on every held copy of `tile_kind`'s base tile, including the zimopai.
+    ///
+    /// The lingshang zimo waits for the qianggang window, which only a guoshi wait may answer
+    /// when the policy allows it.
+    pub fn angang(self, tile_kind: TileKind) -> Result<Round<P, QianggangWindow<P>>, GangError> {
+        if !self.bipai.lingshang_zimo_available() {
+            return Err(GangError::LingshangZimoUnavailable);
+        }
+        let zimopai = self.state.zimopai;
+        let players = try_update_player(self.players, self.actor, |player| {
+            with_angang(player, zimopai, tile_kind)
+        })?;
+        // A gang interrupts the first turn and yifa for every player.
+        let players = players.map(|player| player.clear_first_turn_eligibility().clear_yifa());
+        let (bipai, gang_baopai_pending_count) = declare_gang_baopai(
+            self.bipai,
+            &self.policy,
+            self.gang_baopai_pending_count,
+            self.policy.angang_baopai_timing,
+        )?;
+
+        let gang_tile = tile_kind.base_tile();
+        let mut slots = CallSlots::new();
+        if self.policy.guoshi_angang_qianggang {
+            let scorer = HuleScorer {
+                bipai: &bipai,
+                players: &players,
+                zhuangjia: self.zhuangjia,
+                policy: &self.policy,
+                table_match_state: &self.table_match_state,
+            };
+            for seat in other_seats(self.actor) {
+                slots = slots.with_seat(
+                    seat,
+                    guoshi_rong_responses(&scorer, seat, gang_tile, self.actor),
+                );
+            }
+        }
+
+        Ok(Round {
+            bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count,
+            state: QianggangWindow {
+                gang_kind: FuluKind::Angang,
+                gang_tile,
+                slots,
+            },
+        })
+    }
+
+    /// Adds `tile_kind` from bingpai or the zimopai to the peng of the same base tile.
+    pub fn jiagang(self, tile_kind: TileKind) -> Result<Round<P, QianggangWindow<P>>, GangError> {
+        if !self.bipai.lingshang_zimo_available() {
+            return Err(GangError::LingshangZimoUnavailable);
+        }
+        let zimopai = self.state.zimopai;
+        let players = try_update_player(self.players, self.actor, |player| {
+            Ok::<_, GangError>(player.with_zimopai(zimopai)?.with_jiagang(tile_kind)?)
+        })?;
+        let players = players.map(|player| player.clear_first_turn_eligibility().clear_yifa());
+        let (bipai, gang_baopai_pending_count) = declare_gang_baopai(
+            self.bipai,
+            &self.policy,
+            self.gang_baopai_pending_count,
+            self.policy.minggang_baopai_timing,
+        )?;
+
+        let scorer = HuleScorer {
+            bipai: &bipai,
+            players: &players,
+            zhuangjia: self.zhuangjia,
+            policy: &self.policy,
+            table_match_state: &self.table_match_state,
+        };
+        let mut slots = CallSlots::new();
+        for seat in other_seats(self.actor) {
+            slots = slots.with_seat(
+                seat,
+                rong_responses(&scorer, seat, tile_kind, self.actor, true),
+            );
+        }
+
+        Ok(Round {
+            bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count,
+            state: QianggangWindow {
+                gang_kind: FuluKind::Jiagang,
+                gang_tile: tile_kind,
+                slots,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Declares angang and passes its qianggang window, for fixtures without a guoshi wait.
+    pub(crate) fn angang_and_lingshang_zimo(self, tile_kind: TileKind) -> Self {
+        self.angang(tile_kind)
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap()
+    }
+}
+
+/// The player after angang on `tile_kind` with `zimopai` joined to bingpai.
+pub(super) fn with_angang<P>(
+    player: Player<P>,
+    zimopai: TileKind,
+    tile_kind: TileKind,
+) -> Result<Player<P>, GangError> {
+    let lizhi_waits = player.lizhi().is_declared().then(|| player.tingpai_kinds());
+    let player = player.with_zimopai(zimopai)?;
+    let bingpai_tiles = held_tiles(player.bingpai(), tile_kind.base_tile())
+        .ok_or(GangError::AngangTilesMissing { tile_kind })?;
+    let player = player.with_fulu(Fulu::angang(bingpai_tiles))?;
+    // After lizhi only the zimopai may complete the angang, and the waits must not change.
+    if let Some(waits) = lizhi_waits
+        && (zimopai.base_tile() != tile_kind.base_tile() || player.tingpai_kinds() != waits)
+    {
+        return Err(GangError::AngangUnavailableAfterLizhi { tile_kind });
+    }
+    Ok(player)
+}
+
+/// The seats other than `actor`, in seat order from the next seat.
+fn other_seats<P: PlayerSet>(actor: Seat<P>) -> impl Iterator<Item = Seat<P>> {
+    (1..P::PLAYER_COUNT).map(move |offset| actor.after(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{CallResponse, Dapai};
+    use crate::bipai::Bipai;
+    use crate::fulu::{FuluError, FuluKind};
+    use crate::player_set::FourPlayer;
+    use crate::round::{
+        CallWindowResolution, FirstZimoOrigin, GangBaopaiTiming, QianggangResolution, RoundEnded,
+        RoundOutcome, RoundPolicy, ZimoPending,
+    };
+    use crate::score::Score;
+    use crate::table_match::TableMatchState;
+
+    use super::*;
+
+    use TileKind::*;
+
+    const SEAT0_ANGANG: [TileKind; 13] = [Z1, Z1, Z1, Z1, Z2, Z2, Z2, Z3, Z3, Z3, S1, S1, S1];
+    const SEAT0: [TileKind; 13] = [Z2, Z2, Z2, Z3, Z3, Z3, Z4, Z4, Z4, S1, S1, S1, S2];
+    const SEAT1: [TileKind; 13] = [M7, M7, M8, M8, S3, S4, S5, S6, S7, S8, Z5, Z5, Z6];
+    const SEAT2_PENG: [TileKind; 13] = [M3, M3, S9, S9, S9, Z6, Z6, Z7, Z7, Z7, P1, P1, P2];
+    const SEAT3_RONG: [TileKind; 13] = [M1, M2, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+    const SEAT3_NONE: [TileKind; 13] = [M9, M9, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, &[P9, S9]);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        )
+    }
+
+    fn angang_ready(
+        zimopai: TileKind,
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoCompleted> {
+        let seat3 = [M9, M9, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z5, Z5];
+        zimo_pending([SEAT0_ANGANG, SEAT1, SEAT2_PENG, seat3], &[zimopai], policy)
+            .zimo()
+            .unwrap()
+    }
+
+    /// Seat 2 pengs the first M3 and later draws the fourth copy.
+    fn jiagang_ready(seat3: [TileKind; 13]) -> Round<FourPlayer, ZimoCompleted> {
+        let round = zimo_pending(
+            [SEAT0, SEAT1, SEAT2_PENG, seat3],
+            &[M3, S2, S2, S2, M3],
+            RoundPolicy::mahjong_soul(),
+        );
+        let mut window = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(M3))
+            .unwrap()
+            .call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = if seat == Seat::<FourPlayer>::ALL[2] {
+                CallResponse::Peng {
+                    bingpai_tiles: [M3, M3],
+                }
+            } else {
+                CallResponse::Pass
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        let CallWindowResolution::Fulu(round) = window.resolve().unwrap() else {
+            panic!("expected peng");
+        };
+
+        let mut round = round.dapai(Dapai::Shouqie(P2)).unwrap();
+        for _ in 0..3 {
+            let zimo = round.no_reaction().into_next_zimo_pending().unwrap().zimo();
+            let zimo = zimo.unwrap();
+            let zimopai = zimo.zimopai();
+            round = zimo.dapai(Dapai::Moqie(zimopai)).unwrap();
+        }
+        round
+            .no_reaction()
+            .into_next_zimo_pending()
+            .unwrap()
+            .zimo()
+            .unwrap()
+    }
+
+    #[test]
+    fn angang_moves_four_copies_into_closed_fulu_and_draws_lingshang_tile() {
+        let round = angang_ready(M1, RoundPolicy::mahjong_soul()).angang_and_lingshang_zimo(Z1);
+        let player = &round.players()[0];
+
+        assert_eq!(
+            (
+                player.fulu()[0].kind(),
+                player.fulu()[0].tiles().collect::<std::vec::Vec<_>>(),
+                player.bingpai().counts()[Z1.index()],
+                player.bingpai().counts()[M1.index()],
+                round.zimopai(),
+            ),
+            (FuluKind::Angang, vec![Z1, Z1, Z1, Z1], 0, 1, P9)
+        );
+    }
+
+    #[test]
+    fn angang_can_use_zimopai_as_fourth_copy() {
+        let seat0 = [Z1, Z1, Z1, M1, Z2, Z2, Z2, Z3, Z3, Z3, S1, S1, S1];
+        let seat3 = [M9, M9, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z5, Z5];
+        let round = zimo_pending(
+            [seat0, SEAT1, SEAT2_PENG, seat3],
+            &[Z1],
+            RoundPolicy::mahjong_soul(),
+        )
+        .zimo()
+        .unwrap()
+        .angang_and_lingshang_zimo(Z1);
+
+        assert_eq!(round.players()[0].bingpai().counts()[Z1.index()], 0);
+    }
+
+    #[test]
+    fn angang_rejects_fewer_than_four_copies() {
+        let round = angang_ready(M1, RoundPolicy::mahjong_soul());
+
+        assert_eq!(
+            round.angang(Z2).err(),
+            Some(GangError::AngangTilesMissing { tile_kind: Z2 })
+        );
+    }
+
+    #[test]
+    fn angang_clears_first_turn_eligibility_for_every_player() {
+        let round = angang_ready(M1, RoundPolicy::mahjong_soul()).angang_and_lingshang_zimo(Z1);
+
+        assert!(
+            round
+                .players()
+                .iter()
+                .all(|player| !player.first_turn_eligible())
+        );
+    }
+
+    #[test]
+    fn immediate_angang_baopai_is_revealed_before_dapai() {
+        let round = angang_ready(M1, RoundPolicy::mahjong_soul()).angang_and_lingshang_zimo(Z1);
+
+        assert_eq!(round.baopai_indicators().len(), 2);
+    }
+
+    #[test]
+    fn after_dapai_angang_baopai_is_revealed_by_next_dapai() {
+        let policy = RoundPolicy {
+            angang_baopai_timing: GangBaopaiTiming::AfterDapai,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = angang_ready(M1, policy).angang_and_lingshang_zimo(Z1);
+        let indicator_count_after_gang = round.baopai_indicators().len();
+
+        let round = round.dapai(Dapai::Moqie(P9)).unwrap();
+
+        assert_eq!(
+            (indicator_count_after_gang, round.baopai_indicators().len()),
+            (1, 2)
+        );
+    }
+
+    #[test]
+    fn disabled_baopai_reveals_no_gang_indicator() {
+        let policy = RoundPolicy {
+            baopai: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = angang_ready(M1, policy)
+            .angang_and_lingshang_zimo(Z1)
+            .dapai(Dapai::Moqie(P9))
+            .unwrap();
+
+        assert_eq!(round.baopai_indicators().len(), 0);
+    }
+
+    #[test]
+    fn jiagang_rejects_tile_without_matching_peng() {
+        let round = angang_ready(M1, RoundPolicy::mahjong_soul());
+
+        assert_eq!(
+            round.jiagang(M1).err(),
+            Some(GangError::Fulu(FuluError::JiagangTargetMissing {
+                tile_kind: M1
+            }))
+        );
+    }
+
+    #[test]
+    fn jiagang_opens_qianggang_window_for_seats_that_can_rong() {
+        let round = jiagang_ready(SEAT3_RONG).jiagang(M3).unwrap();
+
+        assert_eq!(
+            (
+                round.gang_tile_kind(),
+                round
+                    .eligible_seats()
+                    .copied()
+                    .collect::<std::vec::Vec<_>>(),
+                round.players()[2].fulu()[0].kind(),
+            ),
+            (M3, vec![seat(3)], FuluKind::Jiagang)
+        );
+    }
+
+    fn passed_qianggang_window() -> Round<FourPlayer, ZimoCompleted> {
+        let round = jiagang_ready(SEAT3_RONG)
+            .jiagang(M3)
+            .unwrap()
+            .respond(seat(3), CallResponse::Pass)
+            .unwrap();
+        let QianggangResolution::LingshangZimo(round) = round.resolve().unwrap() else {
+            panic!("expected lingshang zimo");
+        };
+        round
+    }
+
+    #[test]
+    fn passed_qianggang_window_draws_lingshang_tile() {
+        let round = passed_qianggang_window();
+
+        assert_eq!((round.actor(), round.zimopai()), (&seat(2), P9));
+    }
+
+    #[test]
+    fn passed_qianggang_sets_temporary_zhenting() {
+        let round = passed_qianggang_window();
+
+        assert!(round.players()[3].temporary_zhenting());
+    }
+
+    #[test]
+    fn qianggang_window_without_eligible_seats_draws_lingshang_tile() {
+        let round = jiagang_ready(SEAT3_NONE).jiagang(M3).unwrap();
+
+        assert!(matches!(
+            round.resolve().unwrap(),
+            QianggangResolution::LingshangZimo(_)
+        ));
+    }
+
+    fn qianggang_rong() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = jiagang_ready(SEAT3_RONG)
+            .jiagang(M3)
+            .unwrap()
+            .respond(seat(3), CallResponse::Rong)
+            .unwrap();
+        let QianggangResolution::Hule(round) = round.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+        round
+    }
+
+    #[test]
+    fn qianggang_rong_ends_round_on_gang_tile() {
+        let round = qianggang_rong();
+        let [hule] = round.hule() else {
+            panic!("expected one hule");
+        };
+
+        assert_eq!(
+            (
+                round.round_outcome(),
+                hule.seat(),
+                hule.hulepai(),
+                hule.rong_source()
+            ),
+            (RoundOutcome::Hule, &seat(3), M3, Some(&seat(2)))
+        );
+    }
+
+    #[test]
+    fn qianggang_rong_is_flagged_as_qianggang() {
+        let round = qianggang_rong();
+
+        assert!(round.hule()[0].qianggang());
+    }
+
+    #[test]
+    fn minggang_after_dapai_timing_holds_jiagang_baopai_until_dapai() {
+        let round = jiagang_ready(SEAT3_NONE).jiagang(M3).unwrap();
+        let indicator_count_after_gang = round.baopai_indicators().len();
+        let QianggangResolution::LingshangZimo(round) = round.resolve().unwrap() else {
+            panic!("expected lingshang zimo");
+        };
+
+        let round = round.dapai(Dapai::Moqie(P9)).unwrap();
+
+        assert_eq!(
+            (indicator_count_after_gang, round.baopai_indicators().len()),
+            (1, 2)
+        );
+    }
+
+    /// Seat 3 waits on Z1 with guoshi while seat 0 holds all four copies.
+    const SEAT3_GUOSHI: [TileKind; 13] = [M1, M9, P1, P9, S1, S9, Z2, Z3, Z4, Z4, Z5, Z6, Z7];
+
+    fn guoshi_angang_window(
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, QianggangWindow<FourPlayer>> {
+        let seat2 = [M3, M3, S8, S8, S8, Z6, Z6, Z7, Z7, Z7, P1, P1, P2];
+        zimo_pending([SEAT0_ANGANG, SEAT1, seat2, SEAT3_GUOSHI], &[M2], policy)
+            .zimo()
+            .unwrap()
+            .angang(Z1)
+            .unwrap()
+    }
+
+    #[test]
+    fn angang_opens_qianggang_window_for_guoshi_wait() {
+        let round = guoshi_angang_window(RoundPolicy::mahjong_soul());
+
+        assert_eq!(
+            (
+                round.gang_kind(),
+                round.gang_tile_kind(),
+                round
+                    .eligible_seats()
+                    .copied()
+                    .collect::<std::vec::Vec<_>>(),
+            ),
+            (FuluKind::Angang, Z1, vec![seat(3)])
+        );
+    }
+
+    fn guoshi_angang_qianggang_disabled() -> RoundPolicy<FourPlayer> {
+        RoundPolicy {
+            guoshi_angang_qianggang: false,
+            ..RoundPolicy::mahjong_soul()
+        }
+    }
+
+    #[test]
+    fn angang_qianggang_window_stays_closed_when_policy_disables_it() {
+        let round = guoshi_angang_window(guoshi_angang_qianggang_disabled());
+
+        assert_eq!(round.eligible_seats().count(), 0);
+    }
+
+    #[test]
+    fn closed_angang_qianggang_window_draws_lingshang_tile() {
+        let round = guoshi_angang_window(guoshi_angang_qianggang_disabled());
+
+        assert!(matches!(
+            round.resolve().unwrap(),
+            QianggangResolution::LingshangZimo(_)
+        ));
+    }
+
+    #[test]
+    fn guoshi_rong_on_angang_ends_round_as_qianggang() {
+        let round = guoshi_angang_window(RoundPolicy::mahjong_soul())
+            .respond(seat(3), CallResponse::Rong)
+            .unwrap();
+
+        let QianggangResolution::Hule(round) = round.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+        let [hule] = round.hule() else {
+            panic!("expected one hule");
+        };
+        assert_eq!(
+            (
+                hule.seat(),
+                hule.hulepai(),
+                hule.rong_source(),
+                hule.qianggang()
+            ),
+            (&seat(3), Z1, Some(&seat(0)), true)
+        );
+    }
+
+    fn passed_guoshi_angang_window() -> Round<FourPlayer, ZimoCompleted> {
+        guoshi_angang_window(RoundPolicy::mahjong_soul())
+            .respond(seat(3), CallResponse::Pass)
+            .unwrap()
+            .resolve()
+            .unwrap()
+            .into_lingshang_zimo()
+            .unwrap()
+    }
+
+    #[test]
+    fn passed_guoshi_angang_window_draws_lingshang_tile() {
+        let round = passed_guoshi_angang_window();
+
+        assert_eq!((round.actor(), round.zimopai()), (&seat(0), P9));
+    }
+
+    #[test]
+    fn passed_guoshi_angang_window_sets_temporary_zhenting() {
+        let round = passed_guoshi_angang_window();
+
+        assert!(round.players()[3].temporary_zhenting());
+    }
+}
@@ -0,0 +1,822 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::HuleError;
+use crate::bipai::{Bipai, BipaiSpec, QipaiCompleted};
+use crate::hule::{HuleHand, InHouseHule};
+use crate::player_set::PlayerSet;
+use crate::score::{BaopaiFan, HuleScore};
+use crate::seat::Seat;
+use crate::table_match::TableMatchState;
+use crate::tile::TileKind;
+use crate::yaku::{HuleSituation, YakuEvaluation, YakuEvaluations};
+
+use super::{HuleContext, Round, RoundEnded, RoundOutcome, RoundPolicy, ZimoCompleted};
+
+/// The parts of a round that score a hule, whatever state the round is in.
+///
+/// A window that updates the players before the round is rebuilt scores against its own
+/// `players`.
+pub(super) struct HuleScorer<'a, P: PlayerSet + BipaiSpec> {
+    pub(super) bipai: &'a Bipai<P, QipaiCompleted>,
+    pub(super) players: &'a P::Players,
+    pub(super) zhuangjia: Seat<P>,
+    pub(super) policy: &'a RoundPolicy<P>,
+    pub(super) table_match_state: &'a TableMatchState<P>,
+}
+
+impl<P: PlayerSet + BipaiSpec, S> Round<P, S> {
+    pub(super) fn hule_scorer(&self) -> HuleScorer<'_, P> {
+        HuleScorer {
+            bipai: &self.bipai,
+            players: &self.players,
+            zhuangjia: self.zhuangjia,
+            policy: &self.policy,
+            table_match_state: &self.table_match_state,
+        }
+    }
+}
+
+impl<P: PlayerSet> HuleContext<P> {
+    /// A zimo of `hulepai` by `seat` outside any special situation, which tells whether a wait
+    /// has yaku.
+    pub(super) fn tingpai(seat: Seat<P>, hulepai: TileKind) -> Self {
+        Self {
+            seat,
+            hulepai,
+            rong_source: None,
+            qianggang: false,
+            lingshang: false,
+            haidi: false,
+            first_turn: false,
+            bao: None,
+            takes_lizhibang: false,
+            takes_ben: false,
+        }
+    }
+
+    /// The rong of `hulepai` by `seat`, discarded or robbed from `rong_source`.
+    pub(super) fn rong(
+        players: &P::Players,
+        seat: Seat<P>,
+        hulepai: TileKind,
+        rong_source: Seat<P>,
+        qianggang: bool,
+        haidi: bool,
+        takes_lizhibang: bool,
+    ) -> Self {
+        let player = &players[seat.index()];
+        Self {
+            seat,
+            hulepai,
+            rong_source: Some(rong_source),
+            qianggang,
+            lingshang: false,
+            haidi,
+            first_turn: player.first_turn_eligible(),
+            bao: player.bao().copied(),
+            takes_lizhibang,
+            takes_ben: true,
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> HuleScorer<'_, P> {
+    /// Scores each reading of the winner's hand under the policy's yaku.
+    pub(super) fn yaku(&self, hule: &HuleContext<P>) -> YakuEvaluations {
+        let player = &self.players[hule.seat.index()];
+        self.policy.yaku.evaluate(
+            &InHouseHule,
+            &HuleHand::new(player.bingpai(), player.fulu(), hule.hulepai),
+            &self.hule_situation(hule),
+        )
+    }
+
+    /// The reading with the highest base points among those with yaku, earlier readings first on
+    /// a tie; `None` when no reading has yaku.
+    pub(super) fn hule_score(&self, hule: &HuleContext<P>) -> Option<HuleScore> {
+        let situation = self.hule_situation(hule);
+        let baopai = self.baopai_fan(hule);
+        self.yaku(hule)
+            .into_iter()
+            .filter(YakuEvaluation::has_yaku)
+            .map(|yaku| {
+                self.policy
+                    .score
+                    .hule_score(yaku, baopai, hule.hulepai, &situation)
+            })
+            .fold(None, |best: Option<HuleScore>, score| match best {
+                Some(best) if best.base_points().value() >= score.base_points().value() => {
+                    Some(best)
+                }
+                _ => Some(score),
+            })
+    }
+
+    /// Whether `seat` may rong `hulepai` from `rong_source`: it is not zhenting and a reading
+    /// of its hand has yaku.
+    pub(super) fn rong_available(
+        &self,
+        seat: Seat<P>,
+        hulepai: TileKind,
+        rong_source: Seat<P>,
+        qianggang: bool,
+        haidi: bool,
+    ) -> bool {
+        !self.players[seat.index()].is_zhenting()
+            && self
+                .hule_score(&HuleContext::rong(
+                    self.players,
+                    seat,
+                    hulepai,
+                    rong_source,
+                    qianggang,
+                    haidi,
+                    true,
+                ))
+                .is_some()
+    }
+
+    /// The baopai in the winner's bingpai, fulu, hulepai and babei. The bei set aside by babei
+    /// also match a baopai or li baopai of bei.
+    fn baopai_fan(&self, hule: &HuleContext<P>) -> BaopaiFan {
+        let player = &self.players[hule.seat.index()];
+        let babei_count = player.babei_count();
+        let mut counts = *player.bingpai().counts();
+        counts[hule.hulepai.index()] += 1;
+        for tile_kind in player.fulu().iter().flat_map(|fulu| fulu.tiles()) {
+            counts[tile_kind.index()] += 1;
+        }
+        let matching = |baopai: TileKind| {
+            let hong_count = TileKind::ALL
+                .into_iter()
+                .filter(|tile_kind| tile_kind.is_hong_baopai() && tile_kind.base_tile() == baopai)
+                .map(|tile_kind| counts[tile_kind.index()])
+                .sum::<u8>();
+            let babei = if baopai == TileKind::Z4 {
+                babei_count
+            } else {
+                0
+            };
+            counts[baopai.index()] + hong_count + babei
+        };
+        let li_baopai = if player.lizhi().is_established() {
+            self.bipai.li_baopai().map(matching).sum()
+        } else {
+            0
+        };
+        BaopaiFan {
+            baopai: self.bipai.baopai().map(matching).sum(),
+            li_baopai,
+            hong_baopai: TileKind::ALL
+                .into_iter()
+                .filter(|tile_kind| tile_kind.is_hong_baopai())
+                .map(|tile_kind| counts[tile_kind.index()])
+                .sum(),
+            babei_baopai: babei_count,
+        }
+    }
+
+    fn hule_situation(&self, hule: &HuleContext<P>) -> HuleSituation {
+        let menfeng_offset =
+            (hule.seat.index() + P::PLAYER_COUNT - self.zhuangjia.index()) % P::PLAYER_COUNT;
+        HuleSituation {
+            zimo: hule.rong_source.is_none(),
+            lizhi: self.players[hule.seat.index()].lizhi(),
+            first_turn: hule.first_turn,
+            lingshang: hule.lingshang,
+            haidi: hule.haidi,
+            qianggang: hule.qianggang,
+            zhuangjia: hule.seat == self.zhuangjia,
+            quanfengpai: self.table_match_state.chang().quanfengpai(),
+            menfengpai: TileKind::ALL[TileKind::Z1.index() + menfeng_offset],
+            bei_yipai: self.policy.bei_yipai,
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Declares zimo hule on the zimopai; the bingpai stays as dealt so the hulepai is reported
+    /// only through the hule context.
+    pub fn zimo_hule(self) -> Result<Round<P, RoundEnded<P>>, HuleError> {
+        if self.players[self.actor.index()]
+            .hule_shapes(self.state.zimopai)
+            .is_empty()
+        {
+            return Err(HuleError::NotHuleShape);
+        }
+        let context = self.zimo_hule_context();
+        if self.hule_scorer().hule_score(&context).is_none() {
+            return Err(HuleError::NoYaku);
+        }
+
+        let mut hule = Vec::new();
+        let _ = hule.push(context);
+
+        Ok(Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: RoundEnded {
+                outcome: RoundOutcome::Hule,
+                hule,
+                tingpai: Vec::new(),
+                liuju_manguan: Vec::new(),
+            },
+        })
+    }
+
+    /// Whether the zimopai completes a reading of the actor's hand that has yaku.
+    pub(super) fn zimo_hule_available(&self) -> bool {
+        self.hule_scorer()
+            .hule_score(&self.zimo_hule_context())
+            .is_some()
+    }
+
+    fn zimo_hule_context(&self) -> HuleContext<P> {
+        let player = &self.players[self.actor.index()];
+        HuleContext {
+            seat: self.actor,
+            hulepai: self.state.zimopai,
+            rong_source: None,
+            qianggang: false,
+            lingshang: self.state.lingshang,
+            haidi: !self.state.lingshang && self.bipai.remaining_count() == 0,
+            first_turn: player.first_turn_eligible(),
+            bao: player.zimo_bao(),
+            takes_lizhibang: true,
+            takes_ben: true,
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, RoundEnded<P>> {
+    /// Scores each reading of the winner's hand under the policy's yaku.
+    pub fn yaku(&self, hule: &HuleContext<P>) -> YakuEvaluations {
+        self.hule_scorer().yaku(hule)
+    }
+
+    /// The reading with the highest base points among those with yaku, earlier readings first on
+    /// a tie; `None` when no reading has yaku.
+    pub fn hule_score(&self, hule: &HuleContext<P>) -> Option<HuleScore> {
+        self.hule_scorer().hule_score(hule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{CallResponse, CallWindowError, Dapai, TurnAction};
+    use crate::bipai::Bipai;
+    use crate::hule::HuleShape;
+    use crate::player_set::FourPlayer;
+    use crate::round::{
+        CallWindow, CallWindowResolution, FirstZimoOrigin, NoReactionResult, RoundPolicy,
+        ZimoPending,
+    };
+    use crate::score::{BaopaiFan, Score};
+    use crate::seat::Seat;
+    use crate::table_match::{Ben, Chang, Lizhibang, RoundIndex, TableMatchState};
+    use crate::yaku::{YakuPolicy, YakuRule, YakuRuleId, YakuValue};
+
+    use super::*;
+
+    use TileKind::*;
+
+    const SEAT0: [TileKind; 13] = [M2, M5, M8, P2, P5, P8, S2, S5, S8, Z4, Z4, Z5, Z7];
+    /// Waits on Z3 by danqi.
+    const SEAT1: [TileKind; 13] = [M1, M2, M3, M4, M5, M6, M7, M8, M9, P1, P1, P1, Z3];
+    /// Waits on Z3 and Z5 by shuangpeng; every other Z5 is dealt, so it never becomes furiten.
+    const SEAT2: [TileKind; 13] = [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z5, Z5, Z3, Z3];
+    const SEAT3: [TileKind; 13] = [M4, M7, P3, P6, P9, S1, S4, S7, Z1, Z2, Z6, Z5, Z7];
+
+    fn zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        zimo_pending_in(Chang::Dong, qipai, zimo, lingshang, policy)
+    }
+
+    /// Like `zimo_pending`, with seat 0 as zhuangjia of the first round of `chang`.
+    fn zimo_pending_in(
+        chang: Chang,
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        let zhuangjia = Seat::<FourPlayer>::ALL[0];
+        Round::new(
+            Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, lingshang),
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::new(
+                chang,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                zhuangjia,
+                [Score::new(25_000); 4],
+            ),
+        )
+    }
+
+    /// Seat 1 draws Z3 as the last live-wall tile, placed behind the four lingshang tiles, in the
+    /// first round of `chang`.
+    fn haidi_zimo(chang: Chang) -> Round<FourPlayer, ZimoCompleted> {
+        let round = zimo_pending_in(
+            chang,
+            [SEAT0, SEAT1, SEAT2, SEAT3],
+            &[],
+            &[Z7, Z7, Z6, Z6, Z3],
+            RoundPolicy::mahjong_soul(),
+        );
+        let mut round = round.zimo().unwrap();
+        while round.bipai().remaining_count() > 0 {
+            let zimopai = round.zimopai();
+            round = match round.dapai(Dapai::Moqie(zimopai)).unwrap().no_reaction() {
+                NoReactionResult::NextZimo(round) => round.zimo().unwrap(),
+                NoReactionResult::RoundEnded(_) => panic!("expected the live wall to last"),
+            };
+        }
+        round
+    }
+
+    /// Resolves the call window with rong from `winner` and a pass from everyone else.
+    fn rong(
+        round: Round<FourPlayer, ZimoCompleted>,
+        winner: usize,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let zimopai = round.zimopai();
+        let mut window = round.dapai(Dapai::Moqie(zimopai)).unwrap().call_window();
+        let winner = Seat::<FourPlayer>::ALL[winner];
+        for seat in window
+            .pending_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = if seat == winner {
+                CallResponse::Rong
+            } else {
+                CallResponse::Pass
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        match window.resolve().unwrap() {
+            CallWindowResolution::Hule(round) => round,
+            _ => panic!("expected rong"),
+        }
+    }
+
+    /// Seat 1 rongs the first dapai, Z3, with `seat1` and the other seats as dealt.
+    fn first_dapai_rong(
+        seat1: [TileKind; 13],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = zimo_pending([SEAT0, seat1, SEAT2, SEAT3], &[Z3], &[], policy);
+        rong(round.zimo().unwrap(), 1)
+    }
+
+    fn yaku_ids(
+        round: &Round<FourPlayer, RoundEnded<FourPlayer>>,
+    ) -> std::vec::Vec<std::vec::Vec<YakuRuleId>> {
+        round
+            .yaku(&round.hule()[0])
+            .iter()
+            .map(|evaluation| evaluation.yaku().iter().map(|yaku| yaku.id()).collect())
+            .collect()
+    }
+
+    fn first_zimo_hule() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        zimo_pending(
+            [
+                [M4, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1, Z2, Z2],
+                SEAT1,
+                SEAT2,
+                SEAT3,
+            ],
+            &[M3],
+            &[],
+            RoundPolicy::mahjong_soul(),
+        )
+        .zimo()
+        .unwrap()
+        .zimo_hule()
+        .unwrap()
+    }
+
+    #[test]
+    fn hule_on_the_first_zimo_records_the_first_turn() {
+        let round = first_zimo_hule();
+
+        assert!(round.hule()[0].first_turn());
+    }
+
+    #[test]
+    fn zhuangjia_hule_on_the_first_zimo_scores_tianhu() {
+        let round = first_zimo_hule();
+
+        assert_eq!(yaku_ids(&round), [[YakuRuleId::Tianhu]]);
+    }
+
+    #[test]
+    fn rong_on_the_first_dapai_records_the_first_turn() {
+        let round = first_dapai_rong(SEAT1, RoundPolicy::mahjong_soul());
+
+        assert!(round.hule()[0].first_turn());
+    }
+
+    #[test]
+    fn rong_on_the_first_dapai_scores_no_renhu_by_default() {
+        let round = first_dapai_rong(SEAT1, RoundPolicy::mahjong_soul());
+
+        assert_eq!(yaku_ids(&round), [[YakuRuleId::Yiqitongguan]]);
+    }
+
+    #[test]
+    fn rong_on_the_first_dapai_scores_renhu_when_enabled() {
+        let with_renhu = RoundPolicy {
+            yaku: YakuPolicy::mahjong_soul().with_rule(
+                YakuRuleId::Renhu,
+                YakuRule::menqian_only(YakuValue::Yiman(1)),
+            ),
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = first_dapai_rong(SEAT1, with_renhu);
+
+        assert_eq!(yaku_ids(&round), [[YakuRuleId::Renhu]]);
+    }
+
+    fn lingshang_zimo_hule() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        zimo_pending(
+            [
+                [P7, P7, P7, M2, M3, M4, P3, P4, P5, S6, S7, P6, P6],
+                SEAT1,
+                SEAT2,
+                SEAT3,
+            ],
+            &[P7],
+            &[S8],
+            RoundPolicy::mahjong_soul(),
+        )
+        .zimo()
+        .unwrap()
+        .angang_and_lingshang_zimo(P7)
+        .zimo_hule()
+        .unwrap()
+    }
+
+    #[test]
+    fn lingshang_zimo_hule_records_lingshang() {
+        let round = lingshang_zimo_hule();
+
+        assert!(round.hule()[0].lingshang());
+    }
+
+    #[test]
+    fn lingshang_zimo_hule_is_not_haidi() {
+        let round = lingshang_zimo_hule();
+
+        assert!(!round.hule()[0].haidi());
+    }
+
+    #[test]
+    fn lingshang_zimo_hule_scores_lingshang_kaihua() {
+        let round = lingshang_zimo_hule();
+
+        assert_eq!(
+            yaku_ids(&round),
+            [[
+                YakuRuleId::MenqianqingZimohe,
+                YakuRuleId::Duanyaojiu,
+                YakuRuleId::LingshangKaihua
+            ]]
+        );
+    }
+
+    #[test]
+    fn zimo_hule_on_the_last_live_wall_tile_records_haidi() {
+        let round = haidi_zimo(Chang::Dong).zimo_hule().unwrap();
+
+        assert!(round.hule()[0].haidi());
+    }
+
+    #[test]
+    fn zimo_hule_after_the_first_turn_is_not_first_turn() {
+        let round = haidi_zimo(Chang::Dong).zimo_hule().unwrap();
+
+        assert!(!round.hule()[0].first_turn());
+    }
+
+    #[test]
+    fn zimo_hule_on_the_last_live_wall_tile_scores_haidi_laoyue() {
+        let round = haidi_zimo(Chang::Dong).zimo_hule().unwrap();
+
+        assert_eq!(
+            yaku_ids(&round),
+            [[
+                YakuRuleId::MenqianqingZimohe,
+                YakuRuleId::HaidiLaoyue,
+                YakuRuleId::Yiqitongguan
+            ]]
+        );
+    }
+
+    #[test]
+    fn rong_on_the_last_dapai_records_haidi() {
+        let round = rong(haidi_zimo(Chang::Dong), 2);
+
+        assert!(round.hule()[0].haidi());
+    }
+
+    #[test]
+    fn rong_on_the_last_dapai_scores_hedi_laoyu_and_menfeng() {
+        let round = rong(haidi_zimo(Chang::Dong), 2);
+
+        assert_eq!(
+            yaku_ids(&round),
+            [[
+                YakuRuleId::YipaiMenfeng,
+                YakuRuleId::HediLaoyu,
+                YakuRuleId::Yiqitongguan,
+                YakuRuleId::Hunyise
+            ]]
+        );
+    }
+
+    #[test]
+    fn rong_in_the_xi_chang_by_the_xi_seat_scores_quanfeng_too() {
+        let round = rong(haidi_zimo(Chang::Xi), 2);
+
+        assert_eq!(
+            yaku_ids(&round),
+            [[
+                YakuRuleId::YipaiMenfeng,
+                YakuRuleId::YipaiQuanfeng,
+                YakuRuleId::HediLaoyu,
+                YakuRuleId::Yiqitongguan,
+                YakuRuleId::Hunyise
+            ]]
+        );
+    }
+
+    /// Seat 1 waits on Z3 by danqi, read as erbeikou or as qiduizi.
+    const SEAT1_ERBEIKOU: [TileKind; 13] = [M1, M1, M2, M2, M3, M3, P4, P4, P5, P5, P6, P6, Z3];
+
+    /// Seat 1 rongs the first dapai and is scored under the given policy.
+    fn first_dapai_rong_score(policy: RoundPolicy<FourPlayer>) -> HuleScore {
+        let ended = first_dapai_rong(SEAT1_ERBEIKOU, policy);
+
+        ended.hule_score(&ended.hule()[0]).unwrap()
+    }
+
+    fn erbeikou_score() -> HuleScore {
+        first_dapai_rong_score(RoundPolicy::mahjong_soul())
+    }
+
+    fn qiduizi_score() -> HuleScore {
+        first_dapai_rong_score(RoundPolicy {
+            yaku: YakuPolicy::mahjong_soul().with_rule(YakuRuleId::Erbeikou, YakuRule::DISABLED),
+            ..RoundPolicy::mahjong_soul()
+        })
+    }
+
+    #[test]
+    fn hule_score_takes_the_erbeikou_reading_over_qiduizi() {
+        let score = erbeikou_score();
+
+        assert_eq!((score.yaku().fan(), score.fu()), (3, 40));
+    }
+
+    #[test]
+    fn hule_score_keeps_the_base_points_of_the_chosen_reading() {
+        assert_eq!(erbeikou_score().base_points().value(), 1_280);
+    }
+
+    #[test]
+    fn hule_score_falls_back_to_qiduizi_without_erbeikou() {
+        assert!(matches!(
+            qiduizi_score().yaku().shape(),
+            HuleShape::Qiduizi { .. }
+        ));
+    }
+
+    #[test]
+    fn hule_score_counts_the_qiduizi_fan_and_fu() {
+        let score = qiduizi_score();
+
+        assert_eq!((score.yaku().fan(), score.fu()), (2, 25));
+    }
+
+    #[test]
+    fn hule_score_pays_the_qiduizi_rong() {
+        assert_eq!(
+            qiduizi_score().base_points().rong_payment(false),
+            Score::new(1_600)
+        );
+    }
+
+    /// Seat 1 waits on Z3 by danqi with no yaku in any reading.
+    const SEAT1_WITHOUT_YAKU: [TileKind; 13] = [M1, M2, M3, P4, P5, P6, S1, S2, S3, S7, S8, S9, Z3];
+
+    /// Seat 0 discards Z3, which seat 1 waits on without yaku.
+    fn dapai_without_yaku() -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        zimo_pending(
+            [SEAT0, SEAT1_WITHOUT_YAKU, SEAT2, SEAT3],
+            &[Z3],
+            &[],
+            RoundPolicy::mahjong_soul(),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(Z3))
+        .unwrap()
+        .call_window()
+    }
+
+    #[test]
+    fn rong_without_yaku_is_not_offered() {
+        let window = dapai_without_yaku();
+
+        assert!(
+            !window
+                .eligible_seats()
+                .any(|&seat| seat == Seat::<FourPlayer>::ALL[1])
+        );
+    }
+
+    #[test]
+    fn rong_without_yaku_is_rejected() {
+        let window = dapai_without_yaku();
+
+        assert_eq!(
+            window
+                .respond(Seat::<FourPlayer>::ALL[1], CallResponse::Rong)
+                .err(),
+            Some(CallWindowError::SeatNotEligible)
+        );
+    }
+
+    /// Seat 1 chis M1 with M2 and M3, discards Z7 and draws Z3 to an open hand without yaku.
+    fn open_zimo_without_yaku() -> Round<FourPlayer, ZimoCompleted> {
+        let seat1 = [M2, M3, P4, P5, P6, S1, S2, S3, S7, S8, S9, Z3, Z7];
+        let window = zimo_pending(
+            [SEAT0, seat1, SEAT2, SEAT3],
+            &[M1, P9, P9, P9, Z3],
+            &[],
+            RoundPolicy::mahjong_soul(),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(M1))
+        .unwrap()
+        .call_window()
+        .respond(
+            Seat::<FourPlayer>::ALL[1],
+            CallResponse::Chi {
+                bingpai_tiles: [M2, M3],
+            },
+        )
+        .unwrap();
+        let CallWindowResolution::Fulu(round) = window.resolve().unwrap() else {
+            panic!("expected chi");
+        };
+        let mut transition = round.dapai(Dapai::Shouqie(Z7)).unwrap().no_reaction();
+        for _ in 0..3 {
+            let round = transition.into_next_zimo_pending().unwrap().zimo().unwrap();
+            transition = round.dapai(Dapai::Moqie(P9)).unwrap().no_reaction();
+        }
+        transition.into_next_zimo_pending().unwrap().zimo().unwrap()
+    }
+
+    #[test]
+    fn zimo_hule_without_yaku_is_not_offered() {
+        let round = open_zimo_without_yaku();
+
+        assert!(!round.legal_turn_actions().contains(TurnAction::ZimoHule));
+    }
+
+    #[test]
+    fn zimo_hule_without_yaku_is_rejected() {
+        let round = open_zimo_without_yaku();
+
+        assert_eq!(round.zimo_hule().err(), Some(HuleError::NoYaku));
+    }
+
+    /// Seat 1 rongs the first dapai, Z3, with `seat1`, under the baopai and li baopai
+    /// `indicators`.
+    fn first_dapai_rong_under(
+        seat1: [TileKind; 13],
+        indicators: &[(TileKind, TileKind)],
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = Round::new(
+            Bipai::<FourPlayer>::red_three_arranged_with_indicators(
+                [SEAT0, seat1, SEAT2, SEAT3],
+                &[Z3],
+                &[],
+                indicators,
+            ),
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+        rong(round.zimo().unwrap(), 1)
+    }
+
+    /// Seat 0 waits on S6 and S9, discards Z6 on its first zimo with lizhi when `lizhi` says so,
+    /// and draws S9 after one go-around, under the baopai and li baopai `indicators`.
+    fn zimo_hule_after_one_go_around(
+        lizhi: bool,
+        indicators: &[(TileKind, TileKind)],
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = Round::new(
+            Bipai::<FourPlayer>::red_three_arranged_with_indicators(
+                [
+                    [M2, M3, M4, P4, P5, P6, S4, S5, S6, P2, P2, S7, S8],
+                    SEAT1,
+                    SEAT2,
+                    SEAT3,
+                ],
+                &[Z6, Z6, Z6, Z2, S9],
+                &[],
+                indicators,
+            ),
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
+        let mut round = if lizhi {
+            round.lizhi(Dapai::Moqie(Z6)).unwrap()
+        } else {
+            round.dapai(Dapai::Moqie(Z6)).unwrap()
+        };
+        loop {
+            let NoReactionResult::NextZimo(next) = round.no_reaction() else {
+                panic!("expected the live wall to last");
+            };
+            let next = next.zimo().unwrap();
+            if *next.actor() == Seat::<FourPlayer>::ALL[0] {
+                return next.zimo_hule().unwrap();
+            }
+            let zimopai = next.zimopai();
+            round = next.dapai(Dapai::Moqie(zimopai)).unwrap();
+        }
+    }
+
+    fn baopai_fan(round: &Round<FourPlayer, RoundEnded<FourPlayer>>) -> BaopaiFan {
+        round.hule_score(&round.hule()[0]).unwrap().baopai()
+    }
+
+    #[test]
+    fn baopai_counts_the_hulepai_and_the_bingpai_that_match() {
+        let round = first_dapai_rong_under(SEAT1, &[(Z2, Z1)]);
+
+        assert_eq!(baopai_fan(&round).baopai, 2);
+    }
+
+    #[test]
+    fn baopai_fan_raises_the_base_points() {
+        // Yiqitongguan and one baopai make 3 fan at 40 fu.
+        let round = first_dapai_rong_under(SEAT1, &[(M8, Z1)]);
+
+        assert_eq!(
+            round
+                .hule_score(&round.hule()[0])
+                .map(|score| score.base_points().value()),
+            Some(1_280)
+        );
+    }
+
+    #[test]
+    fn red_five_counts_as_hong_baopai() {
+        let round =
+            first_dapai_rong_under([M1, M2, M3, M4, M0, M6, M7, M8, M9, P1, P1, P1, Z3], &[]);
+
+        assert_eq!(baopai_fan(&round).hong_baopai, 1);
+    }
+
+    #[test]
+    fn established_lizhi_counts_li_baopai() {
+        let round = zimo_hule_after_one_go_around(true, &[(Z1, P1)]);
+
+        assert_eq!(baopai_fan(&round).li_baopai, 2);
+    }
+
+    #[test]
+    fn hule_without_lizhi_counts_no_li_baopai() {
+        let round = zimo_hule_after_one_go_around(false, &[(Z1, P1)]);
+
+        assert_eq!(baopai_fan(&round).li_baopai, 0);
+    }
+}
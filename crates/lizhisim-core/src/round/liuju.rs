@@ -0,0 +1,531 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::JiuzhongJiupaiError;
+use crate::bipai::BipaiSpec;
+use crate::player::Player;
+use crate::player_set::PlayerSet;
+use crate::tile::TileKind;
+use crate::tingpai::{base_counts, yaojiu_kind_count};
+
+use super::{Round, RoundEnded, RoundOutcome, RoundPolicy, SigangSanle, ZimoCompleted};
+
+const JIUZHONG_JIUPAI_KIND_COUNT: usize = 9;
+const SIGANG_COUNT: usize = 4;
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Ends the round by jiuzhong jiupai, counting the zimopai with the bingpai.
+    pub fn jiuzhong_jiupai(self) -> Result<Round<P, RoundEnded<P>>, JiuzhongJiupaiError> {
+        self.jiuzhong_jiupai_available()?;
+
+        Ok(Round {
+            bipai: self.bipai,
+            players: self.players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: RoundEnded {
+                outcome: RoundOutcome::JiuzhongJiupai,
+                hule: Vec::new(),
+                tingpai: Vec::new(),
+                liuju_manguan: Vec::new(),
+            },
+        })
+    }
+
+    pub(super) fn jiuzhong_jiupai_available(&self) -> Result<(), JiuzhongJiupaiError> {
+        if !self.policy.jiuzhong_jiupai {
+            return Err(JiuzhongJiupaiError::Disabled);
+        }
+        let player = &self.players[self.actor.index()];
+        if !player.first_turn_eligible() {
+            return Err(JiuzhongJiupaiError::NotFirstTurn);
+        }
+        let mut counts = base_counts(player.bingpai());
+        counts[self.state.zimopai.base_tile().index()] += 1;
+        let kind_count = yaojiu_kind_count(&counts);
+        if kind_count < JIUZHONG_JIUPAI_KIND_COUNT {
+            return Err(JiuzhongJiupaiError::YaojiuKindsInsufficient { kind_count });
+        }
+        Ok(())
+    }
+}
+
+/// The abortive draw that a dapai completes once it passes without rong, if the policy adopts it.
+pub(super) fn passed_dapai_liuju<P>(
+    players: &[Player<P>],
+    policy: &RoundPolicy<P>,
+) -> Option<RoundOutcome> {
+    if policy.sifeng_lianda && is_sifeng_lianda(players) {
+        return Some(RoundOutcome::SifengLianda);
+    }
+    if policy.sijia_lizhi && players.iter().all(|player| player.lizhi().is_established()) {
+        return Some(RoundOutcome::SijiaLizhi);
+    }
+    if sigang_sanle_pending(players, policy) {
+        return Some(RoundOutcome::SigangSanle);
+    }
+    None
+}
+
+/// Whether four gangs stand and the policy ends the round once the next dapai passes.
+pub(super) fn sigang_sanle_pending<P>(players: &[Player<P>], policy: &RoundPolicy<P>) -> bool {
+    let gang_counts = players
+        .iter()
+        .map(|player| player.fulu().iter().filter(|fulu| fulu.is_gang()).count());
+    if gang_counts.clone().sum::<usize>() < SIGANG_COUNT {
+        return false;
+    }
+    match policy.sigang_sanle {
+        SigangSanle::Disabled => false,
+        SigangSanle::ExceptSinglePlayer => {
+            !gang_counts.into_iter().any(|count| count == SIGANG_COUNT)
+        }
+        SigangSanle::Always => true,
+    }
+}
+
+/// Every player's only sipai is the same feng tile and nobody has declared a fulu.
+fn is_sifeng_lianda<P>(players: &[Player<P>]) -> bool {
+    let mut first_dapai = players.iter().map(|player| {
+        let mut he = player.he().iter();
+        match (he.next(), he.next()) {
+            (Some(sipai), None) if player.fulu().is_empty() => Some(sipai.tile_kind),
+            _ => None,
+        }
+    });
+    let Some(Some(tile_kind)) = first_dapai.next() else {
+        return false;
+    };
+    matches!(
+        tile_kind,
+        TileKind::Z1 | TileKind::Z2 | TileKind::Z3 | TileKind::Z4
+    ) && first_dapai.all(|dapai| dapai == Some(tile_kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{CallResponse, Dapai};
+    use crate::bipai::Bipai;
+    use crate::player_set::{FourPlayer, ThreePlayer};
+    use crate::round::{
+        CallWindow, CallWindowResolution, DapaiCompleted, FirstZimoOrigin, NoReactionResult,
+        ZimoPending,
+    };
+    use crate::score::Score;
+    use crate::seat::Seat;
+    use crate::table_match::TableMatchState;
+
+    use super::*;
+
+    use TileKind::*;
+
+    /// Eight yaojiu kinds.
+    const SEAT0_YAOJIU: [TileKind; 13] = [M1, M9, P1, P9, S1, S9, Z1, Z2, M2, M3, M4, P2, P3];
+    const SEAT1_FENG: [TileKind; 13] = [Z1, M1, M2, M4, M5, M7, M8, P1, P2, P4, P5, P7, P8];
+    const SEAT2_FENG: [TileKind; 13] = [Z1, S1, S2, S4, S5, S7, S8, P1, P2, P4, P5, P7, P8];
+    const SEAT3_FENG: [TileKind; 13] = [Z1, S1, S2, S4, S5, S7, S8, M1, M2, M4, M5, M7, M8];
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        lingshang: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, lingshang);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        )
+    }
+
+    fn jiuzhong_jiupai_ready(
+        zimo: &[TileKind],
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, ZimoCompleted> {
+        let seat1 = [Z5, M1, M2, M4, M5, M7, M8, P1, P2, P4, P5, P7, P8];
+        let seat2 = [Z6, S1, S2, S4, S5, S7, S8, P1, P2, P4, P5, P7, P8];
+        let seat3 = [Z7, S1, S2, S4, S5, S7, S8, M1, M2, M4, M5, M7, M8];
+        zimo_pending([SEAT0_YAOJIU, seat1, seat2, seat3], zimo, &[], policy)
+            .zimo()
+            .unwrap()
+    }
+
+    fn no_reaction(round: Round<FourPlayer, DapaiCompleted>) -> Round<FourPlayer, ZimoPending> {
+        round.no_reaction().into_next_zimo_pending().unwrap()
+    }
+
+    /// Every seat discards its Z1 on the first turn; the last dapai is returned before it passes.
+    fn sifeng_dapai(policy: RoundPolicy<FourPlayer>) -> Round<FourPlayer, DapaiCompleted> {
+        let seat0 = [Z1, M1, M2, M4, M5, M7, M8, P1, P2, P4, P5, P7, P8];
+        let mut round = zimo_pending(
+            [seat0, SEAT1_FENG, SEAT2_FENG, SEAT3_FENG],
+            &[Z7, Z7, Z7, Z6],
+            &[],
+            policy,
+        );
+        for _ in 0..3 {
+            round = no_reaction(round.zimo().unwrap().dapai(Dapai::Shouqie(Z1)).unwrap());
+        }
+        round.zimo().unwrap().dapai(Dapai::Shouqie(Z1)).unwrap()
+    }
+
+    /// Every seat declares lizhi on its first turn with four different dapai.
+    fn sijia_lizhi_dapai(policy: RoundPolicy<FourPlayer>) -> Round<FourPlayer, DapaiCompleted> {
+        let seat0 = [M1, M2, M3, M4, M5, M6, M7, M8, M9, P1, P1, P2, P3];
+        let seat1 = [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z2, Z2];
+        let seat2 = [M1, M2, M3, P4, P5, P6, S1, S2, S3, Z5, Z5, Z5, Z6];
+        let seat3 = [M7, M8, M9, P7, P8, P9, S7, S8, S9, Z3, Z3, Z3, Z7];
+        let zimo = [Z4, P9, M9, S9];
+        let mut round = zimo_pending([seat0, seat1, seat2, seat3], &zimo, &[], policy);
+        for zimopai in &zimo[..3] {
+            round = no_reaction(round.zimo().unwrap().lizhi(Dapai::Moqie(*zimopai)).unwrap());
+        }
+        round.zimo().unwrap().lizhi(Dapai::Moqie(zimo[3])).unwrap()
+    }
+
+    /// Seats 0 and 1 each declare two angang; seat 1 then discards P5, which seat 2 could peng.
+    fn sigang_dapai(policy: RoundPolicy<FourPlayer>) -> Round<FourPlayer, DapaiCompleted> {
+        let seat0 = [Z1, Z1, Z1, Z1, Z2, Z2, Z2, Z2, M1, M2, M3, M4, M5];
+        let seat1 = [Z3, Z3, Z3, Z3, Z4, Z4, Z4, Z4, P1, P2, P3, P4, P5];
+        let seat2 = [P5, P5, S1, S2, S3, S4, S6, S7, S8, S9, M6, M7, M8];
+        let seat3 = [M9, M9, M9, S1, S2, S3, P6, P7, P8, P9, Z5, Z6, Z7];
+        let round = zimo_pending(
+            [seat0, seat1, seat2, seat3],
+            &[M6, M6],
+            &[P9, P9, S9, S9],
+            policy,
+        );
+        let round = round
+            .zimo()
+            .unwrap()
+            .angang_and_lingshang_zimo(Z1)
+            .angang_and_lingshang_zimo(Z2);
+        let round = no_reaction(round.dapai(Dapai::Moqie(P9)).unwrap());
+        let round = round
+            .zimo()
+            .unwrap()
+            .angang_and_lingshang_zimo(Z3)
+            .angang_and_lingshang_zimo(Z4);
+        round.dapai(Dapai::Shouqie(P5)).unwrap()
+    }
+
+    /// Seat 0 declares all four angang and discards the last lingshang tile.
+    fn single_player_sigang_dapai(
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, DapaiCompleted> {
+        let seat0 = [Z1, Z1, Z1, Z1, Z2, Z2, Z2, Z2, Z3, Z3, Z3, Z3, M1];
+        let seat1 = [M3, M4, M5, M6, M7, M8, M9, P1, P2, P3, P4, P5, P6];
+        let seat2 = [S1, S2, S3, S4, S5, S6, S7, S8, S9, P7, P8, P9, M1];
+        let seat3 = [Z5, Z5, Z6, Z6, Z7, Z7, M3, M4, M6, M7, P7, P8, S1];
+        let round = zimo_pending(
+            [seat0, seat1, seat2, seat3],
+            &[Z4],
+            &[Z4, Z4, Z4, M2],
+            policy,
+        );
+        let mut round = round.zimo().unwrap();
+        for tile_kind in [Z1, Z2, Z3, Z4] {
+            round = round.angang_and_lingshang_zimo(tile_kind);
+        }
+        round.dapai(Dapai::Moqie(M2)).unwrap()
+    }
+
+    /// Seats 1, 2 and 3 all wait on the M3 that seat 0 discards.
+    fn sanjia_rong_window(
+        policy: RoundPolicy<FourPlayer>,
+    ) -> Round<FourPlayer, CallWindow<FourPlayer>> {
+        let seat0 = [M3, M7, M8, M9, P1, P2, P3, S1, S2, S3, Z7, Z7, Z7];
+        let seat1 = [M1, M2, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+        let seat2 = [M4, M5, S1, S2, S3, S4, S5, S6, S7, S8, S9, Z2, Z2];
+        let seat3 = [M3, M3, Z3, Z3, Z3, Z4, Z4, Z4, Z5, Z5, Z5, Z6, Z6];
+        let mut window = zimo_pending([seat0, seat1, seat2, seat3], &[Z1], &[], policy)
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Shouqie(M3))
+            .unwrap()
+            .call_window();
+        for index in 1..4 {
+            window = window.respond(seat(index), CallResponse::Rong).unwrap();
+        }
+        window
+    }
+
+    /// Seats 1 and 2 both wait on the P3 that seat 0 discards at a three-player table.
+    fn three_player_double_rong_window() -> Round<ThreePlayer, CallWindow<ThreePlayer>> {
+        let seat0 = [P3, M1, M1, M1, M9, M9, M9, P7, P8, P9, Z7, Z7, Z7];
+        let seat1 = [P1, P2, S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1];
+        let seat2 = [P4, P5, S1, S2, S3, S4, S5, S6, S7, S8, S9, Z2, Z2];
+        let bipai = Bipai::<ThreePlayer>::red_two_arranged([seat0, seat1, seat2], &[Z6], &[]);
+        let mut window = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<ThreePlayer>::ALL[0], [Score::new(35_000); 3]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Shouqie(P3))
+        .unwrap()
+        .call_window();
+        for seat in &Seat::<ThreePlayer>::ALL[1..] {
+            window = window.respond(*seat, CallResponse::Rong).unwrap();
+        }
+        window
+    }
+
+    #[test]
+    fn nine_yaojiu_kinds_on_first_turn_end_by_jiuzhong_jiupai() {
+        let round = jiuzhong_jiupai_ready(&[Z3], RoundPolicy::mahjong_soul());
+
+        let round = round.jiuzhong_jiupai().unwrap();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::JiuzhongJiupai);
+    }
+
+    #[test]
+    fn jiuzhong_jiupai_records_no_hule() {
+        let round = jiuzhong_jiupai_ready(&[Z3], RoundPolicy::mahjong_soul());
+
+        let round = round.jiuzhong_jiupai().unwrap();
+
+        assert!(round.hule().is_empty());
+    }
+
+    #[test]
+    fn jiuzhong_jiupai_is_rejected_when_policy_disables_it() {
+        let policy = RoundPolicy {
+            jiuzhong_jiupai: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let round = jiuzhong_jiupai_ready(&[Z3], policy);
+
+        assert_eq!(
+            round.jiuzhong_jiupai().err(),
+            Some(JiuzhongJiupaiError::Disabled)
+        );
+    }
+
+    #[test]
+    fn jiuzhong_jiupai_counts_distinct_yaojiu_kinds_with_zimopai() {
+        let round = jiuzhong_jiupai_ready(&[Z1], RoundPolicy::mahjong_soul());
+
+        assert_eq!(
+            round.jiuzhong_jiupai().err(),
+            Some(JiuzhongJiupaiError::YaojiuKindsInsufficient { kind_count: 8 })
+        );
+    }
+
+    #[test]
+    fn jiuzhong_jiupai_is_rejected_after_own_first_dapai() {
+        let round = jiuzhong_jiupai_ready(&[M5, Z6, Z6, Z6, Z3], RoundPolicy::mahjong_soul());
+        let mut round = no_reaction(round.dapai(Dapai::Moqie(M5)).unwrap());
+        for _ in 0..3 {
+            round = no_reaction(round.zimo().unwrap().dapai(Dapai::Moqie(Z6)).unwrap());
+        }
+        let round = round.zimo().unwrap();
+
+        assert_eq!(
+            round.jiuzhong_jiupai().err(),
+            Some(JiuzhongJiupaiError::NotFirstTurn)
+        );
+    }
+
+    #[test]
+    fn four_same_feng_first_dapai_end_by_sifeng_lianda() {
+        let result = sifeng_dapai(RoundPolicy::mahjong_soul()).no_reaction();
+
+        assert_eq!(result.round_outcome(), Some(RoundOutcome::SifengLianda));
+    }
+
+    #[test]
+    fn sifeng_lianda_continues_when_policy_disables_it() {
+        let policy = RoundPolicy {
+            sifeng_lianda: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+
+        let result = sifeng_dapai(policy).no_reaction();
+
+        assert!(matches!(result, NoReactionResult::NextZimo(_)));
+    }
+
+    fn sijia_lizhi_ended() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let result = sijia_lizhi_dapai(RoundPolicy::mahjong_soul()).no_reaction();
+        let NoReactionResult::RoundEnded(round) = result else {
+            panic!("expected sijia lizhi");
+        };
+        round
+    }
+
+    #[test]
+    fn fourth_established_lizhi_ends_by_sijia_lizhi() {
+        let round = sijia_lizhi_ended();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::SijiaLizhi);
+    }
+
+    #[test]
+    fn sijia_lizhi_establishes_the_fourth_lizhi() {
+        let round = sijia_lizhi_ended();
+
+        assert!(
+            round
+                .players()
+                .iter()
+                .all(|player| player.lizhi().is_established())
+        );
+    }
+
+    #[test]
+    fn sijia_lizhi_continues_when_policy_disables_it() {
+        let policy = RoundPolicy {
+            sijia_lizhi: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+
+        let result = sijia_lizhi_dapai(policy).no_reaction();
+
+        assert!(matches!(result, NoReactionResult::NextZimo(_)));
+    }
+
+    #[test]
+    fn dapai_after_fourth_gang_by_two_players_offers_no_call() {
+        let window = sigang_dapai(RoundPolicy::mahjong_soul()).call_window();
+
+        assert_eq!(window.eligible_seats().count(), 0);
+    }
+
+    #[test]
+    fn dapai_after_fourth_gang_by_two_players_ends_by_sigang_sanle() {
+        let window = sigang_dapai(RoundPolicy::mahjong_soul()).call_window();
+
+        let CallWindowResolution::NoCall(result) = window.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        assert_eq!(result.round_outcome(), Some(RoundOutcome::SigangSanle));
+    }
+
+    fn sigang_sanle_disabled() -> RoundPolicy<FourPlayer> {
+        RoundPolicy {
+            sigang_sanle: SigangSanle::Disabled,
+            ..RoundPolicy::mahjong_soul()
+        }
+    }
+
+    #[test]
+    fn sigang_sanle_keeps_calls_when_policy_disables_it() {
+        let window = sigang_dapai(sigang_sanle_disabled()).call_window();
+
+        assert!(
+            window
+                .legal_responses(seat(2))
+                .unwrap()
+                .contains(&CallResponse::Peng {
+                    bingpai_tiles: [P5, P5]
+                })
+        );
+    }
+
+    #[test]
+    fn sigang_sanle_continues_when_policy_disables_it() {
+        let window = sigang_dapai(sigang_sanle_disabled())
+            .call_window()
+            .respond(seat(2), CallResponse::Pass)
+            .unwrap();
+
+        let CallWindowResolution::NoCall(result) = window.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        assert!(matches!(result, NoReactionResult::NextZimo(_)));
+    }
+
+    #[test]
+    fn four_gangs_by_one_player_continue_except_for_single_player() {
+        let result = single_player_sigang_dapai(RoundPolicy::mahjong_soul()).no_reaction();
+
+        assert!(matches!(result, NoReactionResult::NextZimo(_)));
+    }
+
+    #[test]
+    fn four_gangs_by_one_player_end_when_policy_always_applies() {
+        let policy = RoundPolicy {
+            sigang_sanle: SigangSanle::Always,
+            ..RoundPolicy::mahjong_soul()
+        };
+
+        let result = single_player_sigang_dapai(policy).no_reaction();
+
+        assert_eq!(result.round_outcome(), Some(RoundOutcome::SigangSanle));
+    }
+
+    fn sanjia_hele_ended() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let window = sanjia_rong_window(RoundPolicy::mahjong_soul());
+        let CallWindowResolution::Liuju(round) = window.resolve().unwrap() else {
+            panic!("expected sanjia hele");
+        };
+        round
+    }
+
+    #[test]
+    fn rong_by_three_seats_ends_by_sanjia_hele() {
+        let round = sanjia_hele_ended();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::SanjiaHele);
+    }
+
+    #[test]
+    fn sanjia_hele_records_no_hule() {
+        let round = sanjia_hele_ended();
+
+        assert!(round.hule().is_empty());
+    }
+
+    fn sanjia_rong_without_sanjia_hele() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let policy = RoundPolicy {
+            sanjia_hele: false,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let window = sanjia_rong_window(policy);
+        let CallWindowResolution::Hule(round) = window.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+        round
+    }
+
+    #[test]
+    fn rong_by_three_seats_is_hule_when_policy_disables_sanjia_hele() {
+        let round = sanjia_rong_without_sanjia_hele();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::Hule);
+    }
+
+    #[test]
+    fn rong_by_three_seats_lists_the_seat_after_the_source_first() {
+        let round = sanjia_rong_without_sanjia_hele();
+
+        assert_eq!(round.hule()[0].seat(), &seat(1));
+    }
+
+    #[test]
+    fn rong_by_both_other_seats_at_a_three_player_table_is_hule() {
+        let window = three_player_double_rong_window();
+
+        assert!(matches!(
+            window.resolve(),
+            Ok(CallWindowResolution::Hule(round)) if round.hule().len() == 2
+        ));
+    }
+}
@@ -0,0 +1,570 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::action::{Dapai, LizhiError};
+use crate::bipai::BipaiSpec;
+use crate::fulu::Fulu;
+use crate::hule::InHouseHule;
+use crate::player_set::PlayerSet;
+use crate::tingpai::is_tingpai;
+
+use super::{DapaiCompleted, Round, ZimoCompleted, try_update_player};
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Declares lizhi with its declaration dapai; the lizhi stays pending until the dapai's
+    /// call window resolves without rong.
+    pub fn lizhi(self, dapai: Dapai) -> Result<Round<P, DapaiCompleted>, LizhiError> {
+        let liang_lizhi = self.lizhi_declarable()?;
+
+        let round = self.dapai(dapai)?;
+        let actor = round.actor;
+        let player = &round.players[actor.index()];
+        if !is_tingpai(&InHouseHule, player.bingpai(), player.fulu()) {
+            return Err(LizhiError::NotTingpai);
+        }
+        let players = try_update_player(round.players, actor, |player| {
+            Ok::<_, LizhiError>(player.with_lizhi_declared(liang_lizhi))
+        })?;
+
+        Ok(Round { players, ..round })
+    }
+
+    /// Checks every lizhi condition that does not depend on the declaration dapai and reports
+    /// whether the declaration would be liang lizhi.
+    pub(super) fn lizhi_declarable(&self) -> Result<bool, LizhiError> {
+        let player = &self.players[self.actor.index()];
+        if player.lizhi().is_declared() {
+            return Err(LizhiError::AlreadyDeclared);
+        }
+        if player.fulu().iter().any(Fulu::is_open) {
+            return Err(LizhiError::OpenFulu);
+        }
+        let score = self.table_match_state.scores()[self.actor.index()];
+        let required = self.policy.lizhi_required_score;
+        if score.value() < required.value() {
+            return Err(LizhiError::ScoreInsufficient { score, required });
+        }
+        let remaining_count = self.bipai.remaining_count();
+        let required = self.policy.lizhi_required_remaining_count;
+        if remaining_count < required {
+            return Err(LizhiError::RemainingCountInsufficient {
+                remaining_count,
+                required,
+            });
+        }
+        Ok(player.first_turn_eligible())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{CallResponse, DapaiError, GangError};
+    use crate::bipai::Bipai;
+    use crate::lizhi::LizhiState;
+    use crate::player_set::FourPlayer;
+    use crate::round::{
+        CallWindowResolution, FirstZimoOrigin, NoReactionResult, RoundPolicy, ZimoPending,
+    };
+    use crate::score::Score;
+    use crate::seat::Seat;
+    use crate::table_match::{Lizhibang, MatchRules, RoundSettlement, TableMatchState};
+    use crate::tile::TileKind;
+
+    use super::*;
+
+    use TileKind::*;
+
+    /// Waits on M3 and M6 after dapai of Z7.
+    const SEAT0_LIZHI: [TileKind; 13] = [M4, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1, Z2, Z2];
+    const SEAT1: [TileKind; 13] = [M7, M7, M8, M8, S3, S4, S5, S6, S1, S1, Z5, Z5, Z6];
+    const SEAT1_RONG: [TileKind; 13] = [M1, M2, M3, P4, P5, P6, S4, S5, S6, Z5, Z5, Z5, Z7];
+    const SEAT2: [TileKind; 13] = [M9, M9, S9, P9, P9, P9, Z6, Z6, Z7, Z7, P4, P4, P5];
+    const SEAT3: [TileKind; 13] = [P7, P8, S2, S2, S3, S3, Z3, Z3, Z4, Z4, S4, S5, M8];
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        start_scores: [Score; 4],
+    ) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, &[M6, S6]);
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(seat(0), start_scores),
+        )
+    }
+
+    fn lizhi_ready(zimo: &[TileKind]) -> Round<FourPlayer, ZimoCompleted> {
+        zimo_pending(
+            [SEAT0_LIZHI, SEAT1, SEAT2, SEAT3],
+            zimo,
+            [Score::new(25_000); 4],
+        )
+        .zimo()
+        .unwrap()
+    }
+
+    /// Passes every eligible seat and returns the next zimo.
+    fn pass_all(round: Round<FourPlayer, DapaiCompleted>) -> Round<FourPlayer, ZimoPending> {
+        let mut window = round.call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            window = window.respond(seat, CallResponse::Pass).unwrap();
+        }
+        let CallWindowResolution::NoCall(result) = window.resolve().unwrap() else {
+            panic!("expected no call");
+        };
+        result.into_next_zimo_pending().unwrap()
+    }
+
+    fn moqie_round(round: Round<FourPlayer, ZimoPending>) -> Round<FourPlayer, DapaiCompleted> {
+        let round = round.zimo().unwrap();
+        let zimopai = round.zimopai();
+        round.dapai(Dapai::Moqie(zimopai)).unwrap()
+    }
+
+    #[test]
+    fn lizhi_records_pending_state_at_declaration_sipai() {
+        let round = lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap();
+        let player = &round.players()[0];
+
+        assert_eq!(
+            player.lizhi(),
+            LizhiState::Pending {
+                sipai_index: player.he().last_index().unwrap(),
+                liang_lizhi: true,
+            }
+        );
+    }
+
+    #[test]
+    fn lizhi_sipai_index_points_at_the_declaration_tile() {
+        let round = lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap();
+        let player = &round.players()[0];
+
+        assert_eq!(
+            player
+                .lizhi()
+                .sipai_index()
+                .and_then(|index| player.he().get(index))
+                .map(|sipai| sipai.tile_kind),
+            Some(Z7)
+        );
+    }
+
+    #[test]
+    fn lizhi_is_established_after_declaration_passes() {
+        let round = pass_all(lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap());
+
+        assert!(round.players()[0].lizhi().is_established());
+    }
+
+    #[test]
+    fn established_lizhi_has_yifa() {
+        let round = pass_all(lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap());
+
+        assert!(round.players()[0].lizhi().yifa());
+    }
+
+    #[test]
+    fn lizhi_on_the_first_dapai_is_liang_lizhi() {
+        let round = pass_all(lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap());
+
+        assert!(round.players()[0].lizhi().liang_lizhi());
+    }
+
+    #[test]
+    fn rong_on_declaration_sipai_leaves_lizhi_pending() {
+        let round = zimo_pending(
+            [SEAT0_LIZHI, SEAT1_RONG, SEAT2, SEAT3],
+            &[Z7],
+            [Score::new(25_000); 4],
+        )
+        .zimo()
+        .unwrap()
+        .lizhi(Dapai::Moqie(Z7))
+        .unwrap();
+        let mut window = round.call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = if seat == self::seat(1) {
+                CallResponse::Rong
+            } else {
+                CallResponse::Pass
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        let CallWindowResolution::Hule(round) = window.resolve().unwrap() else {
+            panic!("expected rong");
+        };
+
+        assert!(!round.players()[0].lizhi().is_established());
+    }
+
+    #[test]
+    fn lizhi_after_first_dapai_is_not_liang_lizhi() {
+        let round = lizhi_ready(&[M9, P6, P6, P6, Z7]).dapai(Dapai::Moqie(M9));
+        let mut round = pass_all(round.unwrap());
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+        let round = round.zimo().unwrap().lizhi(Dapai::Moqie(Z7)).unwrap();
+
+        assert!(!round.players()[0].lizhi().liang_lizhi());
+    }
+
+    /// Seat 0's lizhi with its next own moqie.
+    fn lizhi_then_own_next_dapai() -> Round<FourPlayer, DapaiCompleted> {
+        let mut round = pass_all(
+            lizhi_ready(&[Z7, P6, P6, P6, M9])
+                .lizhi(Dapai::Moqie(Z7))
+                .unwrap(),
+        );
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+        moqie_round(round)
+    }
+
+    #[test]
+    fn own_next_dapai_clears_yifa() {
+        let round = lizhi_then_own_next_dapai();
+
+        assert!(!round.players()[0].lizhi().yifa());
+    }
+
+    #[test]
+    fn own_next_dapai_keeps_lizhi_established() {
+        let round = lizhi_then_own_next_dapai();
+
+        assert!(round.players()[0].lizhi().is_established());
+    }
+
+    #[test]
+    fn fulu_by_any_seat_clears_yifa() {
+        let round = pass_all(lizhi_ready(&[Z7, P9]).lizhi(Dapai::Moqie(Z7)).unwrap());
+        let window = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(P9))
+            .unwrap()
+            .call_window();
+        let mut window = window;
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = if seat == self::seat(2) {
+                CallResponse::Peng {
+                    bingpai_tiles: [P9, P9],
+                }
+            } else {
+                CallResponse::Pass
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        let CallWindowResolution::Fulu(round) = window.resolve().unwrap() else {
+            panic!("expected peng");
+        };
+
+        assert!(!round.players()[0].lizhi().yifa());
+    }
+
+    #[test]
+    fn lizhi_rejects_second_declaration() {
+        let round = pass_all(
+            lizhi_ready(&[Z7, P6, P6, P6, M9])
+                .lizhi(Dapai::Moqie(Z7))
+                .unwrap(),
+        );
+        let mut round = round;
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+
+        assert_eq!(
+            round.zimo().unwrap().lizhi(Dapai::Moqie(M9)).err(),
+            Some(LizhiError::AlreadyDeclared)
+        );
+    }
+
+    #[test]
+    fn lizhi_rejects_dapai_that_leaves_no_tingpai() {
+        assert_eq!(
+            lizhi_ready(&[Z7]).lizhi(Dapai::Shouqie(M4)).err(),
+            Some(LizhiError::NotTingpai)
+        );
+    }
+
+    #[test]
+    fn lizhi_rejects_score_below_requirement() {
+        let round = zimo_pending(
+            [SEAT0_LIZHI, SEAT1, SEAT2, SEAT3],
+            &[Z7],
+            [
+                Score::new(900),
+                Score::new(25_000),
+                Score::new(25_000),
+                Score::new(49_100),
+            ],
+        )
+        .zimo()
+        .unwrap();
+
+        assert_eq!(
+            round.lizhi(Dapai::Moqie(Z7)).err(),
+            Some(LizhiError::ScoreInsufficient {
+                score: Score::new(900),
+                required: Score::new(1_000),
+            })
+        );
+    }
+
+    #[test]
+    fn lizhi_rejects_too_few_remaining_tiles() {
+        let policy = RoundPolicy {
+            lizhi_required_remaining_count: 70,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(
+            [SEAT0_LIZHI, SEAT1, SEAT2, SEAT3],
+            &[Z7],
+            &[M6, S6],
+        );
+        let round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(seat(0), [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap();
+
+        assert_eq!(
+            round.lizhi(Dapai::Moqie(Z7)).err(),
+            Some(LizhiError::RemainingCountInsufficient {
+                remaining_count: 69,
+                required: 70,
+            })
+        );
+    }
+
+    #[test]
+    fn lizhi_rejects_open_fulu() {
+        let round = zimo_pending(
+            [SEAT0_LIZHI, SEAT1, SEAT2, SEAT3],
+            &[M9, Z2],
+            [Score::new(25_000); 4],
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(M9))
+        .unwrap();
+        let round = pass_all(round);
+        // Seat 1 discards Z2 for seat 0 to peng.
+        let round = round.zimo().unwrap().dapai(Dapai::Moqie(Z2)).unwrap();
+        let mut window = round.call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = if seat == self::seat(0) {
+                CallResponse::Peng {
+                    bingpai_tiles: [Z2, Z2],
+                }
+            } else {
+                CallResponse::Pass
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        let CallWindowResolution::Fulu(round) = window.resolve().unwrap() else {
+            panic!("expected peng");
+        };
+        let round = pass_all(round.dapai(Dapai::Shouqie(P1)).unwrap());
+        let mut round = round;
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+        let round = round.zimo().unwrap();
+        let zimopai = round.zimopai();
+
+        assert_eq!(
+            round.lizhi(Dapai::Moqie(zimopai)).err(),
+            Some(LizhiError::OpenFulu)
+        );
+    }
+
+    #[test]
+    fn established_lizhi_rejects_shouqie() {
+        let round = pass_all(
+            lizhi_ready(&[Z7, P6, P6, P6, M9])
+                .lizhi(Dapai::Moqie(Z7))
+                .unwrap(),
+        );
+        let mut round = round;
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+
+        assert_eq!(
+            round.zimo().unwrap().dapai(Dapai::Shouqie(M4)).err(),
+            Some(DapaiError::ShouqieUnavailableAfterLizhi)
+        );
+    }
+
+    #[test]
+    fn lizhi_seat_is_offered_only_rong_in_call_window() {
+        let mut round = pass_all(
+            lizhi_ready(&[Z7, P6, P6, M3])
+                .lizhi(Dapai::Moqie(Z7))
+                .unwrap(),
+        );
+        for _ in 0..2 {
+            round = pass_all(moqie_round(round));
+        }
+        // Seat 3 discards M3, which seat 0 could otherwise chi with M4 and M5.
+        let window = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(M3))
+            .unwrap()
+            .call_window();
+
+        assert_eq!(
+            window.legal_responses(seat(0)).unwrap(),
+            [CallResponse::Pass, CallResponse::Rong]
+        );
+    }
+
+    fn angang_after_lizhi(
+        seat0: [TileKind; 13],
+        zimo: &[TileKind],
+        discard: TileKind,
+    ) -> Round<FourPlayer, ZimoCompleted> {
+        let round = zimo_pending([seat0, SEAT1, SEAT2, SEAT3], zimo, [Score::new(25_000); 4])
+            .zimo()
+            .unwrap()
+            .lizhi(Dapai::Moqie(discard))
+            .unwrap();
+        let mut round = pass_all(round);
+        for _ in 0..3 {
+            round = pass_all(moqie_round(round));
+        }
+        round.zimo().unwrap()
+    }
+
+    #[test]
+    fn angang_after_lizhi_keeps_waits_with_zimopai() {
+        let round = angang_after_lizhi(SEAT0_LIZHI, &[Z7, P6, P6, P6, Z1], Z7)
+            .angang_and_lingshang_zimo(Z1);
+
+        assert_eq!(round.players()[0].fulu().len(), 1);
+    }
+
+    #[test]
+    fn angang_after_lizhi_clears_yifa() {
+        let round = angang_after_lizhi(SEAT0_LIZHI, &[Z7, P6, P6, P6, Z1], Z7)
+            .angang_and_lingshang_zimo(Z1);
+
+        assert!(!round.players()[0].lizhi().yifa());
+    }
+
+    #[test]
+    fn angang_after_lizhi_rejects_tile_other_than_zimopai() {
+        let seat0 = [M1, M1, M1, M1, M2, M3, P1, P2, P3, S7, S8, S9, Z2];
+        let round = angang_after_lizhi(seat0, &[Z7, P6, P6, P6, M9], Z7);
+
+        assert_eq!(
+            round.angang(M1).err(),
+            Some(GangError::AngangUnavailableAfterLizhi { tile_kind: M1 })
+        );
+    }
+
+    #[test]
+    fn angang_after_lizhi_rejects_changed_waits() {
+        // The bingpai waits on M1, M4 and Z2; after the angang only M1 and M4 remain.
+        let seat0 = [M1, M1, M1, M2, M3, P1, P2, P3, S7, S8, S9, Z2, Z2];
+        let round = angang_after_lizhi(seat0, &[Z7, P6, P6, P6, M1], Z7);
+
+        assert_eq!(
+            round.angang(M1).err(),
+            Some(GangError::AngangUnavailableAfterLizhi { tile_kind: M1 })
+        );
+    }
+
+    #[test]
+    fn established_lizhi_posts_the_deposit_to_the_lizhibang() {
+        let round = pass_all(lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap());
+
+        assert_eq!(round.table_match_state().lizhibang(), Lizhibang::new(1));
+    }
+
+    #[test]
+    fn established_lizhi_takes_the_deposit_from_the_current_score() {
+        let round = pass_all(lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap());
+
+        assert_eq!(round.table_match_state().scores()[0], Score::new(24_000));
+    }
+
+    #[test]
+    fn pending_lizhi_posts_no_deposit() {
+        let round = lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap();
+
+        assert_eq!(round.table_match_state().lizhibang(), Lizhibang::new(0));
+    }
+
+    /// Plays the established lizhi of seat 0 by moqie to huangpai pingju and settles it.
+    fn lizhi_huangpai_pingju_settlement() -> RoundSettlement<FourPlayer> {
+        let mut transition = NoReactionResult::NextZimo(pass_all(
+            lizhi_ready(&[Z7]).lizhi(Dapai::Moqie(Z7)).unwrap(),
+        ));
+        for _ in 0..69 {
+            transition = match transition {
+                NoReactionResult::NextZimo(round) => moqie_round(round).no_reaction(),
+                NoReactionResult::RoundEnded(_) => break,
+            };
+        }
+        let NoReactionResult::RoundEnded(round) = transition else {
+            panic!("expected huangpai pingju");
+        };
+        round.into_settlement(&MatchRules::mahjong_soul()).unwrap()
+    }
+
+    #[test]
+    fn settlement_carries_the_established_deposit_in_the_lizhibang() {
+        let settlement = lizhi_huangpai_pingju_settlement();
+
+        assert_eq!(
+            settlement.table_match_state().lizhibang(),
+            Lizhibang::new(1)
+        );
+    }
+
+    #[test]
+    fn settlement_takes_no_second_deposit_from_the_lizhi_seat() {
+        let settlement = lizhi_huangpai_pingju_settlement();
+
+        // The lizhi seat is the only tingpai seat, so it also receives the buting fafu.
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [27_000, 24_000, 24_000, 24_000]
+        );
+    }
+}
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use core::marker::PhantomData;
+
+use crate::bao::BaoPolicy;
+use crate::score::{Score, ScorePolicy};
+use crate::yaku::YakuPolicy;
+
+/// The part of the validated table rules that a `Round` consults while it runs.
+///
+/// The value is fixed for the whole round and carries no preset identity or source.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RoundPolicy<P> {
+    /// Whether the initial baopai indicator is revealed after qipai.
+    pub baopai: bool,
+    pub angang_baopai_timing: GangBaopaiTiming,
+    /// Timing shared by daminggang and jiagang.
+    pub minggang_baopai_timing: GangBaopaiTiming,
+    /// Whether a guoshi wait may rob an angang by qianggang.
+    pub guoshi_angang_qianggang: bool,
+    /// Lowest start-of-round score that still allows a lizhi declaration.
+    pub lizhi_required_score: Score,
+    /// Lowest live-wall remaining count, checked before the declaration dapai.
+    pub lizhi_required_remaining_count: usize,
+    /// Points each established lizhi moves into the lizhibang.
+    pub lizhi_deposit: Score,
+    /// Whether jiuzhong jiupai is offered as a turn action.
+    pub jiuzhong_jiupai: bool,
+    pub sifeng_lianda: bool,
+    /// Whether the round ends once every player's lizhi is established.
+    pub sijia_lizhi: bool,
+    pub sigang_sanle: SigangSanle,
+    pub rong_resolution: RongResolution,
+    /// Whether rong by every seat other than the dapai actor ends the round without hule,
+    /// checked before `rong_resolution`.
+    pub sanjia_hele: bool,
+    /// Whether tingpai counts at huangpai pingju without a yaku.
+    pub xingshi_tingpai: bool,
+    /// Whether a wait on a kind whose four copies the player holds counts as tingpai at
+    /// huangpai pingju.
+    pub shouzhong_kongting: bool,
+    /// Points the buting seats pay in total to the tingpai seats at huangpai pingju.
+    pub buting_fafu: Score,
+    pub liuju_manguan: LiujuManguan,
+    pub shiti: Shiti,
+    pub babei: Babei,
+    /// Whether other seats may rong the `Z4` set aside by babei.
+    pub babei_rong: bool,
+    /// Whether a kezi of `Z4` counts as a yipai for every seat.
+    pub bei_yipai: bool,
+    pub bao: BaoPolicy,
+    pub yaku: YakuPolicy,
+    pub score: ScorePolicy,
+    pub player_set: PhantomData<fn() -> P>,
+}
+
+/// When a gang reveals its additional baopai indicator.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum GangBaopaiTiming {
+    /// Revealed as the gang is declared, before the lingshang zimo.
+    Immediate,
+    /// Held pending until the gang actor's next dapai.
+    AfterDapai,
+}
+
+/// Whether the dapai after the fourth gang ends the round once it passes without rong.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum SigangSanle {
+    Disabled,
+    /// Four gangs by one player leave the round running so that player may still complete it.
+    ExceptSinglePlayer,
+    Always,
+}
+
+/// Which of several seats answering rong to the same tile win.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum RongResolution {
+    /// Every rong seat wins. The first winner in seat order from the dapai actor takes the
+    /// lizhibang, and every winner is paid the ben.
+    Multiple,
+    /// Only the first rong seat in seat order from the dapai actor wins.
+    FirstInSeatOrder,
+}
+
+/// How huangpai pingju settles a seat eligible for liuju manguan.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LiujuManguan {
+    Disabled,
+    /// Paid as a manguan zimo hule, taking the ben and the lizhibang.
+    Hule,
+    /// Paid as a manguan zimo at the draw, leaving the ben and the lizhibang untouched.
+    PingjuPayment,
+}
+
+/// Which dapai the caller may not make right after chi or peng.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Shiti {
+    Allowed,
+    /// The base tile of the called tile is forbidden.
+    ForbidSameKind,
+    /// Chi also forbids the base tile at the other end of the sequence it completes.
+    ForbidSameKindAndSuji,
+}
+
+/// Whether the actor may set `Z4` aside and draw a lingshang replacement.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Babei {
+    Disabled,
+    /// A held `Z4` may be set aside or kept in bingpai.
+    Optional,
+    /// A held `Z4` may not be kept: dapai waits until it is set aside while babei remains
+    /// available.
+    Required,
+}
+
+#[cfg(test)]
+impl<P> RoundPolicy<P> {
+    pub(crate) fn mahjong_soul() -> Self {
+        Self {
+            baopai: true,
+            angang_baopai_timing: GangBaopaiTiming::Immediate,
+            minggang_baopai_timing: GangBaopaiTiming::AfterDapai,
+            guoshi_angang_qianggang: true,
+            lizhi_required_score: Score::new(1_000),
+            lizhi_required_remaining_count: 4,
+            lizhi_deposit: Score::new(1_000),
+            jiuzhong_jiupai: true,
+            sifeng_lianda: true,
+            sijia_lizhi: true,
+            sigang_sanle: SigangSanle::ExceptSinglePlayer,
+            rong_resolution: RongResolution::Multiple,
+            sanjia_hele: true,
+            xingshi_tingpai: true,
+            shouzhong_kongting: false,
+            buting_fafu: Score::new(3_000),
+            liuju_manguan: LiujuManguan::Hule,
+            shiti: Shiti::ForbidSameKindAndSuji,
+            babei: Babei::Disabled,
+            babei_rong: false,
+            bei_yipai: false,
+            bao: BaoPolicy::mahjong_soul(),
+            yaku: YakuPolicy::mahjong_soul(),
+            score: ScorePolicy::mahjong_soul(),
+            player_set: PhantomData,
+        }
+    }
+}
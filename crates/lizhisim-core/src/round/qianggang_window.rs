@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::{CallResponse, CallWindowError};
+use crate::bipai::BipaiSpec;
+use crate::fulu::FuluKind;
+use crate::player_set::PlayerSet;
+use crate::seat::Seat;
+use crate::tile::TileKind;
+
+use super::call_window::CallSlots;
+use super::{Round, RoundEnded, RoundOutcome, ZimoCompleted, pass_hulepai, update_player};
+
+/// The window in which other seats may rob the gang tile before the lingshang zimo.
+pub struct QianggangWindow<P> {
+    /// `Jiagang`, or `Angang` whose tile only a guoshi wait may rob.
+    pub(super) gang_kind: FuluKind,
+    pub(super) gang_tile: TileKind,
+    pub(super) slots: CallSlots<P>,
+}
+
+pub enum QianggangResolution<P: PlayerSet + BipaiSpec> {
+    LingshangZimo(Round<P, ZimoCompleted>),
+    Hule(Round<P, RoundEnded<P>>),
+    /// The responses ended the round by an abortive draw instead of hule.
+    Liuju(Round<P, RoundEnded<P>>),
+}
+
+impl<P: PlayerSet + BipaiSpec> QianggangResolution<P> {
+    pub fn into_lingshang_zimo(self) -> Option<Round<P, ZimoCompleted>> {
+        match self {
+            Self::LingshangZimo(round) => Some(round),
+            Self::Hule(_) | Self::Liuju(_) => None,
+        }
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, QianggangWindow<P>> {
+    pub fn gang_kind(&self) -> FuluKind {
+        self.state.gang_kind
+    }
+
+    pub fn gang_tile_kind(&self) -> TileKind {
+        self.state.gang_tile
+    }
+
+    pub fn eligible_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.eligible_seats()
+    }
+
+    pub fn pending_seats(&self) -> impl Iterator<Item = &Seat<P>> {
+        self.state.slots.pending_seats()
+    }
+
+    pub fn legal_responses(&self, seat: Seat<P>) -> Option<&[CallResponse]> {
+        self.state.slots.legal_responses(seat)
+    }
+
+    pub fn respond(
+        mut self,
+        seat: Seat<P>,
+        response: CallResponse,
+    ) -> Result<Self, CallWindowError> {
+        self.state.slots.respond(seat, response)?;
+        Ok(self)
+    }
+
+    pub fn resolve(self) -> Result<QianggangResolution<P>, CallWindowError> {
+        if !self.state.slots.is_complete() {
+            return Err(CallWindowError::ResponsesPending);
+        }
+
+        if self.policy.sanjia_hele && self.state.slots.is_sanjia_hele() {
+            return Ok(QianggangResolution::Liuju(Round {
+                bipai: self.bipai,
+                players: self.players,
+                actor: self.actor,
+                zhuangjia: self.zhuangjia,
+                first_zimo_origin: self.first_zimo_origin,
+                policy: self.policy,
+                table_match_state: self.table_match_state,
+                gang_baopai_pending_count: self.gang_baopai_pending_count,
+                state: RoundEnded {
+                    outcome: RoundOutcome::SanjiaHele,
+                    hule: Vec::new(),
+                    tingpai: Vec::new(),
+                    liuju_manguan: Vec::new(),
+                },
+            }));
+        }
+
+        // Only rong is offered besides pass.
+        let hule = self.state.slots.rong_hule(
+            self.policy.rong_resolution,
+            self.state.gang_tile,
+            self.actor,
+            true,
+            false,
+            &self.players,
+        );
+        if !hule.is_empty() {
+            return Ok(QianggangResolution::Hule(Round {
+                bipai: self.bipai,
+                players: self.players,
+                actor: self.actor,
+                zhuangjia: self.zhuangjia,
+                first_zimo_origin: self.first_zimo_origin,
+                policy: self.policy,
+                table_match_state: self.table_match_state,
+                gang_baopai_pending_count: self.gang_baopai_pending_count,
+                state: RoundEnded {
+                    outcome: RoundOutcome::Hule,
+                    hule,
+                    tingpai: Vec::new(),
+                    liuju_manguan: Vec::new(),
+                },
+            }));
+        }
+
+        let (bipai, zimopai) = self.bipai.lingshang_zimo()?;
+        // An angang tile is never discarded, so only the guoshi waits offered rong miss it.
+        let players = if self.state.gang_kind == FuluKind::Angang {
+            let gang_tile = self.state.gang_tile;
+            self.state
+                .slots
+                .eligible_seats()
+                .fold(self.players, |players, &seat| {
+                    update_player(players, seat, |player| {
+                        player.with_missed_hulepai(gang_tile)
+                    })
+                })
+        } else {
+            pass_hulepai(self.players, self.actor, self.state.gang_tile)
+        };
+        Ok(QianggangResolution::LingshangZimo(Round {
+            bipai,
+            players,
+            actor: self.actor,
+            zhuangjia: self.zhuangjia,
+            first_zimo_origin: self.first_zimo_origin,
+            policy: self.policy,
+            table_match_state: self.table_match_state,
+            gang_baopai_pending_count: self.gang_baopai_pending_count,
+            state: ZimoCompleted {
+                zimopai,
+                lingshang: true,
+            },
+        }))
+    }
+}
@@ -0,0 +1,541 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::action::{Dapai, TurnAction, TurnActionError};
+use crate::bingpai::Bingpai;
+use crate::bipai::BipaiSpec;
+use crate::hule::InHouseHule;
+use crate::player_set::PlayerSet;
+use crate::tile::TileKind;
+use crate::tingpai::{base_counts, is_tingpai};
+
+use super::babei::BabeiWindow;
+use super::gang::with_angang;
+use super::qianggang_window::QianggangWindow;
+use super::{DapaiCompleted, FirstZimoOrigin, Round, RoundEnded, ZimoCompleted};
+
+/// Upper bound on one turn's actions: 14 dapai and 14 lizhi dapai, 3 angang, 4 jiagang, babei,
+/// zimo hule and jiuzhong jiupai.
+const MAX_TURN_ACTION_COUNT: usize = 41;
+/// A bingpai of 13 tiles plus the zimopai offers at most 14 distinct dapai.
+const MAX_DAPAI_OPTION_COUNT: usize = 14;
+
+/// Every action the actor may take after zimo, in a fixed order: dapai, lizhi, angang, jiagang,
+/// babei, zimo hule, jiuzhong jiupai, each ordered by `TileKind::ALL` with moqie first.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LegalTurnActions {
+    actions: Vec<TurnAction, MAX_TURN_ACTION_COUNT>,
+}
+
+impl LegalTurnActions {
+    pub fn as_slice(&self) -> &[TurnAction] {
+        &self.actions
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &TurnAction> {
+        self.actions.iter()
+    }
+
+    pub fn contains(&self, action: TurnAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    fn push(&mut self, action: TurnAction) {
+        // The bound above covers every hand, so the push never overflows.
+        let _ = self.actions.push(action);
+    }
+}
+
+pub enum TurnActionResult<P: PlayerSet + BipaiSpec> {
+    Dapai(Round<P, DapaiCompleted>),
+    Angang(Round<P, QianggangWindow<P>>),
+    Jiagang(Round<P, QianggangWindow<P>>),
+    Babei(Round<P, BabeiWindow<P>>),
+    RoundEnded(Round<P, RoundEnded<P>>),
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, ZimoCompleted> {
+    /// Lists the actions that `turn_action` accepts; every listed action succeeds.
+    pub fn legal_turn_actions(&self) -> LegalTurnActions {
+        let mut legal = LegalTurnActions {
+            actions: Vec::new(),
+        };
+        let player = &self.players[self.actor.index()];
+        let zimopai = self.state.zimopai;
+        let mut held_counts = base_counts(player.bingpai());
+        held_counts[zimopai.base_tile().index()] += 1;
+
+        // A required babei holds back every dapai, lizhi included.
+        let dapai_options = if self.babei_required() {
+            Vec::new()
+        } else {
+            self.dapai_options()
+        };
+        for &(dapai, _) in &dapai_options {
+            legal.push(TurnAction::Dapai(dapai));
+        }
+        if self.lizhi_declarable().is_ok() {
+            for (dapai, bingpai) in &dapai_options {
+                if is_tingpai(&InHouseHule, bingpai, player.fulu()) {
+                    legal.push(TurnAction::Lizhi(*dapai));
+                }
+            }
+        }
+
+        if self.bipai.lingshang_zimo_available() {
+            for tile_kind in TileKind::ALL.into_iter().filter(|&tile_kind| {
+                !tile_kind.is_hong_baopai()
+                    && held_counts[tile_kind.index()] == 4
+                    && with_angang(player.clone(), zimopai, tile_kind).is_ok()
+            }) {
+                legal.push(TurnAction::Angang(tile_kind));
+            }
+            for tile_kind in TileKind::ALL.into_iter().filter(|&tile_kind| {
+                (player.bingpai().counts()[tile_kind.index()] > 0 || tile_kind == zimopai)
+                    && player
+                        .fulu()
+                        .iter()
+                        .any(|fulu| fulu.is_jiagang_target(tile_kind))
+            }) {
+                legal.push(TurnAction::Jiagang(tile_kind));
+            }
+        }
+
+        if self.babei_available().is_ok() {
+            legal.push(TurnAction::Babei);
+        }
+        if self.zimo_hule_available() {
+            legal.push(TurnAction::ZimoHule);
+        }
+        if self.jiuzhong_jiupai_available().is_ok() {
+            legal.push(TurnAction::JiuzhongJiupai);
+        }
+        legal
+    }
+
+    /// Applies a member of `legal_turn_actions`, rejecting anything outside the set.
+    pub fn turn_action(self, action: TurnAction) -> Result<TurnActionResult<P>, TurnActionError> {
+        if !self.legal_turn_actions().contains(action) {
+            return Err(TurnActionError::IllegalAction { action });
+        }
+
+        Ok(match action {
+            TurnAction::Dapai(dapai) => TurnActionResult::Dapai(self.dapai(dapai)?),
+            TurnAction::Lizhi(dapai) => TurnActionResult::Dapai(self.lizhi(dapai)?),
+            TurnAction::Angang(tile_kind) => TurnActionResult::Angang(self.angang(tile_kind)?),
+            TurnAction::Jiagang(tile_kind) => TurnActionResult::Jiagang(self.jiagang(tile_kind)?),
+            TurnAction::Babei => TurnActionResult::Babei(self.babei()?),
+            TurnAction::ZimoHule => TurnActionResult::RoundEnded(self.zimo_hule()?),
+            TurnAction::JiuzhongJiupai => TurnActionResult::RoundEnded(self.jiuzhong_jiupai()?),
+        })
+    }
+
+    /// Each accepted dapai with the bingpai it leaves.
+    fn dapai_options(&self) -> Vec<(Dapai, Bingpai), MAX_DAPAI_OPTION_COUNT> {
+        let mut options = Vec::new();
+        let player = &self.players[self.actor.index()];
+        let zimopai = self.state.zimopai;
+        if player.lizhi().is_declared() {
+            let _ = options.push((Dapai::Moqie(zimopai), player.bingpai().clone()));
+            return options;
+        }
+
+        // The initial-deal zhuangjia holds the zimopai as part of the qipai, so it has no moqie
+        // and may shouqie the zimopai itself.
+        let initial_deal_first_dapai = self.first_zimo_origin == FirstZimoOrigin::InitialDeal
+            && self.actor == self.zhuangjia
+            && player.first_turn_eligible();
+        if !initial_deal_first_dapai {
+            let _ = options.push((Dapai::Moqie(zimopai), player.bingpai().clone()));
+        }
+        for tile_kind in TileKind::ALL {
+            let held = player.bingpai().counts()[tile_kind.index()] > 0
+                || (initial_deal_first_dapai && tile_kind == zimopai);
+            if held
+                && let Ok(bingpai) = player
+                    .bingpai()
+                    .clone()
+                    .with_added(zimopai)
+                    .and_then(|bingpai| bingpai.with_removed(tile_kind))
+            {
+                let _ = options.push((Dapai::Shouqie(tile_kind), bingpai));
+            }
+        }
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::action::{CallResponse, DapaiError};
+    use crate::bipai::Bipai;
+    use crate::player_set::FourPlayer;
+    use crate::round::{CallWindowResolution, RoundOutcome, RoundPolicy, ZimoPending};
+    use crate::score::Score;
+    use crate::seat::Seat;
+    use crate::table_match::TableMatchState;
+
+    use super::*;
+
+    use TileKind::*;
+
+    /// Waits on M3 and M6 after dapai of Z7.
+    const SEAT0_LIZHI: [TileKind; 13] = [M4, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1, Z2, Z2];
+    const SEAT0_JIUZHONG: [TileKind; 13] = [M1, M9, P1, P9, S1, S9, Z1, Z2, Z3, M2, M3, P2, P3];
+    const SEAT0_ANGANG: [TileKind; 13] = [Z1, Z1, Z1, Z1, Z2, Z2, Z2, M1, M1, M1, M2, M2, M3];
+    const SEAT1: [TileKind; 13] = [M7, M7, M8, M8, S3, S4, S5, S6, S1, S1, Z5, Z5, Z6];
+    const SEAT2: [TileKind; 13] = [M9, M9, S9, P9, P9, P9, Z6, Z6, Z7, Z7, P4, P4, P5];
+    const SEAT3: [TileKind; 13] = [P7, P8, S2, S2, S3, S3, Z3, Z3, Z4, Z4, S4, S5, M8];
+    const JIAGANG_SEAT0: [TileKind; 13] = [Z2, Z2, Z2, Z3, Z3, Z3, Z4, Z4, Z4, S1, S1, S1, S2];
+    const JIAGANG_SEAT1: [TileKind; 13] = [M7, M7, M8, M8, S3, S4, S5, S6, S7, S8, Z5, Z5, Z6];
+    const JIAGANG_SEAT2: [TileKind; 13] = [M3, M3, S9, S9, S9, Z6, Z6, Z7, Z7, Z7, P1, P1, P2];
+    const JIAGANG_SEAT3: [TileKind; 13] = [M9, M9, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+
+    fn zimo_pending(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+        first_zimo_origin: FirstZimoOrigin,
+    ) -> Round<FourPlayer, ZimoPending> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, &[P6, S6]);
+        Round::new(
+            bipai,
+            first_zimo_origin,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+    }
+
+    fn live_wall_zimo(
+        qipai: [[TileKind; 13]; 4],
+        zimo: &[TileKind],
+    ) -> Round<FourPlayer, ZimoCompleted> {
+        zimo_pending(qipai, zimo, FirstZimoOrigin::LiveWall)
+            .zimo()
+            .unwrap()
+    }
+
+    /// Resolves the call window with `response` from `caller` and a pass from everyone else.
+    fn respond_all(
+        round: Round<FourPlayer, DapaiCompleted>,
+        caller: Option<(usize, CallResponse)>,
+    ) -> CallWindowResolution<FourPlayer> {
+        let mut window = round.call_window();
+        for seat in window
+            .eligible_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>()
+        {
+            let response = match caller {
+                Some((index, response)) if seat == Seat::<FourPlayer>::ALL[index] => response,
+                _ => CallResponse::Pass,
+            };
+            window = window.respond(seat, response).unwrap();
+        }
+        window.resolve().unwrap()
+    }
+
+    fn pass_all(round: Round<FourPlayer, DapaiCompleted>) -> Round<FourPlayer, ZimoPending> {
+        let CallWindowResolution::NoCall(result) = respond_all(round, None) else {
+            panic!("expected no call");
+        };
+        result.into_next_zimo_pending().unwrap()
+    }
+
+    fn moqie_pass_all(round: Round<FourPlayer, ZimoPending>) -> Round<FourPlayer, ZimoPending> {
+        let round = round.zimo().unwrap();
+        let zimopai = round.zimopai();
+        pass_all(round.dapai(Dapai::Moqie(zimopai)).unwrap())
+    }
+
+    /// Seat 0 waits on M3 and M6 and draws M3.
+    fn hule_ready() -> Round<FourPlayer, ZimoCompleted> {
+        live_wall_zimo([SEAT0_LIZHI, SEAT1, SEAT2, SEAT3], &[M3])
+    }
+
+    /// Seat 0 declared lizhi on Z7 and draws the fourth Z1.
+    fn lizhi_declared() -> Round<FourPlayer, ZimoCompleted> {
+        let round = live_wall_zimo([SEAT0_LIZHI, SEAT1, SEAT2, SEAT3], &[Z7, P6, P6, P6, Z1]);
+        let mut round = pass_all(round.lizhi(Dapai::Moqie(Z7)).unwrap());
+        for _ in 0..3 {
+            round = moqie_pass_all(round);
+        }
+        round.zimo().unwrap()
+    }
+
+    fn initial_deal_jiuzhong() -> Round<FourPlayer, ZimoCompleted> {
+        zimo_pending(
+            [SEAT0_JIUZHONG, SEAT1, SEAT2, SEAT3],
+            &[Z4],
+            FirstZimoOrigin::InitialDeal,
+        )
+        .zimo()
+        .unwrap()
+    }
+
+    fn angang_ready() -> Round<FourPlayer, ZimoCompleted> {
+        live_wall_zimo([SEAT0_ANGANG, SEAT1, SEAT2, SEAT3], &[Z2])
+    }
+
+    /// Seat 2 pengs the first M3 and later draws the fourth copy.
+    fn jiagang_ready() -> Round<FourPlayer, ZimoCompleted> {
+        let round = live_wall_zimo(
+            [JIAGANG_SEAT0, JIAGANG_SEAT1, JIAGANG_SEAT2, JIAGANG_SEAT3],
+            &[M3, S2, S2, S2, M3],
+        );
+        let peng = CallResponse::Peng {
+            bingpai_tiles: [M3, M3],
+        };
+        let CallWindowResolution::Fulu(round) =
+            respond_all(round.dapai(Dapai::Moqie(M3)).unwrap(), Some((2, peng)))
+        else {
+            panic!("expected peng");
+        };
+        let mut round = pass_all(round.dapai(Dapai::Shouqie(P2)).unwrap());
+        for _ in 0..3 {
+            round = moqie_pass_all(round);
+        }
+        round.zimo().unwrap()
+    }
+
+    fn candidate_actions() -> std::vec::Vec<TurnAction> {
+        TileKind::ALL
+            .into_iter()
+            .flat_map(|tile_kind| {
+                [
+                    TurnAction::Dapai(Dapai::Moqie(tile_kind)),
+                    TurnAction::Dapai(Dapai::Shouqie(tile_kind)),
+                    TurnAction::Lizhi(Dapai::Moqie(tile_kind)),
+                    TurnAction::Lizhi(Dapai::Shouqie(tile_kind)),
+                    TurnAction::Angang(tile_kind),
+                    TurnAction::Jiagang(tile_kind),
+                ]
+            })
+            .chain([
+                TurnAction::Babei,
+                TurnAction::ZimoHule,
+                TurnAction::JiuzhongJiupai,
+            ])
+            .collect()
+    }
+
+    fn direct_call_succeeds(round: Round<FourPlayer, ZimoCompleted>, action: TurnAction) -> bool {
+        match action {
+            TurnAction::Dapai(dapai) => round.dapai(dapai).is_ok(),
+            TurnAction::Lizhi(dapai) => round.lizhi(dapai).is_ok(),
+            TurnAction::Angang(tile_kind) => round.angang(tile_kind).is_ok(),
+            TurnAction::Jiagang(tile_kind) => round.jiagang(tile_kind).is_ok(),
+            TurnAction::Babei => round.babei().is_ok(),
+            TurnAction::ZimoHule => round.zimo_hule().is_ok(),
+            TurnAction::JiuzhongJiupai => round.jiuzhong_jiupai().is_ok(),
+        }
+    }
+
+    /// Checks every candidate against both `turn_action` and the direct transition.
+    fn assert_legal_set_is_exact(fixture: fn() -> Round<FourPlayer, ZimoCompleted>) {
+        let legal = fixture().legal_turn_actions();
+        for action in candidate_actions() {
+            assert_eq!(
+                fixture().turn_action(action).is_ok(),
+                legal.contains(action),
+                "{action:?}"
+            );
+            // Angang names the base tile, so the hong baopai spelling is left out of the set.
+            if let TurnAction::Angang(tile_kind) = action
+                && tile_kind.is_hong_baopai()
+            {
+                continue;
+            }
+            assert_eq!(
+                direct_call_succeeds(fixture(), action),
+                legal.contains(action),
+                "{action:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn legal_turn_actions_are_exact_for_hule_shape() {
+        assert_legal_set_is_exact(hule_ready);
+    }
+
+    #[test]
+    fn legal_turn_actions_are_exact_after_lizhi() {
+        assert_legal_set_is_exact(lizhi_declared);
+    }
+
+    #[test]
+    fn legal_turn_actions_are_exact_for_initial_deal_zhuangjia() {
+        assert_legal_set_is_exact(initial_deal_jiuzhong);
+    }
+
+    #[test]
+    fn legal_turn_actions_are_exact_with_angang() {
+        assert_legal_set_is_exact(angang_ready);
+    }
+
+    #[test]
+    fn legal_turn_actions_are_exact_with_jiagang() {
+        assert_legal_set_is_exact(jiagang_ready);
+    }
+
+    #[test]
+    fn legal_turn_actions_list_moqie_then_shouqie_in_tile_kind_order() {
+        let legal = hule_ready().legal_turn_actions();
+        let dapai = legal
+            .iter()
+            .filter_map(|action| match action {
+                TurnAction::Dapai(dapai) => Some(*dapai),
+                _ => None,
+            })
+            .collect::<std::vec::Vec<_>>();
+
+        assert_eq!(
+            dapai,
+            [
+                Dapai::Moqie(M3),
+                Dapai::Shouqie(M4),
+                Dapai::Shouqie(M5),
+                Dapai::Shouqie(P1),
+                Dapai::Shouqie(P2),
+                Dapai::Shouqie(P3),
+                Dapai::Shouqie(S7),
+                Dapai::Shouqie(S8),
+                Dapai::Shouqie(S9),
+                Dapai::Shouqie(Z1),
+                Dapai::Shouqie(Z2),
+            ]
+        );
+    }
+
+    #[test]
+    fn legal_turn_actions_include_zimo_hule_for_hule_shape() {
+        let legal = hule_ready().legal_turn_actions();
+
+        assert!(legal.contains(TurnAction::ZimoHule));
+    }
+
+    /// Seat 0 holds both M0 and M5 after drawing M6.
+    fn hong_baopai_zimo() -> Round<FourPlayer, ZimoCompleted> {
+        let qipai = [M0, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1, Z2, Z2];
+        live_wall_zimo([qipai, SEAT1, SEAT2, SEAT3], &[M6])
+    }
+
+    #[test]
+    fn legal_turn_actions_distinguish_hong_baopai_shouqie() {
+        let legal = hong_baopai_zimo().legal_turn_actions();
+
+        assert_eq!(
+            [M0, M5].map(|tile_kind| legal.contains(TurnAction::Dapai(Dapai::Shouqie(tile_kind)))),
+            [true, true]
+        );
+    }
+
+    #[test]
+    fn legal_turn_actions_distinguish_hong_baopai_lizhi_shouqie() {
+        let legal = hong_baopai_zimo().legal_turn_actions();
+
+        assert_eq!(
+            [M0, M5].map(|tile_kind| legal.contains(TurnAction::Lizhi(Dapai::Shouqie(tile_kind)))),
+            [true, true]
+        );
+    }
+
+    #[test]
+    fn lizhi_declared_actor_may_only_moqie_or_keep_waits_with_angang() {
+        let legal = lizhi_declared().legal_turn_actions();
+
+        assert_eq!(
+            legal.as_slice(),
+            [TurnAction::Dapai(Dapai::Moqie(Z1)), TurnAction::Angang(Z1),]
+        );
+    }
+
+    #[test]
+    fn initial_deal_zhuangjia_has_no_moqie() {
+        let legal = initial_deal_jiuzhong().legal_turn_actions();
+
+        assert!(!legal.contains(TurnAction::Dapai(Dapai::Moqie(Z4))));
+    }
+
+    #[test]
+    fn initial_deal_zhuangjia_may_shouqie_the_first_zimopai() {
+        let legal = initial_deal_jiuzhong().legal_turn_actions();
+
+        assert!(legal.contains(TurnAction::Dapai(Dapai::Shouqie(Z4))));
+    }
+
+    #[test]
+    fn initial_deal_zhuangjia_may_declare_jiuzhong_jiupai() {
+        let legal = initial_deal_jiuzhong().legal_turn_actions();
+
+        assert!(legal.contains(TurnAction::JiuzhongJiupai));
+    }
+
+    #[test]
+    fn turn_action_rejects_actions_outside_the_legal_set() {
+        let action = TurnAction::Dapai(Dapai::Moqie(Z1));
+
+        assert_eq!(
+            hule_ready().turn_action(action).err(),
+            Some(TurnActionError::IllegalAction { action })
+        );
+    }
+
+    fn zimo_hule_ended() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let Ok(TurnActionResult::RoundEnded(round)) =
+            hule_ready().turn_action(TurnAction::ZimoHule)
+        else {
+            panic!("expected zimo hule");
+        };
+        round
+    }
+
+    #[test]
+    fn zimo_hule_ends_round_by_hule() {
+        let round = zimo_hule_ended();
+
+        assert_eq!(round.round_outcome(), RoundOutcome::Hule);
+    }
+
+    #[test]
+    fn zimo_hule_is_won_by_the_actor_alone() {
+        let round = zimo_hule_ended();
+
+        assert_eq!(
+            round
+                .hule()
+                .iter()
+                .map(|hule| *hule.seat())
+                .collect::<std::vec::Vec<_>>(),
+            [Seat::<FourPlayer>::ALL[0]]
+        );
+    }
+
+    #[test]
+    fn zimo_hule_takes_zimopai_as_hulepai() {
+        let round = zimo_hule_ended();
+
+        assert_eq!(round.hule()[0].hulepai(), M3);
+    }
+
+    #[test]
+    fn zimo_hule_has_no_rong_source() {
+        let round = zimo_hule_ended();
+
+        assert_eq!(round.hule()[0].rong_source(), None);
+    }
+
+    #[test]
+    fn moqie_of_a_tile_other_than_zimopai_is_rejected() {
+        assert_eq!(
+            hule_ready().dapai(Dapai::Moqie(Z1)).err(),
+            Some(DapaiError::MoqieTileMismatch {
+                tile_kind: Z1,
+                zimopai: M3,
+            })
+        );
+    }
+}
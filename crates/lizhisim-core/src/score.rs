@@ -2,6 +2,10 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+mod evaluate;
+
+use crate::yaku::YakuEvaluation;
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Score(i32);
 
@@ -14,3 +18,148 @@ impl Score {
         self.0
     }
 }
+
+/// How a preset counts fu, turns fan and fu into base points and shares out zimo payments.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ScorePolicy {
+    /// Whether a menqian pinghe zimo still adds the zimo fu.
+    pub pinghe_zimo_fu: bool,
+    /// Whether a zimo hule on a lingshang replacement adds the zimo fu.
+    pub lingshang_zimo_fu: bool,
+    /// Fixed fu of every qiduizi reading.
+    pub qiduizi_fu: u8,
+    /// Fu of a quetou that is both menfengpai and quanfengpai.
+    pub lianfengpai_quetou_fu: u8,
+    /// Whether 4 fan 30 fu and 3 fan 60 fu are rounded up to manguan.
+    pub qieshang_manguan: bool,
+    pub leiji_yiman: LeijiYiman,
+    /// Highest yiman multiple paid; 1 keeps yiman from stacking.
+    pub max_yiman: u8,
+    pub missing_seat_share: MissingSeatShare,
+}
+
+/// What 13 fan or more without a yiman yaku is paid as.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LeijiYiman {
+    Yiman,
+    /// Capped at sanbeiman.
+    Sanbeiman,
+}
+
+#[cfg(test)]
+impl ScorePolicy {
+    pub(crate) const fn mahjong_soul() -> Self {
+        Self {
+            pinghe_zimo_fu: false,
+            lingshang_zimo_fu: true,
+            qiduizi_fu: 25,
+            lianfengpai_quetou_fu: 4,
+            qieshang_manguan: false,
+            leiji_yiman: LeijiYiman::Yiman,
+            max_yiman: 6,
+            missing_seat_share: MissingSeatShare::Lost,
+        }
+    }
+}
+
+/// What becomes of the zimo share a missing seat would pay at a table of fewer than four.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MissingSeatShare {
+    /// Nobody pays it.
+    Lost,
+    /// The payers split it, each part rounded up to a multiple of 100.
+    Split,
+}
+
+/// The limit a hule reached, when its base points no longer follow fan and fu.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ScoreLimit {
+    Manguan,
+    Tiaoman,
+    Beiman,
+    Sanbeiman,
+    /// A yiman multiple, counted fan included.
+    Yiman(u8),
+}
+
+/// The base points every payment of a hule is derived from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct BasePoints {
+    value: i32,
+    limit: Option<ScoreLimit>,
+}
+
+impl BasePoints {
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    pub fn limit(&self) -> Option<ScoreLimit> {
+        self.limit
+    }
+
+    /// What the rong source pays, before ben.
+    pub fn rong_payment(&self, zhuangjia: bool) -> Score {
+        self.payment(if zhuangjia { 6 } else { 4 })
+    }
+
+    /// What one payer of a zimo hule pays, before ben; the zhuangjia pays or is paid double.
+    pub fn zimo_payment(&self, zhuangjia: bool, payer_zhuangjia: bool) -> Score {
+        self.payment(if zhuangjia || payer_zhuangjia { 2 } else { 1 })
+    }
+
+    /// `multiplier` times the base points, rounded up to a multiple of 100.
+    fn payment(&self, multiplier: i32) -> Score {
+        Score::new((self.value * multiplier + 99) / 100 * 100)
+    }
+}
+
+/// The fan a hule gains from baopai, counted once per matching tile.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct BaopaiFan {
+    /// Tiles matching a revealed baopai, counted once per indicator.
+    pub baopai: u8,
+    /// Tiles matching a li baopai, counted only for an established lizhi.
+    pub li_baopai: u8,
+    pub hong_baopai: u8,
+    /// Bei set aside by babei.
+    pub babei_baopai: u8,
+}
+
+impl BaopaiFan {
+    pub fn total(&self) -> u8 {
+        self.baopai + self.li_baopai + self.hong_baopai + self.babei_baopai
+    }
+}
+
+/// The scored reading of a hule: its yaku breakdown, baopai, fu and base points.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HuleScore {
+    yaku: YakuEvaluation,
+    baopai: BaopaiFan,
+    fu: u8,
+    base_points: BasePoints,
+}
+
+impl HuleScore {
+    pub fn yaku(&self) -> &YakuEvaluation {
+        &self.yaku
+    }
+
+    pub fn baopai(&self) -> BaopaiFan {
+        self.baopai
+    }
+
+    /// The fan of the yaku and the baopai together.
+    pub fn fan(&self) -> u8 {
+        self.yaku.fan() + self.baopai.total()
+    }
+
+    pub fn fu(&self) -> u8 {
+        self.fu
+    }
+
+    pub fn base_points(&self) -> BasePoints {
+        self.base_points
+    }
+}
@@ -0,0 +1,540 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::hule::{HuleShape, HuleWait, MianziKind};
+use crate::score::{BaopaiFan, BasePoints, HuleScore, LeijiYiman, ScoreLimit, ScorePolicy};
+use crate::tile::TileKind;
+use crate::yaku::{HuleSituation, YakuEvaluation};
+
+const BASE_FU: u8 = 20;
+const MENQIAN_RONG_FU: u8 = 10;
+const ZIMO_FU: u8 = 2;
+/// Fu of a yipai quetou and of a qianzhang, bianzhang or danqi wait.
+const MINOR_FU: u8 = 2;
+/// Fu of an open pinghe-shaped rong, which would otherwise count only the base fu.
+const OPEN_PINGHE_FU: u8 = 30;
+const MANGUAN_BASE_POINTS: i32 = 2_000;
+/// What 4 fan 30 fu and 3 fan 60 fu reach before rounding up.
+const QIESHANG_BASE_POINTS: i32 = 1_920;
+const YIMAN_BASE_POINTS: i32 = 8_000;
+const LEIJI_YIMAN_FAN: u8 = 13;
+
+impl ScorePolicy {
+    /// Scores one reading: its fu and the base points of its fan with `baopai`, fu and yiman.
+    pub fn hule_score(
+        &self,
+        yaku: YakuEvaluation,
+        baopai: BaopaiFan,
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> HuleScore {
+        let fu = self.fu(yaku.shape(), hulepai, situation);
+        let base_points = self.base_points(yaku.fan() + baopai.total(), fu, yaku.yiman());
+        HuleScore {
+            yaku,
+            baopai,
+            fu,
+            base_points,
+        }
+    }
+
+    /// Fu of one reading, rounded up to a multiple of 10 except for qiduizi.
+    pub fn fu(&self, shape: &HuleShape, hulepai: TileKind, situation: &HuleSituation) -> u8 {
+        let (fu, menqian) = match shape {
+            HuleShape::Qiduizi { .. } => return self.qiduizi_fu,
+            HuleShape::Guoshi { .. } => (BASE_FU, true),
+            HuleShape::Mianzi {
+                quetou,
+                mianzi,
+                wait,
+            } => {
+                let mianzi_fu: u8 = mianzi
+                    .iter()
+                    .map(|mianzi| {
+                        let fu = match mianzi.kind() {
+                            MianziKind::Shunzi => return 0,
+                            MianziKind::Kezi => 2,
+                            MianziKind::Gangzi => 8,
+                        };
+                        let yaojiu = if mianzi.tile_kind().is_yaojiu() { 2 } else { 1 };
+                        let anke = if mianzi.is_anke(hulepai, *wait, situation.zimo) {
+                            2
+                        } else {
+                            1
+                        };
+                        fu * yaojiu * anke
+                    })
+                    .sum();
+                let wait_fu = match wait {
+                    HuleWait::Qianzhang | HuleWait::Bianzhang | HuleWait::Danqi => MINOR_FU,
+                    HuleWait::Liangmian | HuleWait::Shuangpeng => 0,
+                };
+                (
+                    BASE_FU + mianzi_fu + self.quetou_fu(*quetou, situation) + wait_fu,
+                    !mianzi.iter().any(|mianzi| mianzi.is_open()),
+                )
+            }
+        };
+        let pinghe = fu == BASE_FU && matches!(shape, HuleShape::Mianzi { .. });
+
+        let fu = if !situation.zimo {
+            if menqian {
+                fu + MENQIAN_RONG_FU
+            } else if pinghe {
+                OPEN_PINGHE_FU
+            } else {
+                fu
+            }
+        } else if (pinghe && menqian && !self.pinghe_zimo_fu)
+            || (situation.lingshang && !self.lingshang_zimo_fu)
+        {
+            fu
+        } else {
+            fu + ZIMO_FU
+        };
+        fu.div_ceil(10) * 10
+    }
+
+    fn quetou_fu(&self, quetou: TileKind, situation: &HuleSituation) -> u8 {
+        let fengpai_fu = match (
+            quetou == situation.menfengpai,
+            quetou == situation.quanfengpai,
+        ) {
+            (true, true) => self.lianfengpai_quetou_fu,
+            (true, false) | (false, true) => MINOR_FU,
+            (false, false) => 0,
+        };
+        let other_yipai = matches!(quetou, TileKind::Z5 | TileKind::Z6 | TileKind::Z7)
+            || (situation.bei_yipai && quetou == TileKind::Z4);
+        fengpai_fu + if other_yipai { MINOR_FU } else { 0 }
+    }
+
+    /// Base points of `fan` and `fu`, or of `yiman` once any yiman yaku is scored.
+    pub fn base_points(&self, fan: u8, fu: u8, yiman: u8) -> BasePoints {
+        let limit = if yiman > 0 {
+            ScoreLimit::Yiman(yiman.min(self.max_yiman))
+        } else {
+            match fan {
+                LEIJI_YIMAN_FAN.. => match self.leiji_yiman {
+                    LeijiYiman::Yiman => ScoreLimit::Yiman(1),
+                    LeijiYiman::Sanbeiman => ScoreLimit::Sanbeiman,
+                },
+                11..=12 => ScoreLimit::Sanbeiman,
+                8..=10 => ScoreLimit::Beiman,
+                6..=7 => ScoreLimit::Tiaoman,
+                5 => ScoreLimit::Manguan,
+                _ => {
+                    let value = i32::from(fu) << (fan + 2);
+                    if value < MANGUAN_BASE_POINTS
+                        && !(self.qieshang_manguan && value == QIESHANG_BASE_POINTS)
+                    {
+                        return BasePoints { value, limit: None };
+                    }
+                    ScoreLimit::Manguan
+                }
+            }
+        };
+        let value = match limit {
+            ScoreLimit::Manguan => MANGUAN_BASE_POINTS,
+            ScoreLimit::Tiaoman => MANGUAN_BASE_POINTS * 3 / 2,
+            ScoreLimit::Beiman => MANGUAN_BASE_POINTS * 2,
+            ScoreLimit::Sanbeiman => MANGUAN_BASE_POINTS * 3,
+            ScoreLimit::Yiman(multiple) => YIMAN_BASE_POINTS * i32::from(multiple),
+        };
+        BasePoints {
+            value,
+            limit: Some(limit),
+        }
+    }
+}
+
+impl BasePoints {
+    /// The base points of a manguan, which liuju manguan is paid as.
+    pub(crate) const MANGUAN: Self = Self {
+        value: MANGUAN_BASE_POINTS,
+        limit: Some(ScoreLimit::Manguan),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use crate::bingpai::Bingpai;
+    use crate::fulu::Fulu;
+    use crate::hule::{Hule, HuleHand, InHouseHule};
+    use crate::lizhi::LizhiState;
+    use crate::player_set::FourPlayer;
+    use crate::score::Score;
+    use crate::seat::Seat;
+
+    use super::*;
+
+    use TileKind::*;
+
+    const PINGHE_TANYAO: [TileKind; 13] = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S6, S7, P8, P8];
+
+    fn rong() -> HuleSituation {
+        HuleSituation {
+            zimo: false,
+            lizhi: LizhiState::Undeclared,
+            first_turn: false,
+            lingshang: false,
+            haidi: false,
+            qianggang: false,
+            zhuangjia: false,
+            quanfengpai: Z1,
+            menfengpai: Z2,
+            bei_yipai: false,
+        }
+    }
+
+    fn zimo() -> HuleSituation {
+        HuleSituation {
+            zimo: true,
+            ..rong()
+        }
+    }
+
+    /// Fu of every reading of the hand.
+    fn fu_with(
+        policy: &ScorePolicy,
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> Vec<u8> {
+        let bingpai = tile_kinds
+            .iter()
+            .fold(Bingpai::red_three_four_player(), |bingpai, &tile_kind| {
+                bingpai.with_added(tile_kind).unwrap()
+            });
+        InHouseHule
+            .decompose(&HuleHand::new(&bingpai, fulu, hulepai))
+            .iter()
+            .map(|shape| policy.fu(shape, hulepai, situation))
+            .collect()
+    }
+
+    fn fu(
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> Vec<u8> {
+        fu_with(
+            &ScorePolicy::mahjong_soul(),
+            tile_kinds,
+            fulu,
+            hulepai,
+            situation,
+        )
+    }
+
+    #[test]
+    fn menqian_pinghe_zimo_counts_no_zimo_fu() {
+        assert_eq!(fu(&PINGHE_TANYAO, &[], S8, &zimo()), [20]);
+    }
+
+    #[test]
+    fn menqian_pinghe_rong_counts_the_menqian_rong_fu() {
+        assert_eq!(fu(&PINGHE_TANYAO, &[], S8, &rong()), [30]);
+    }
+
+    #[test]
+    fn menqian_pinghe_zimo_counts_the_zimo_fu_when_the_preset_adds_it() {
+        let with_zimo_fu = ScorePolicy {
+            pinghe_zimo_fu: true,
+            ..ScorePolicy::mahjong_soul()
+        };
+
+        assert_eq!(
+            fu_with(&with_zimo_fu, &PINGHE_TANYAO, &[], S8, &zimo()),
+            [30]
+        );
+    }
+
+    fn open_pinghe_fulu() -> [Fulu<FourPlayer>; 1] {
+        [Fulu::chi([M3, M4], M2, Seat::<FourPlayer>::ALL[3])]
+    }
+
+    const OPEN_PINGHE: [TileKind; 10] = [P3, P4, P5, S4, S5, S6, S6, S7, P8, P8];
+
+    #[test]
+    fn open_pinghe_shape_rong_counts_thirty_fu() {
+        assert_eq!(fu(&OPEN_PINGHE, &open_pinghe_fulu(), S8, &rong()), [30]);
+    }
+
+    #[test]
+    fn open_pinghe_shape_zimo_counts_thirty_fu() {
+        assert_eq!(fu(&OPEN_PINGHE, &open_pinghe_fulu(), S8, &zimo()), [30]);
+    }
+
+    const QIDUIZI: [TileKind; 13] = [M1, M1, M4, M4, P2, P2, P7, P7, S3, S3, Z6, Z6, Z1];
+
+    #[test]
+    fn qiduizi_rong_counts_the_fixed_fu() {
+        assert_eq!(fu(&QIDUIZI, &[], Z1, &rong()), [25]);
+    }
+
+    #[test]
+    fn qiduizi_zimo_counts_the_fixed_fu() {
+        assert_eq!(fu(&QIDUIZI, &[], Z1, &zimo()), [25]);
+    }
+
+    /// Waits on S7 and P8 by shuangpeng.
+    const SHUANGPENG: [TileKind; 13] = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S7, S7, P8, P8];
+
+    #[test]
+    fn kezi_completed_by_rong_counts_as_minke() {
+        assert_eq!(fu(&SHUANGPENG, &[], S7, &rong()), [40]);
+    }
+
+    #[test]
+    fn kezi_completed_by_zimo_counts_as_anke() {
+        assert_eq!(fu(&SHUANGPENG, &[], S7, &zimo()), [30]);
+    }
+
+    const LIANFENGPAI_QUETOU: [TileKind; 13] = [P9, P9, P9, M2, M3, M4, P3, P4, P5, S6, S7, Z1, Z1];
+
+    fn lianfengpai() -> HuleSituation {
+        HuleSituation {
+            zhuangjia: true,
+            menfengpai: Z1,
+            ..rong()
+        }
+    }
+
+    #[test]
+    fn lianfengpai_quetou_counts_four_fu_by_default() {
+        assert_eq!(fu(&LIANFENGPAI_QUETOU, &[], S8, &lianfengpai()), [50]);
+    }
+
+    #[test]
+    fn lianfengpai_quetou_counts_the_preset_fu() {
+        let two_fu = ScorePolicy {
+            lianfengpai_quetou_fu: 2,
+            ..ScorePolicy::mahjong_soul()
+        };
+
+        assert_eq!(
+            fu_with(&two_fu, &LIANFENGPAI_QUETOU, &[], S8, &lianfengpai()),
+            [40]
+        );
+    }
+
+    fn lingshang_angang() -> [Fulu<FourPlayer>; 1] {
+        [Fulu::angang([M8, M8, M8, M8])]
+    }
+
+    const LINGSHANG_QIANZHANG: [TileKind; 10] = [M2, M3, M4, P3, P4, P5, S6, S8, Z5, Z5];
+
+    fn lingshang_zimo() -> HuleSituation {
+        HuleSituation {
+            lingshang: true,
+            ..zimo()
+        }
+    }
+
+    #[test]
+    fn lingshang_zimo_counts_the_zimo_fu_by_default() {
+        assert_eq!(
+            fu(
+                &LINGSHANG_QIANZHANG,
+                &lingshang_angang(),
+                S7,
+                &lingshang_zimo()
+            ),
+            [50]
+        );
+    }
+
+    #[test]
+    fn lingshang_zimo_counts_no_zimo_fu_when_the_preset_leaves_it_out() {
+        let without_zimo_fu = ScorePolicy {
+            lingshang_zimo_fu: false,
+            ..ScorePolicy::mahjong_soul()
+        };
+
+        assert_eq!(
+            fu_with(
+                &without_zimo_fu,
+                &LINGSHANG_QIANZHANG,
+                &lingshang_angang(),
+                S7,
+                &lingshang_zimo()
+            ),
+            [40]
+        );
+    }
+
+    #[test]
+    fn yaojiu_and_gangzi_raise_the_mianzi_fu() {
+        let fulu = [Fulu::daminggang(
+            [Z5, Z5, Z5],
+            Z5,
+            Seat::<FourPlayer>::ALL[1],
+        )];
+        let bingpai = [M1, M1, M1, P2, P3, P4, S6, S7, S8, Z1];
+
+        // 20 base, 16 open yaojiu gangzi, 8 yaojiu anke, 2 quanfengpai quetou and 2 danqi.
+        assert_eq!(fu(&bingpai, &fulu, Z1, &rong()), [50]);
+    }
+
+    #[test]
+    fn one_fan_thirty_fu_makes_240_base_points() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(1, 30, 0).value(),
+            240
+        );
+    }
+
+    #[test]
+    fn three_fan_forty_fu_makes_1_280_base_points() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(3, 40, 0).value(),
+            1_280
+        );
+    }
+
+    #[test]
+    fn four_fan_thirty_fu_stays_below_manguan() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(4, 30, 0).limit(),
+            None
+        );
+    }
+
+    #[test]
+    fn four_fan_forty_fu_reaches_manguan() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(4, 40, 0).limit(),
+            Some(ScoreLimit::Manguan)
+        );
+    }
+
+    fn qieshang_manguan() -> ScorePolicy {
+        ScorePolicy {
+            qieshang_manguan: true,
+            ..ScorePolicy::mahjong_soul()
+        }
+    }
+
+    #[test]
+    fn qieshang_manguan_rounds_up_four_fan_thirty_fu() {
+        assert_eq!(qieshang_manguan().base_points(4, 30, 0).value(), 2_000);
+    }
+
+    #[test]
+    fn qieshang_manguan_rounds_up_three_fan_sixty_fu() {
+        assert_eq!(qieshang_manguan().base_points(3, 60, 0).value(), 2_000);
+    }
+
+    #[test]
+    fn qieshang_manguan_leaves_three_fan_fifty_fu() {
+        assert_eq!(qieshang_manguan().base_points(3, 50, 0).value(), 1_600);
+    }
+
+    #[test]
+    fn fan_limits_follow_the_manguan_to_yiman_boundaries() {
+        let policy = ScorePolicy::mahjong_soul();
+        let limits = [5, 6, 7, 8, 10, 11, 12, 13].map(|fan| {
+            (
+                policy.base_points(fan, 30, 0).limit(),
+                policy.base_points(fan, 30, 0).value(),
+            )
+        });
+
+        assert_eq!(
+            limits,
+            [
+                (Some(ScoreLimit::Manguan), 2_000),
+                (Some(ScoreLimit::Tiaoman), 3_000),
+                (Some(ScoreLimit::Tiaoman), 3_000),
+                (Some(ScoreLimit::Beiman), 4_000),
+                (Some(ScoreLimit::Beiman), 4_000),
+                (Some(ScoreLimit::Sanbeiman), 6_000),
+                (Some(ScoreLimit::Sanbeiman), 6_000),
+                (Some(ScoreLimit::Yiman(1)), 8_000),
+            ]
+        );
+    }
+
+    #[test]
+    fn leiji_yiman_is_capped_at_sanbeiman_when_the_preset_says_so() {
+        let policy = ScorePolicy {
+            leiji_yiman: LeijiYiman::Sanbeiman,
+            ..ScorePolicy::mahjong_soul()
+        };
+
+        assert_eq!(
+            policy.base_points(15, 40, 0).limit(),
+            Some(ScoreLimit::Sanbeiman)
+        );
+    }
+
+    #[test]
+    fn yiman_stack_multiply_the_base_points() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(0, 30, 4).value(),
+            32_000
+        );
+    }
+
+    #[test]
+    fn yiman_stack_up_to_the_preset_maximum() {
+        assert_eq!(
+            ScorePolicy::mahjong_soul().base_points(0, 30, 7).limit(),
+            Some(ScoreLimit::Yiman(6))
+        );
+    }
+
+    #[test]
+    fn single_yiman_preset_caps_double_yiman() {
+        let single = ScorePolicy {
+            max_yiman: 1,
+            ..ScorePolicy::mahjong_soul()
+        };
+
+        assert_eq!(single.base_points(0, 30, 2).value(), 8_000);
+    }
+
+    fn one_fan_thirty_fu() -> BasePoints {
+        ScorePolicy::mahjong_soul().base_points(1, 30, 0)
+    }
+
+    #[test]
+    fn sanjia_rong_payment_is_rounded_up_to_a_hundred() {
+        assert_eq!(one_fan_thirty_fu().rong_payment(false), Score::new(1_000));
+    }
+
+    #[test]
+    fn zhuangjia_rong_payment_is_rounded_up_to_a_hundred() {
+        assert_eq!(one_fan_thirty_fu().rong_payment(true), Score::new(1_500));
+    }
+
+    #[test]
+    fn sanjia_zimo_payment_by_sanjia_is_rounded_up_to_a_hundred() {
+        assert_eq!(
+            one_fan_thirty_fu().zimo_payment(false, false),
+            Score::new(300)
+        );
+    }
+
+    #[test]
+    fn sanjia_zimo_payment_by_zhuangjia_is_rounded_up_to_a_hundred() {
+        assert_eq!(
+            one_fan_thirty_fu().zimo_payment(false, true),
+            Score::new(500)
+        );
+    }
+
+    #[test]
+    fn zhuangjia_zimo_payment_is_rounded_up_to_a_hundred() {
+        assert_eq!(
+            one_fan_thirty_fu().zimo_payment(true, false),
+            Score::new(500)
+        );
+    }
+}
@@ -2,13 +2,15 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+use core::hash::{Hash, Hasher};
 use core::marker::PhantomData;
+use core::ops::Index;
 
 use thiserror::Error;
 
-use crate::player_set::{FourPlayer, PlayerSet};
+use crate::player_set::{FourPlayer, PlayerSet, ThreePlayer};
 
-#[derive(Debug, Eq, Hash, PartialEq)]
+#[derive(Debug)]
 pub struct Seat<P> {
     index: u8,
     player_set: PhantomData<fn() -> P>,
@@ -22,6 +24,20 @@ impl<P> Clone for Seat<P> {
     }
 }
 
+impl<P> PartialEq for Seat<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<P> Eq for Seat<P> {}
+
+impl<P> Hash for Seat<P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 #[error("seat index {index} is out of range for {seat_count} seats")]
 pub struct SeatIndexOutOfRange {
@@ -29,10 +45,7 @@ pub struct SeatIndexOutOfRange {
     pub seat_count: usize,
 }
 
-impl Seat<FourPlayer> {
-    pub const ALL: [Self; FourPlayer::PLAYER_COUNT] =
-        [Self::new(0), Self::new(1), Self::new(2), Self::new(3)];
-
+impl<P> Seat<P> {
     pub(crate) const fn index(self) -> usize {
         self.index as usize
     }
@@ -45,14 +58,67 @@ impl Seat<FourPlayer> {
     }
 }
 
-impl TryFrom<usize> for Seat<FourPlayer> {
+impl<P: PlayerSet> Seat<P> {
+    /// The seat `offset` turns after this one.
+    pub(crate) fn after(self, offset: usize) -> Self {
+        Self::new(((self.index() + offset) % P::PLAYER_COUNT) as u8)
+    }
+}
+
+impl Seat<FourPlayer> {
+    pub const ALL: [Self; FourPlayer::PLAYER_COUNT] =
+        [Self::new(0), Self::new(1), Self::new(2), Self::new(3)];
+}
+
+impl Seat<ThreePlayer> {
+    pub const ALL: [Self; ThreePlayer::PLAYER_COUNT] = [Self::new(0), Self::new(1), Self::new(2)];
+}
+
+impl<P: PlayerSet> TryFrom<usize> for Seat<P> {
     type Error = SeatIndexOutOfRange;
 
     fn try_from(index: usize) -> Result<Self, Self::Error> {
-        Self::ALL.get(index).copied().ok_or(SeatIndexOutOfRange {
-            index,
-            seat_count: Self::ALL.len(),
-        })
+        if index >= P::PLAYER_COUNT {
+            return Err(SeatIndexOutOfRange {
+                index,
+                seat_count: P::PLAYER_COUNT,
+            });
+        }
+        Ok(Self::new(index as u8))
+    }
+}
+
+/// One value for every seat of a player set, in seat order.
+pub trait SeatArray<T>: AsRef<[T]> + AsMut<[T]> + Index<usize, Output = T> + Sized {
+    fn from_fn(f: impl FnMut(usize) -> T) -> Self;
+
+    fn map(self, f: impl FnMut(T) -> T) -> Self;
+
+    /// Replaces the value at `index` with the result of `update`, or returns its error.
+    fn try_update<E>(self, index: usize, update: impl FnOnce(T) -> Result<T, E>)
+    -> Result<Self, E>;
+}
+
+impl<T, const N: usize> SeatArray<T> for [T; N] {
+    fn from_fn(f: impl FnMut(usize) -> T) -> Self {
+        core::array::from_fn(f)
+    }
+
+    fn map(self, f: impl FnMut(T) -> T) -> Self {
+        self.map(f)
+    }
+
+    fn try_update<E>(
+        self,
+        index: usize,
+        update: impl FnOnce(T) -> Result<T, E>,
+    ) -> Result<Self, E> {
+        let mut values = self.map(Some);
+        let value = values[index]
+            .take()
+            .expect("index is within the seat count");
+        values[index] = Some(update(value)?);
+        Ok(values.map(|value| value.expect("every other value is left in place")))
     }
 }
 
@@ -78,4 +144,34 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn three_player_seat_converts_every_valid_index() {
+        assert_eq!(
+            [0, 1, 2].map(|index| Seat::<ThreePlayer>::try_from(index).ok()),
+            Seat::<ThreePlayer>::ALL.map(Some),
+        );
+    }
+
+    #[test]
+    fn three_player_seat_rejects_fourth_index() {
+        assert_eq!(
+            Seat::<ThreePlayer>::try_from(3),
+            Err(SeatIndexOutOfRange {
+                index: 3,
+                seat_count: 3,
+            })
+        );
+    }
+
+    #[test]
+    fn seat_after_wraps_around_the_player_set() {
+        assert_eq!(
+            (
+                Seat::<FourPlayer>::ALL[2].after(2),
+                Seat::<ThreePlayer>::ALL[2].after(1),
+            ),
+            (Seat::<FourPlayer>::ALL[0], Seat::<ThreePlayer>::ALL[0]),
+        );
+    }
 }
@@ -2,18 +2,74 @@
 // SPDX-License-Identifier: MIT
 // This file is part of https://github.com/Apricot-S/lizhisim
 
+mod driver;
+mod progress;
+mod result;
+mod rules;
+mod transfer;
+
+use heapless::Vec;
+
+use crate::bao::Bao;
 use crate::bipai::BipaiSpec;
-use crate::player_set::PlayerSet;
-use crate::round::{Round, RoundEnded, RoundOutcome};
+use crate::player_set::{FourPlayer, PlayerSet};
+use crate::round::{LiujuManguan, Round, RoundEnded, RoundOutcome};
+use crate::score::{BasePoints, HuleScore, MissingSeatShare, Score, ScorePolicy};
 use crate::seat::Seat;
+use crate::tile::TileKind;
+use crate::yaku::YakuValue;
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub use driver::{
+    BetweenRounds, RoundInPlay, RoundSettled, TableMatch, TableMatchError, TableMatchStep,
+};
+pub use progress::{FinishReason, TableMatchProgress};
+pub use result::{
+    LeftoverLizhibang, MatchResultPolicy, Placement, PlacementTie, TableMatchResult,
+    ThousandRounding,
+};
+pub use rules::{Bust, Extension, MatchRules, MatchTerminationPolicy, PingjuLianzhuang};
+pub use transfer::{SettlementError, Transfer, TransferParty, TransferReason, Transfers};
+
+/// The seat count zimo payments are laid out for; smaller tables miss the remaining shares.
+const MAX_PLAYER_COUNT: usize = FourPlayer::PLAYER_COUNT;
+
+/// The rong source's payment for one ben.
+const BEN_RONG_PAYMENT: i32 = 300;
+/// Each payer's share of one ben on a zimo hule.
+const BEN_ZIMO_PAYMENT: i32 = 100;
+
+/// Ordered as played, so a later chang compares greater.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Chang {
     Dong,
     Nan,
     Xi,
+    Bei,
+}
+
+impl Chang {
+    /// The chang that follows once every seat has been zhuangjia, or `None` after the last.
+    pub const fn next(self) -> Option<Self> {
+        match self {
+            Self::Dong => Some(Self::Nan),
+            Self::Nan => Some(Self::Xi),
+            Self::Xi => Some(Self::Bei),
+            Self::Bei => None,
+        }
+    }
+
+    pub(crate) const fn quanfengpai(self) -> TileKind {
+        match self {
+            Self::Dong => TileKind::Z1,
+            Self::Nan => TileKind::Z2,
+            Self::Xi => TileKind::Z3,
+            Self::Bei => TileKind::Z4,
+        }
+    }
 }
 
+/// The round within the chang, counted from 0. Past the last round of the last chang it reaches
+/// the player count, where no round is left to start.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct RoundIndex(u16);
 
@@ -64,8 +120,15 @@ pub struct TableMatchState<P: PlayerSet> {
 }
 
 pub struct RoundSettlement<P: PlayerSet + BipaiSpec> {
-    table_match_state: TableMatchState<P>,
-    round: Round<P, RoundEnded>,
+    /// The settled round, which holds the state for the next round.
+    round: Round<P, RoundEnded<P>>,
+    transfers: Transfers<P>,
+    /// Scores and lizhibang before the transfers, in points.
+    start_total: i32,
+    lianzhuang: bool,
+    /// The chang and round index of the settled round.
+    chang: Chang,
+    round_index: RoundIndex,
 }
 
 impl<P: PlayerSet> TableMatchState<P> {
@@ -107,46 +170,527 @@ impl<P: PlayerSet> TableMatchState<P> {
         self.zhuangjia
     }
 
+    /// The zhuangjia of the first round of every chang, found back from the round index.
+    pub fn qijia(&self) -> Seat<P> {
+        let round_index = usize::from(self.round_index.value()) % P::PLAYER_COUNT;
+        self.zhuangjia.after(P::PLAYER_COUNT - round_index)
+    }
+
     pub fn scores(&self) -> &P::Scores {
         &self.scores
     }
+
+    /// Moves the deposit of the lizhi `seat` just established from its score onto the lizhibang.
+    pub(crate) fn post_lizhi_deposit(&mut self, seat: Seat<P>, deposit: Score) {
+        let score = &mut self.scores.as_mut()[seat.index()];
+        *score = Score::new(score.value() - deposit.value());
+        self.lizhibang = Lizhibang::new(self.lizhibang.value() + 1);
+    }
+}
+
+#[cfg(test)]
+impl<P: PlayerSet> TableMatchState<P> {
+    /// A dong-chang state without ben or lizhibang whose round index makes seat 0 the qijia.
+    pub(crate) fn dong(zhuangjia: Seat<P>, scores: P::Scores) -> Self {
+        Self::new(
+            Chang::Dong,
+            RoundIndex::new(zhuangjia.index() as u16),
+            Ben::new(0),
+            Lizhibang::new(0),
+            zhuangjia,
+            scores,
+        )
+    }
+}
+
+impl<P: PlayerSet + BipaiSpec> Round<P, RoundEnded<P>> {
+    /// Lists the transfers of the round, applies them to the scores and lizhibang of its table
+    /// match state, and moves that state on to the next round under `rules`.
+    ///
+    /// At hule each winner is paid the hule payment and their ben, and one winner takes the
+    /// lizhibang. At huangpai pingju the liuju manguan seats are paid, or without any, the
+    /// buting seats pay the buting fafu to the tingpai seats. Lizhi deposits are already on the
+    /// lizhibang, posted as each lizhi was established.
+    ///
+    /// The ben grows when the zhuangjia stays or the round ends without hule, and is reset
+    /// otherwise. Without lianzhuang the next seat becomes the zhuangjia and the round index
+    /// advances, moving to the next chang after the last seat.
+    ///
+    /// A hule without a reading that has yaku cannot be paid and fails the settlement.
+    pub fn into_settlement(
+        self,
+        rules: &MatchRules,
+    ) -> Result<RoundSettlement<P>, SettlementError> {
+        self.try_into_settlement(rules).map_err(|(_, error)| error)
+    }
+
+    /// Settles the round like [`Round::into_settlement`], handing the round back when the
+    /// settlement fails.
+    #[expect(
+        clippy::result_large_err,
+        reason = "the failed round is handed back so the table match can settle it again"
+    )]
+    pub(crate) fn try_into_settlement(
+        mut self,
+        rules: &MatchRules,
+    ) -> Result<RoundSettlement<P>, (Self, SettlementError)> {
+        let deposit = self.policy().lizhi_deposit;
+        let state = self.table_match_state();
+        let start_total = state.total(deposit);
+        let mut transfers = Transfers::new();
+        if self.round_outcome() == RoundOutcome::Hule
+            && let Err(error) = state.hule_transfers(&self, &mut transfers)
+        {
+            return Err((self, error));
+        }
+        if self.round_outcome() == RoundOutcome::HuangpaiPingju {
+            if self.liuju_manguan_seats().is_empty() {
+                buting_fafu_transfers(&self, &mut transfers);
+            } else {
+                state.liuju_manguan_transfers(&self, &mut transfers);
+            }
+        }
+
+        let lianzhuang = rules.lianzhuang(&self);
+        let pingju = !paid_as_hule(&self);
+        let state = self.table_match_state_mut();
+        for transfer in &transfers {
+            state.apply(transfer.from(), -transfer.amount().value(), deposit);
+            state.apply(transfer.to(), transfer.amount().value(), deposit);
+        }
+        let (chang, round_index) = (state.chang, state.round_index);
+        state.advance(lianzhuang, pingju);
+
+        Ok(RoundSettlement {
+            round: self,
+            transfers,
+            start_total,
+            lianzhuang,
+            chang,
+            round_index,
+        })
+    }
 }
 
 impl<P: PlayerSet + BipaiSpec> TableMatchState<P> {
-    pub fn into_round_settlement(self, round: Round<P, RoundEnded>) -> RoundSettlement<P> {
-        RoundSettlement {
-            table_match_state: self,
-            round,
+    fn advance(&mut self, lianzhuang: bool, pingju: bool) {
+        self.ben = if lianzhuang || pingju {
+            Ben::new(self.ben.value() + 1)
+        } else {
+            Ben::new(0)
+        };
+        if lianzhuang {
+            return;
+        }
+
+        self.zhuangjia = self.zhuangjia.after(1);
+        let round_index = self.round_index.value() + 1;
+        match self.chang.next() {
+            Some(chang) if usize::from(round_index) == P::PLAYER_COUNT => {
+                self.chang = chang;
+                self.round_index = RoundIndex::new(0);
+            }
+            _ => self.round_index = RoundIndex::new(round_index),
+        }
+    }
+
+    /// Pays each winner from the rong source, or from every other seat on zimo, and the ben to
+    /// every winner that takes it; the winner that takes the lizhibang then takes the whole of it.
+    ///
+    /// A liable seat pays the part of the hule it answers for as a rong payment. On rong by
+    /// another seat's dapai it pays half of that part, and on zimo it also pays the whole ben.
+    fn hule_transfers(
+        &self,
+        round: &Round<P, RoundEnded<P>>,
+        transfers: &mut Transfers<P>,
+    ) -> Result<(), SettlementError> {
+        let zhuangjia = *round.zhuangjia();
+        let ben = i32::from(self.ben.value());
+        for hule in round.hule() {
+            let winner = *hule.seat();
+            let score = round
+                .hule_score(hule)
+                .ok_or(SettlementError::UnscoredHule {
+                    index: winner.index(),
+                })?;
+            let (bao, base_points) = split_bao(hule.bao(), score, &round.policy().score);
+            let payers: Vec<Seat<P>, MAX_PLAYER_COUNT> = match hule.rong_source() {
+                Some(source) => Vec::from_iter([*source]),
+                None => round
+                    .players()
+                    .as_ref()
+                    .iter()
+                    .map(|player| *player.seat())
+                    .filter(|&seat| seat != winner)
+                    .collect(),
+            };
+
+            if let Some(base_points) = base_points {
+                match hule.rong_source() {
+                    Some(&source) => push(
+                        transfers,
+                        TransferParty::Seat(source),
+                        TransferParty::Seat(winner),
+                        base_points.rong_payment(winner == zhuangjia).value(),
+                        TransferReason::Hule,
+                    ),
+                    None => {
+                        zimo_transfers(round, transfers, winner, base_points, TransferReason::Hule)
+                    }
+                }
+            }
+
+            if let Some((bao_seat, bao_base_points)) = bao {
+                let payment = bao_base_points.rong_payment(winner == zhuangjia).value();
+                match hule.rong_source() {
+                    Some(&source) if source == bao_seat => push(
+                        transfers,
+                        TransferParty::Seat(source),
+                        TransferParty::Seat(winner),
+                        payment,
+                        TransferReason::Hule,
+                    ),
+                    Some(&source) => {
+                        let half = (payment / 2 + 99) / 100 * 100;
+                        push(
+                            transfers,
+                            TransferParty::Seat(source),
+                            TransferParty::Seat(winner),
+                            payment - half,
+                            TransferReason::Hule,
+                        );
+                        push(
+                            transfers,
+                            TransferParty::Seat(bao_seat),
+                            TransferParty::Seat(winner),
+                            half,
+                            TransferReason::Bao,
+                        );
+                    }
+                    None => push(
+                        transfers,
+                        TransferParty::Seat(bao_seat),
+                        TransferParty::Seat(winner),
+                        payment,
+                        TransferReason::Bao,
+                    ),
+                }
+            }
+
+            if hule.takes_ben() {
+                let (payers, payment) = match (hule.rong_source(), bao) {
+                    (Some(_), _) => (payers, ben * BEN_RONG_PAYMENT),
+                    (None, Some((bao_seat, _))) => {
+                        (Vec::from_iter([bao_seat]), ben * BEN_RONG_PAYMENT)
+                    }
+                    (None, None) => (payers, ben * BEN_ZIMO_PAYMENT),
+                };
+                for payer in payers {
+                    push(
+                        transfers,
+                        TransferParty::Seat(payer),
+                        TransferParty::Seat(winner),
+                        payment,
+                        TransferReason::Ben,
+                    );
+                }
+            }
+        }
+
+        if let Some(hule) = round.hule().iter().find(|hule| hule.takes_lizhibang()) {
+            self.lizhibang_transfer(round, transfers, *hule.seat());
+        }
+        Ok(())
+    }
+
+    /// Pays every liuju manguan seat as a manguan zimo, missing seat share included. In the hule
+    /// mode each payer also pays the ben, and the first eligible seat in seat order takes the
+    /// lizhibang.
+    fn liuju_manguan_transfers(
+        &self,
+        round: &Round<P, RoundEnded<P>>,
+        transfers: &mut Transfers<P>,
+    ) {
+        let hule = round.policy().liuju_manguan == LiujuManguan::Hule;
+
+        for &receiver in round.liuju_manguan_seats() {
+            zimo_transfers(
+                round,
+                transfers,
+                receiver,
+                BasePoints::MANGUAN,
+                TransferReason::LiujuManguan,
+            );
+            if hule {
+                for player in round.players().as_ref() {
+                    let payer = *player.seat();
+                    if payer != receiver {
+                        push(
+                            transfers,
+                            TransferParty::Seat(payer),
+                            TransferParty::Seat(receiver),
+                            i32::from(self.ben.value()) * BEN_ZIMO_PAYMENT,
+                            TransferReason::Ben,
+                        );
+                    }
+                }
+            }
+        }
+
+        if hule && let Some(receiver) = round.liuju_manguan_seats().first() {
+            self.lizhibang_transfer(round, transfers, *receiver);
+        }
+    }
+
+    /// Gives `receiver` the lizhibang on the table, this round's deposits included.
+    fn lizhibang_transfer(
+        &self,
+        round: &Round<P, RoundEnded<P>>,
+        transfers: &mut Transfers<P>,
+        receiver: Seat<P>,
+    ) {
+        push(
+            transfers,
+            TransferParty::Lizhibang,
+            TransferParty::Seat(receiver),
+            i32::from(self.lizhibang.value()) * round.policy().lizhi_deposit.value(),
+            TransferReason::Lizhibang,
+        );
+    }
+
+    fn apply(&mut self, party: TransferParty<P>, amount: i32, deposit: Score) {
+        match party {
+            TransferParty::Seat(seat) => {
+                let score = &mut self.scores.as_mut()[seat.index()];
+                *score = Score::new(score.value() + amount);
+            }
+            TransferParty::Lizhibang => {
+                let count = i32::from(self.lizhibang.value()) + amount / deposit.value();
+                self.lizhibang = Lizhibang::new(count as u16);
+            }
+        }
+    }
+
+    /// Scores and lizhibang together, in points.
+    fn total(&self, deposit: Score) -> i32 {
+        self.scores
+            .as_ref()
+            .iter()
+            .map(|score| score.value())
+            .sum::<i32>()
+            + i32::from(self.lizhibang.value()) * deposit.value()
+    }
+}
+
+/// Whether `round` settles as a hule: by hule, or by liuju manguan in the hule mode.
+fn paid_as_hule<P: PlayerSet + BipaiSpec>(round: &Round<P, RoundEnded<P>>) -> bool {
+    match round.round_outcome() {
+        RoundOutcome::Hule => true,
+        RoundOutcome::HuangpaiPingju => {
+            round.policy().liuju_manguan == LiujuManguan::Hule
+                && !round.liuju_manguan_seats().is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// Has every other seat pay `winner` its share of a zimo of `base_points` for `reason`. When the
+/// policy splits the share a missing seat of a smaller table would have paid, the payers each
+/// add an equal part of it.
+fn zimo_transfers<P: PlayerSet + BipaiSpec>(
+    round: &Round<P, RoundEnded<P>>,
+    transfers: &mut Transfers<P>,
+    winner: Seat<P>,
+    base_points: BasePoints,
+    reason: TransferReason,
+) {
+    let zhuangjia = *round.zhuangjia();
+    let payers: Vec<Seat<P>, MAX_PLAYER_COUNT> = round
+        .players()
+        .as_ref()
+        .iter()
+        .map(|player| *player.seat())
+        .filter(|&seat| seat != winner)
+        .collect();
+    for &payer in &payers {
+        push(
+            transfers,
+            TransferParty::Seat(payer),
+            TransferParty::Seat(winner),
+            base_points
+                .zimo_payment(winner == zhuangjia, payer == zhuangjia)
+                .value(),
+            reason,
+        );
+    }
+
+    if round.policy().score.missing_seat_share == MissingSeatShare::Split {
+        let missing_seat_count = MAX_PLAYER_COUNT - P::PLAYER_COUNT;
+        let share = base_points.zimo_payment(winner == zhuangjia, false).value()
+            * missing_seat_count as i32;
+        let part = (share / payers.len() as i32 + 99) / 100 * 100;
+        for &payer in &payers {
+            push(
+                transfers,
+                TransferParty::Seat(payer),
+                TransferParty::Seat(winner),
+                part,
+                TransferReason::MissingSeatShare,
+            );
+        }
+    }
+}
+
+/// Splits a hule into the liable seat with the base points it answers for, and the base points
+/// paid as usual. A yaku liability covers only the yiman of that yaku, within the preset cap.
+fn split_bao<P>(
+    bao: Option<&Bao<P>>,
+    score: HuleScore,
+    policy: &ScorePolicy,
+) -> (Option<(Seat<P>, BasePoints)>, Option<BasePoints>) {
+    let Some(bao) = bao else {
+        return (None, Some(score.base_points()));
+    };
+    let Some(id) = bao.kind().yaku() else {
+        return (Some((*bao.seat(), score.base_points())), None);
+    };
+    let Some(YakuValue::Yiman(multiple)) = score
+        .yaku()
+        .yaku()
+        .iter()
+        .find(|yaku| yaku.id() == id)
+        .map(|yaku| yaku.value())
+    else {
+        return (None, Some(score.base_points()));
+    };
+
+    let total = score.yaku().yiman().min(policy.max_yiman);
+    let multiple = multiple.min(total);
+    let rest = (total > multiple).then(|| policy.base_points(0, 0, total - multiple));
+    (
+        Some((*bao.seat(), policy.base_points(0, 0, multiple))),
+        rest,
+    )
+}
+
+/// Has every buting seat pay each tingpai seat an equal part of the buting fafu.
+fn buting_fafu_transfers<P: PlayerSet + BipaiSpec>(
+    round: &Round<P, RoundEnded<P>>,
+    transfers: &mut Transfers<P>,
+) {
+    let tingpai_seats = round.tingpai_seats();
+    let buting_count = P::PLAYER_COUNT - tingpai_seats.len();
+    if tingpai_seats.is_empty() || buting_count == 0 {
+        return;
+    }
+    let payment = round.policy().buting_fafu.value() / (tingpai_seats.len() * buting_count) as i32;
+    for player in round.players().as_ref() {
+        if tingpai_seats.contains(player.seat()) {
+            continue;
         }
+        for receiver in tingpai_seats {
+            push(
+                transfers,
+                TransferParty::Seat(*player.seat()),
+                TransferParty::Seat(*receiver),
+                payment,
+                TransferReason::ButingFafu,
+            );
+        }
+    }
+}
+
+/// Records a transfer unless it moves no points.
+fn push<P>(
+    transfers: &mut Transfers<P>,
+    from: TransferParty<P>,
+    to: TransferParty<P>,
+    amount: i32,
+    reason: TransferReason,
+) {
+    if amount > 0 {
+        let _ = transfers.push(Transfer::new(from, to, Score::new(amount), reason));
     }
 }
 
 impl<P: PlayerSet + BipaiSpec> RoundSettlement<P> {
+    /// The state for the next round.
     pub fn table_match_state(&self) -> &TableMatchState<P> {
-        &self.table_match_state
+        self.round.table_match_state()
     }
 
     pub fn round_outcome(&self) -> RoundOutcome {
         self.round.round_outcome()
     }
+
+    /// Whether the zhuangjia stays for the next round.
+    pub fn lianzhuang(&self) -> bool {
+        self.lianzhuang
+    }
+
+    /// Every transfer of the round in the order it was applied.
+    pub fn transfers(&self) -> &[Transfer<P>] {
+        &self.transfers
+    }
+
+    /// Checks that the transfers kept the total of scores and lizhibang.
+    pub fn check_conservation(&self) -> Result<(), SettlementError> {
+        let after = self
+            .table_match_state()
+            .total(self.round.policy().lizhi_deposit);
+        if after == self.start_total {
+            Ok(())
+        } else {
+            Err(SettlementError::NotConserved {
+                before: self.start_total,
+                after,
+            })
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::action::CallResponse;
     use crate::action::Dapai;
+    use crate::bao::{BaoKind, BaoPolicy};
     use crate::bipai::Bipai;
-    use crate::player_set::FourPlayer;
-    use crate::round::{FirstZimoOrigin, NoReactionResult, Round, RoundEnded, RoundOutcome};
-    use crate::score::Score;
-    use crate::tile::TileKind;
+    use crate::player_set::ThreePlayer;
+    use crate::round::{
+        CallWindow, CallWindowResolution, FirstZimoOrigin, LiujuManguan, NoReactionResult,
+        RongResolution, Round, RoundEnded, RoundOutcome, RoundPolicy, ZimoPending,
+    };
     use crate::tile_set::TileSet;
+    use crate::wall_source::WallSource;
+    use crate::yaku::{YakuPolicy, YakuRule};
 
+    use super::driver::RejectedRound;
     use super::*;
 
+    use TileKind::*;
+
+    /// Waits on P1 and P4.
+    const TINGPAI_SEAT0: [TileKind; 13] = [M1, M2, M3, M4, M5, M6, M7, M8, M9, P1, P1, P2, P3];
+    const BUTING_SEAT1: [TileKind; 13] = [M2, M5, M8, P2, P5, P8, S2, S5, S8, Z4, Z4, Z6, Z7];
+    const BUTING_SEAT2: [TileKind; 13] = [M3, M9, P4, P7, S3, S6, S9, Z1, Z2, Z3, Z6, Z7, P9];
+    /// Waits on Z6.
+    const TINGPAI_SEAT2: [TileKind; 13] = [M6, M7, M8, P4, P5, P6, S1, S2, S3, Z5, Z5, Z5, Z6];
+    const BUTING_SEAT3: [TileKind; 13] = [M4, M7, P3, P6, P9, S1, S4, S7, Z1, Z2, Z3, Z5, Z7];
+
     fn scores(values: [i32; FourPlayer::PLAYER_COUNT]) -> [Score; FourPlayer::PLAYER_COUNT] {
         values.map(Score::new)
     }
 
+    /// Settles `round` as a round played in `state`.
+    fn settle_in<P: PlayerSet + BipaiSpec>(
+        state: TableMatchState<P>,
+        mut round: Round<P, RoundEnded<P>>,
+        rules: &MatchRules,
+    ) -> RoundSettlement<P> {
+        *round.table_match_state_mut() = state;
+        round.into_settlement(rules).unwrap()
+    }
+
     fn red_three_tiles() -> ([TileKind; 136], TileSet) {
         let tile_set = TileSet::red_three_four_player();
         let mut tiles = [TileKind::M1; 136];
@@ -162,11 +706,15 @@ mod tests {
         (tiles, tile_set)
     }
 
-    fn huangpai_pingju_round() -> Round<FourPlayer, RoundEnded> {
+    fn huangpai_pingju_round() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
         let (tiles, tile_set) = red_three_tiles();
         let bipai = Bipai::<FourPlayer>::try_new(tiles, tile_set).unwrap();
-        let mut transition =
-            NoReactionResult::NextZimo(Round::new(bipai, Seat::ALL[0], FirstZimoOrigin::LiveWall));
+        let mut transition = NoReactionResult::NextZimo(Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        ));
 
         for _ in 0..70 {
             transition = match transition {
@@ -192,7 +740,7 @@ mod tests {
             RoundIndex::new(0),
             Ben::new(0),
             Lizhibang::new(0),
-            Seat::ALL[0],
+            Seat::<FourPlayer>::ALL[0],
             scores([25_000, 25_000, 25_000, 25_000]),
         );
 
@@ -223,11 +771,1888 @@ mod tests {
             RoundIndex::new(0),
             Ben::new(0),
             Lizhibang::new(0),
-            Seat::ALL[0],
+            Seat::<FourPlayer>::ALL[0],
             scores([25_000; FourPlayer::PLAYER_COUNT]),
         );
-        let settlement = state.into_round_settlement(huangpai_pingju_round());
+        let settlement = settle_in(state, huangpai_pingju_round(), &MatchRules::mahjong_soul());
 
         assert_eq!(settlement.round_outcome(), RoundOutcome::HuangpaiPingju);
     }
+
+    /// Moqies every zimo from the arranged qipai until huangpai pingju.
+    fn arranged_huangpai_pingju_round(
+        qipai: [[TileKind; 13]; 4],
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, &[], &[]);
+        play_moqie(Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        ))
+    }
+
+    /// Moqies every zimo until the round ends.
+    fn play_moqie(
+        round: Round<FourPlayer, ZimoPending>,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let mut transition = NoReactionResult::NextZimo(round);
+        loop {
+            transition = match transition {
+                NoReactionResult::NextZimo(round) => {
+                    let round = round.zimo().unwrap();
+                    let dapai = Dapai::Moqie(round.zimopai());
+                    round.dapai(dapai).unwrap().no_reaction()
+                }
+                NoReactionResult::RoundEnded(round) => return round,
+            };
+        }
+    }
+
+    fn settled_scores(qipai: [[TileKind; 13]; 4]) -> [i32; FourPlayer::PLAYER_COUNT] {
+        let state = TableMatchState::new(
+            Chang::Dong,
+            RoundIndex::new(0),
+            Ben::new(0),
+            Lizhibang::new(0),
+            Seat::<FourPlayer>::ALL[0],
+            scores([25_000; FourPlayer::PLAYER_COUNT]),
+        );
+        let settlement = settle_in(
+            state,
+            arranged_huangpai_pingju_round(qipai),
+            &MatchRules::mahjong_soul(),
+        );
+
+        settlement
+            .table_match_state()
+            .scores()
+            .map(|score| score.value())
+    }
+
+    #[test]
+    fn buting_seats_pay_buting_fafu_to_single_tingpai_seat() {
+        let scores = settled_scores([TINGPAI_SEAT0, BUTING_SEAT1, BUTING_SEAT2, BUTING_SEAT3]);
+
+        assert_eq!(scores, [28_000, 24_000, 24_000, 24_000]);
+    }
+
+    #[test]
+    fn buting_fafu_is_split_between_two_tingpai_seats() {
+        let scores = settled_scores([TINGPAI_SEAT0, BUTING_SEAT1, TINGPAI_SEAT2, BUTING_SEAT3]);
+
+        assert_eq!(scores, [26_500, 23_500, 26_500, 23_500]);
+    }
+
+    /// Non-yaojiu bingpai far from tingpai.
+    const SIMPLE_QIPAI: [[TileKind; 13]; 4] = [
+        [M2, M2, M4, M6, M8, P2, P4, P6, P8, S2, S4, S6, S8],
+        [M3, M3, M5, M7, P3, P5, P7, S3, S5, S7, M2, P2, S2],
+        [M4, M6, M8, P4, P6, P8, S4, S6, S8, M3, P3, S3, M7],
+        [M5, M7, P5, P7, S5, S7, M8, P8, S8, M6, P6, S6, M4],
+    ];
+
+    /// Seat 0 discards only yaojiu tiles until huangpai pingju.
+    fn liuju_manguan_settlement(
+        zhuangjia: usize,
+        liuju_manguan: LiujuManguan,
+    ) -> RoundSettlement<FourPlayer> {
+        let draw_offset = (FourPlayer::PLAYER_COUNT - zhuangjia) % FourPlayer::PLAYER_COUNT;
+        let bipai = Bipai::<FourPlayer>::red_three_yaojiu_zimo(SIMPLE_QIPAI, draw_offset);
+        let policy = RoundPolicy {
+            liuju_manguan,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let mut transition = NoReactionResult::NextZimo(Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[zhuangjia], [Score::new(25_000); 4]),
+        ));
+        let round = loop {
+            transition = match transition {
+                NoReactionResult::NextZimo(round) => {
+                    let round = round.zimo().unwrap();
+                    let dapai = Dapai::Moqie(round.zimopai());
+                    round.dapai(dapai).unwrap().no_reaction()
+                }
+                NoReactionResult::RoundEnded(round) => break round,
+            };
+        };
+
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(1),
+                Lizhibang::new(1),
+                Seat::<FourPlayer>::ALL[zhuangjia],
+                scores([25_000; FourPlayer::PLAYER_COUNT]),
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    #[test]
+    fn hule_liuju_manguan_takes_ben() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::Hule);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [38_300, 20_900, 20_900, 20_900]
+        );
+    }
+
+    #[test]
+    fn hule_liuju_manguan_takes_lizhibang() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::Hule);
+
+        assert_eq!(
+            settlement.table_match_state().lizhibang(),
+            Lizhibang::new(0)
+        );
+    }
+
+    #[test]
+    fn pingju_payment_liuju_manguan_leaves_ben() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::PingjuPayment);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [37_000, 21_000, 21_000, 21_000]
+        );
+    }
+
+    #[test]
+    fn pingju_payment_liuju_manguan_leaves_lizhibang() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::PingjuPayment);
+
+        assert_eq!(
+            settlement.table_match_state().lizhibang(),
+            Lizhibang::new(1)
+        );
+    }
+
+    #[test]
+    fn sanjia_liuju_manguan_is_paid_double_by_zhuangjia() {
+        let settlement = liuju_manguan_settlement(3, LiujuManguan::PingjuPayment);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [33_000, 23_000, 23_000, 21_000]
+        );
+    }
+
+    #[test]
+    fn disabled_liuju_manguan_moves_no_points() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::Disabled);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [25_000; FourPlayer::PLAYER_COUNT]
+        );
+    }
+
+    #[test]
+    fn hule_liuju_manguan_of_a_non_zhuangjia_resets_the_ben_and_passes_the_zhuangjia_on() {
+        let settlement = liuju_manguan_settlement(3, LiujuManguan::Hule);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Dong,
+                RoundIndex::new(1),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(0)
+            )
+        );
+    }
+
+    #[test]
+    fn hule_liuju_manguan_of_the_zhuangjia_keeps_the_zhuangjia() {
+        let settlement = liuju_manguan_settlement(0, LiujuManguan::Hule);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                true,
+                Chang::Dong,
+                RoundIndex::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(2)
+            )
+        );
+    }
+
+    #[test]
+    fn pingju_payment_liuju_manguan_grows_the_ben() {
+        let settlement = liuju_manguan_settlement(3, LiujuManguan::PingjuPayment);
+
+        assert_eq!(settlement.table_match_state().ben(), Ben::new(2));
+    }
+
+    /// Non-yaojiu bingpai of three seats far from tingpai.
+    const THREE_PLAYER_SIMPLE_QIPAI: [[TileKind; 13]; 3] = [
+        [P2, P2, P4, P6, P8, S2, S4, S6, S8, P3, P5, S3, S7],
+        [P3, P3, P5, P7, S3, S5, S7, P2, S2, P4, S4, P6, S6],
+        [P4, P6, P8, S4, S6, S8, P7, S7, P8, S8, P3, S3, S5],
+    ];
+
+    /// Seat 0 of three discards only yaojiu tiles until huangpai pingju, with seat 1 as
+    /// zhuangjia.
+    fn three_player_liuju_manguan_settlement(
+        missing_seat_share: MissingSeatShare,
+    ) -> RoundSettlement<ThreePlayer> {
+        let bipai = Bipai::<ThreePlayer>::red_two_yaojiu_zimo(THREE_PLAYER_SIMPLE_QIPAI, 2);
+        let policy = RoundPolicy {
+            liuju_manguan: LiujuManguan::PingjuPayment,
+            score: ScorePolicy {
+                missing_seat_share,
+                ..ScorePolicy::mahjong_soul()
+            },
+            ..RoundPolicy::mahjong_soul()
+        };
+        let mut transition = NoReactionResult::NextZimo(Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<ThreePlayer>::ALL[1], [Score::new(35_000); 3]),
+        ));
+        let round = loop {
+            transition = match transition {
+                NoReactionResult::NextZimo(round) => {
+                    let round = round.zimo().unwrap();
+                    let dapai = Dapai::Moqie(round.zimopai());
+                    round.dapai(dapai).unwrap().no_reaction()
+                }
+                NoReactionResult::RoundEnded(round) => break round,
+            };
+        };
+
+        round.into_settlement(&MatchRules::mahjong_soul()).unwrap()
+    }
+
+    #[test]
+    fn lost_missing_seat_share_leaves_the_liuju_manguan_short() {
+        let settlement = three_player_liuju_manguan_settlement(MissingSeatShare::Lost);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [41_000, 31_000, 33_000]
+        );
+    }
+
+    #[test]
+    fn split_missing_seat_share_is_added_to_the_liuju_manguan() {
+        let settlement = three_player_liuju_manguan_settlement(MissingSeatShare::Split);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [43_000, 30_000, 32_000]
+        );
+    }
+
+    /// Seat 0 discards M3 into rong by seats 1 and 3 with one ben and one lizhibang on the table.
+    fn double_rong_settlement(rong_resolution: RongResolution) -> RoundSettlement<FourPlayer> {
+        let seat0 = [M3, M7, M8, M9, P1, P2, P3, S1, S2, S3, Z7, Z7, Z7];
+        let seat1 = [M1, M2, P1, P2, P3, P4, P5, P6, P7, P8, P9, Z1, Z1];
+        let seat2 = [M4, M5, S1, S2, S3, S4, S5, S6, S7, S8, S9, Z2, Z2];
+        let seat3 = [M3, M3, Z3, Z3, Z3, Z4, Z4, Z4, Z5, Z5, Z5, Z6, Z6];
+        let bipai =
+            Bipai::<FourPlayer>::red_three_arranged([seat0, seat1, seat2, seat3], &[Z1], &[]);
+        let policy = RoundPolicy {
+            rong_resolution,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let mut window = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Shouqie(M3))
+        .unwrap()
+        .call_window();
+        for (index, response) in [
+            (1, CallResponse::Rong),
+            (2, CallResponse::Pass),
+            (3, CallResponse::Rong),
+        ] {
+            window = window
+                .respond(Seat::<FourPlayer>::ALL[index], response)
+                .unwrap();
+        }
+        let CallWindowResolution::Hule(round) = window.resolve().unwrap() else {
+            panic!("expected hule");
+        };
+
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(1),
+                Lizhibang::new(1),
+                Seat::<FourPlayer>::ALL[0],
+                scores([25_000; 4]),
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    #[test]
+    fn multiple_rong_pays_each_winner_with_ben_and_lizhibang_to_the_first() {
+        let settlement = double_rong_settlement(RongResolution::Multiple);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [5_800, 28_900, 25_000, 41_300]
+        );
+    }
+
+    #[test]
+    fn multiple_rong_clears_the_lizhibang() {
+        let settlement = double_rong_settlement(RongResolution::Multiple);
+
+        assert_eq!(
+            settlement.table_match_state().lizhibang(),
+            Lizhibang::new(0)
+        );
+    }
+
+    #[test]
+    fn first_in_seat_order_pays_only_the_single_winner() {
+        let settlement = double_rong_settlement(RongResolution::FirstInSeatOrder);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [22_100, 28_900, 25_000, 25_000]
+        );
+    }
+
+    #[test]
+    fn rong_transfers_list_each_payment_with_its_reason() {
+        let settlement = double_rong_settlement(RongResolution::Multiple);
+        let seat = |index| TransferParty::Seat(Seat::<FourPlayer>::ALL[index]);
+
+        assert_eq!(
+            settlement.transfers(),
+            [
+                Transfer::new(seat(0), seat(1), Score::new(2_600), TransferReason::Hule),
+                Transfer::new(seat(0), seat(1), Score::new(300), TransferReason::Ben),
+                Transfer::new(seat(0), seat(3), Score::new(16_000), TransferReason::Hule),
+                Transfer::new(seat(0), seat(3), Score::new(300), TransferReason::Ben),
+                Transfer::new(
+                    TransferParty::Lizhibang,
+                    seat(1),
+                    Score::new(1_000),
+                    TransferReason::Lizhibang
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn every_settlement_conserves_scores_and_lizhibang() {
+        let settlements = [
+            double_rong_settlement(RongResolution::Multiple),
+            double_rong_settlement(RongResolution::FirstInSeatOrder),
+            liuju_manguan_settlement(0, LiujuManguan::Hule),
+            liuju_manguan_settlement(3, LiujuManguan::PingjuPayment),
+            dihu_settlement(),
+        ];
+
+        for settlement in settlements {
+            assert_eq!(settlement.check_conservation(), Ok(()));
+        }
+    }
+
+    /// Seat 1 draws Z2 for dihu after seat 0 discards M1.
+    fn dihu_settlement() -> RoundSettlement<FourPlayer> {
+        let qipai = [
+            [M1, M1, M1, M9, M9, M9, P1, P2, P3, P4, P5, P6, P7],
+            [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z1, Z2],
+            [Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, Z7, P9, P9, P9, P8],
+            [M2, M3, M4, M5, M6, M7, M8, P2, P3, P4, P5, P6, Z3],
+        ];
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, &[M1, Z2], &[]);
+        let NoReactionResult::NextZimo(round) = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(M1))
+        .unwrap()
+        .no_reaction() else {
+            panic!("expected the next zimo");
+        };
+        let round = round.zimo().unwrap().zimo_hule().unwrap();
+
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                scores([25_000; 4]),
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    #[test]
+    fn zimo_is_paid_double_by_zhuangjia() {
+        let settlement = dihu_settlement();
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [9_000, 57_000, 17_000, 17_000]
+        );
+    }
+
+    /// Seat 1 of three draws Z2 for dihu after seat 0 discards M1.
+    fn three_player_dihu_settlement(
+        missing_seat_share: MissingSeatShare,
+    ) -> RoundSettlement<ThreePlayer> {
+        let qipai = [
+            [M1, M1, M1, M9, M9, M9, P1, P2, P3, P4, P5, P6, P7],
+            [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z1, Z2],
+            [Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, Z7, P9, P9, P9, P8],
+        ];
+        let bipai = Bipai::<ThreePlayer>::red_two_arranged(qipai, &[M1, Z2], &[]);
+        let policy = RoundPolicy {
+            score: ScorePolicy {
+                missing_seat_share,
+                ..ScorePolicy::mahjong_soul()
+            },
+            ..RoundPolicy::mahjong_soul()
+        };
+        let NoReactionResult::NextZimo(round) = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<ThreePlayer>::ALL[0], [Score::new(35_000); 3]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(M1))
+        .unwrap()
+        .no_reaction() else {
+            panic!("expected the next zimo");
+        };
+        let round = round.zimo().unwrap().zimo_hule().unwrap();
+
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                Seat::<ThreePlayer>::ALL[0],
+                [Score::new(35_000); 3],
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    #[test]
+    fn lost_missing_seat_share_leaves_the_winner_short() {
+        let settlement = three_player_dihu_settlement(MissingSeatShare::Lost);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [19_000, 59_000, 27_000]
+        );
+    }
+
+    #[test]
+    fn lost_missing_seat_share_conserves_scores() {
+        let settlement = three_player_dihu_settlement(MissingSeatShare::Lost);
+
+        assert_eq!(settlement.check_conservation(), Ok(()));
+    }
+
+    #[test]
+    fn split_missing_seat_share_is_paid_by_both_payers() {
+        let settlement = three_player_dihu_settlement(MissingSeatShare::Split);
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [15_000, 67_000, 23_000]
+        );
+    }
+
+    #[test]
+    fn split_missing_seat_share_is_listed_as_its_own_transfers() {
+        let settlement = three_player_dihu_settlement(MissingSeatShare::Split);
+
+        assert_eq!(
+            settlement
+                .transfers()
+                .iter()
+                .filter(|transfer| transfer.reason() == TransferReason::MissingSeatShare)
+                .map(|transfer| transfer.amount().value())
+                .collect::<std::vec::Vec<_>>(),
+            [4_000, 4_000]
+        );
+    }
+
+    #[test]
+    fn split_missing_seat_share_conserves_scores() {
+        let settlement = three_player_dihu_settlement(MissingSeatShare::Split);
+
+        assert_eq!(settlement.check_conservation(), Ok(()));
+    }
+
+    /// Answers `response` for `seat` and passes every other pending seat.
+    fn answer(
+        window: Round<FourPlayer, CallWindow<FourPlayer>>,
+        seat: usize,
+        response: CallResponse,
+    ) -> CallWindowResolution<FourPlayer> {
+        let pending = window
+            .pending_seats()
+            .copied()
+            .collect::<std::vec::Vec<_>>();
+        pending
+            .into_iter()
+            .fold(window, |window, pending| {
+                let response = if pending == Seat::<FourPlayer>::ALL[seat] {
+                    response
+                } else {
+                    CallResponse::Pass
+                };
+                window.respond(pending, response).unwrap()
+            })
+            .resolve()
+            .unwrap()
+    }
+
+    fn next_zimo(resolution: CallWindowResolution<FourPlayer>) -> Round<FourPlayer, ZimoPending> {
+        let CallWindowResolution::NoCall(NoReactionResult::NextZimo(round)) = resolution else {
+            panic!("expected the next zimo");
+        };
+        round
+    }
+
+    /// Seat 1 pengs Z5 from seat 0, then Z6 and Z7 from seat 2, and waits on S4 and S7.
+    fn dasanyuan_tingpai(zimo: &[TileKind]) -> Round<FourPlayer, ZimoPending> {
+        let qipai = [
+            [M4, M5, M6, M7, M8, M9, P2, P3, P4, P5, P6, P7, P8],
+            [Z5, Z5, Z6, Z6, Z7, Z7, M1, M2, M3, P1, P1, S5, S6],
+            [S1, S1, S2, S2, S3, S3, M4, M5, M6, P4, P5, P6, Z1],
+            [S7, S8, S9, M7, M8, M9, P7, P8, P9, Z2, Z3, Z4, Z1],
+        ];
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, zimo, &[]);
+        let mut round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        );
+        for (sanyuanpai, dapai) in [(Z5, M1), (Z6, M2), (Z7, M3)] {
+            let window = round
+                .zimo()
+                .unwrap()
+                .dapai(Dapai::Moqie(sanyuanpai))
+                .unwrap()
+                .call_window();
+            let peng = CallResponse::Peng {
+                bingpai_tiles: [sanyuanpai; 2],
+            };
+            let CallWindowResolution::Fulu(fulu) = answer(window, 1, peng) else {
+                panic!("expected peng");
+            };
+            let window = fulu.dapai(Dapai::Shouqie(dapai)).unwrap().call_window();
+            round = next_zimo(answer(window, 1, CallResponse::Pass));
+        }
+        round
+    }
+
+    fn settle(round: Round<FourPlayer, RoundEnded<FourPlayer>>) -> RoundSettlement<FourPlayer> {
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                scores([25_000; 4]),
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    /// Seat 3 discards S4 into the dasanyuan of seat 1, who rongs it.
+    fn dasanyuan_rong() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let round = dasanyuan_tingpai(&[Z5, Z6, Z7, M1, S4]);
+        let window = round
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(M1))
+            .unwrap()
+            .call_window();
+        let window = next_zimo(answer(window, 1, CallResponse::Pass))
+            .zimo()
+            .unwrap()
+            .dapai(Dapai::Moqie(S4))
+            .unwrap()
+            .call_window();
+        let CallWindowResolution::Hule(round) = answer(window, 1, CallResponse::Rong) else {
+            panic!("expected hule");
+        };
+        round
+    }
+
+    #[test]
+    fn rong_on_bao_hule_records_the_liable_seat() {
+        let round = dasanyuan_rong();
+        let bao = round.hule()[0].bao().copied().unwrap();
+
+        assert_eq!(
+            (bao.kind(), *bao.seat()),
+            (BaoKind::Dasanyuan, Seat::<FourPlayer>::ALL[2])
+        );
+    }
+
+    #[test]
+    fn rong_on_bao_hule_is_split_between_source_and_liable_seat() {
+        let settlement = settle(dasanyuan_rong());
+        let seat = |index| TransferParty::Seat(Seat::<FourPlayer>::ALL[index]);
+
+        assert_eq!(
+            settlement.transfers(),
+            [
+                Transfer::new(seat(3), seat(1), Score::new(16_000), TransferReason::Hule),
+                Transfer::new(seat(2), seat(1), Score::new(16_000), TransferReason::Bao),
+            ]
+        );
+    }
+
+    #[test]
+    fn rong_on_bao_hule_conserves_scores() {
+        assert_eq!(settle(dasanyuan_rong()).check_conservation(), Ok(()));
+    }
+
+    /// Seat 1 draws S4 for dasanyuan after the other three seats discard.
+    fn dasanyuan_zimo_settlement() -> RoundSettlement<FourPlayer> {
+        let mut round = dasanyuan_tingpai(&[Z5, Z6, Z7, M1, Z2, Z3, S4]);
+        for dapai in [M1, Z2, Z3] {
+            let window = round
+                .zimo()
+                .unwrap()
+                .dapai(Dapai::Moqie(dapai))
+                .unwrap()
+                .call_window();
+            round = next_zimo(answer(window, 1, CallResponse::Pass));
+        }
+        settle(round.zimo().unwrap().zimo_hule().unwrap())
+    }
+
+    #[test]
+    fn zimo_on_bao_hule_is_paid_by_the_liable_seat_alone() {
+        assert_eq!(
+            dasanyuan_zimo_settlement()
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [25_000, 57_000, -7_000, 25_000]
+        );
+    }
+
+    #[test]
+    fn zimo_on_bao_hule_conserves_scores() {
+        assert_eq!(dasanyuan_zimo_settlement().check_conservation(), Ok(()));
+    }
+
+    /// Seat 1 takes M1 from seat 0 by daminggang and completes its hand on the lingshang S9.
+    fn daminggang_lingshang_settlement(bao: BaoPolicy) -> RoundSettlement<FourPlayer> {
+        let qipai = [
+            [M4, M5, M6, M7, M8, M9, P2, P3, P4, P5, P6, P7, P8],
+            [M1, M1, M1, P1, P1, S1, S2, S3, S4, S5, S6, S7, S8],
+            [S1, S1, S2, S2, S3, S3, M4, M5, M6, P4, P5, P6, Z1],
+            [S7, S8, S9, M7, M8, M9, P7, P8, P9, Z2, Z3, Z4, Z1],
+        ];
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, &[M1], &[S9]);
+        let policy = RoundPolicy {
+            bao,
+            ..RoundPolicy::mahjong_soul()
+        };
+        let window = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            policy,
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .dapai(Dapai::Moqie(M1))
+        .unwrap()
+        .call_window();
+        let daminggang = CallResponse::Daminggang {
+            bingpai_tiles: [M1; 3],
+        };
+        let CallWindowResolution::Daminggang(round) = answer(window, 1, daminggang) else {
+            panic!("expected daminggang");
+        };
+
+        settle(round.zimo_hule().unwrap())
+    }
+
+    #[test]
+    fn daminggang_lingshang_bao_turns_zimo_into_a_payment_by_the_source() {
+        let settlement = daminggang_lingshang_settlement(BaoPolicy {
+            daminggang_lingshang: true,
+            ..BaoPolicy::mahjong_soul()
+        });
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [22_400, 27_600, 25_000, 25_000]
+        );
+    }
+
+    #[test]
+    fn daminggang_lingshang_zimo_is_shared_unless_the_preset_adds_bao() {
+        let settlement = daminggang_lingshang_settlement(BaoPolicy::mahjong_soul());
+
+        assert_eq!(
+            settlement
+                .table_match_state()
+                .scores()
+                .map(|score| score.value()),
+            [23_700, 27_700, 24_300, 24_300]
+        );
+    }
+
+    fn next_round(
+        settlement: &RoundSettlement<FourPlayer>,
+    ) -> (bool, Chang, RoundIndex, Seat<FourPlayer>, Ben) {
+        let state = settlement.table_match_state();
+        (
+            settlement.lianzhuang(),
+            state.chang(),
+            state.round_index(),
+            state.zhuangjia(),
+            state.ben(),
+        )
+    }
+
+    fn pingju_settlement(rules: MatchRules) -> RoundSettlement<FourPlayer> {
+        let round = arranged_huangpai_pingju_round([
+            TINGPAI_SEAT0,
+            BUTING_SEAT1,
+            BUTING_SEAT2,
+            BUTING_SEAT3,
+        ]);
+        settle_in(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                scores([25_000; 4]),
+            ),
+            round,
+            &rules,
+        )
+    }
+
+    #[test]
+    fn tingpai_zhuangjia_stays_and_ben_grows_at_huangpai_pingju() {
+        let settlement = pingju_settlement(MatchRules::mahjong_soul());
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                true,
+                Chang::Dong,
+                RoundIndex::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(1)
+            )
+        );
+    }
+
+    #[test]
+    fn pingju_without_lianzhuang_passes_zhuangjia_on_and_still_grows_ben() {
+        let settlement = pingju_settlement(MatchRules {
+            pingju_lianzhuang: PingjuLianzhuang::Never,
+            ..MatchRules::mahjong_soul()
+        });
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Dong,
+                RoundIndex::new(1),
+                Seat::<FourPlayer>::ALL[1],
+                Ben::new(1)
+            )
+        );
+    }
+
+    #[test]
+    fn sanjia_hule_passes_zhuangjia_on_and_resets_ben() {
+        let settlement = double_rong_settlement(RongResolution::Multiple);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Dong,
+                RoundIndex::new(1),
+                Seat::<FourPlayer>::ALL[1],
+                Ben::new(0)
+            )
+        );
+    }
+
+    /// The first zimo of `winner` completes its hand after every seat before it moqies Z4.
+    fn first_zimo_hule(
+        zhuangjia: usize,
+        winner: usize,
+    ) -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let mut qipai = [
+            [M1, M1, M1, M9, M9, M9, P1, P2, P3, P4, P5, P6, P7],
+            [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z1, Z2],
+            [Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, Z7, P9, P9, P9, P8],
+            [M2, M3, M4, M5, M6, M7, M8, P2, P3, P4, P5, P6, Z3],
+        ];
+        let moqie_count =
+            (winner + FourPlayer::PLAYER_COUNT - zhuangjia) % FourPlayer::PLAYER_COUNT;
+        // Qipai is dealt from the zhuangjia, so the winner's hand sits at its draw offset.
+        qipai.swap(1, moqie_count);
+        let zimo = core::iter::repeat_n(Z4, moqie_count)
+            .chain([Z2])
+            .collect::<std::vec::Vec<_>>();
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(qipai, &zimo, &[]);
+        let mut round = Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[zhuangjia], [Score::new(25_000); 4]),
+        );
+        for _ in 0..moqie_count {
+            round = round
+                .zimo()
+                .unwrap()
+                .dapai(Dapai::Moqie(Z4))
+                .unwrap()
+                .no_reaction()
+                .into_next_zimo_pending()
+                .unwrap();
+        }
+
+        round.zimo().unwrap().zimo_hule().unwrap()
+    }
+
+    fn settle_at(
+        round: Round<FourPlayer, RoundEnded<FourPlayer>>,
+        chang: Chang,
+        round_index: u16,
+        ben: u16,
+    ) -> RoundSettlement<FourPlayer> {
+        let zhuangjia = *round.zhuangjia();
+        settle_in(
+            TableMatchState::new(
+                chang,
+                RoundIndex::new(round_index),
+                Ben::new(ben),
+                Lizhibang::new(0),
+                zhuangjia,
+                scores([25_000; 4]),
+            ),
+            round,
+            &MatchRules::mahjong_soul(),
+        )
+    }
+
+    #[test]
+    fn hule_without_yaku_fails_the_settlement() {
+        let mut round = first_zimo_hule(0, 1);
+        round.policy_mut().yaku = YakuPolicy::from_fn(|_| YakuRule::DISABLED);
+
+        assert_eq!(
+            round.into_settlement(&MatchRules::mahjong_soul()).err(),
+            Some(SettlementError::UnscoredHule { index: 1 })
+        );
+    }
+
+    #[test]
+    fn zhuangjia_hule_keeps_zhuangjia_and_grows_ben() {
+        let settlement = settle_at(first_zimo_hule(1, 1), Chang::Dong, 1, 2);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                true,
+                Chang::Dong,
+                RoundIndex::new(1),
+                Seat::<FourPlayer>::ALL[1],
+                Ben::new(3)
+            )
+        );
+    }
+
+    #[test]
+    fn last_seat_passing_zhuangjia_on_moves_to_the_next_chang() {
+        let settlement = settle_at(first_zimo_hule(3, 0), Chang::Dong, 3, 1);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Nan,
+                RoundIndex::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(0)
+            )
+        );
+    }
+
+    #[test]
+    fn round_index_reaches_the_player_count_after_the_last_chang() {
+        let settlement = settle_at(first_zimo_hule(3, 0), Chang::Bei, 3, 0);
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Bei,
+                RoundIndex::new(4),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(0)
+            )
+        );
+    }
+
+    /// Seat 0 declares jiuzhong jiupai on its first zimo.
+    fn jiuzhong_jiupai_round() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        let seat0 = [M1, M9, P1, P9, S1, S9, Z1, Z2, Z3, M2, M3, M4, M5];
+        let bipai = Bipai::<FourPlayer>::red_three_arranged(
+            [seat0, BUTING_SEAT1, BUTING_SEAT2, BUTING_SEAT3],
+            &[],
+            &[],
+        );
+        Round::new(
+            bipai,
+            FirstZimoOrigin::LiveWall,
+            RoundPolicy::mahjong_soul(),
+            TableMatchState::dong(Seat::<FourPlayer>::ALL[0], [Score::new(25_000); 4]),
+        )
+        .zimo()
+        .unwrap()
+        .jiuzhong_jiupai()
+        .unwrap()
+    }
+
+    fn abortive_draw_settlement(rules: MatchRules) -> RoundSettlement<FourPlayer> {
+        let state = TableMatchState::new(
+            Chang::Dong,
+            RoundIndex::new(0),
+            Ben::new(0),
+            Lizhibang::new(0),
+            Seat::<FourPlayer>::ALL[0],
+            scores([25_000; 4]),
+        );
+        settle_in(state, jiuzhong_jiupai_round(), &rules)
+    }
+
+    #[test]
+    fn abortive_draw_keeps_zhuangjia_under_the_rules() {
+        let settlement = abortive_draw_settlement(MatchRules::mahjong_soul());
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                true,
+                Chang::Dong,
+                RoundIndex::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                Ben::new(1)
+            )
+        );
+    }
+
+    #[test]
+    fn abortive_draw_without_lianzhuang_passes_zhuangjia_on() {
+        let settlement = abortive_draw_settlement(MatchRules {
+            liuju_lianzhuang: false,
+            ..MatchRules::mahjong_soul()
+        });
+
+        assert_eq!(
+            next_round(&settlement),
+            (
+                false,
+                Chang::Dong,
+                RoundIndex::new(1),
+                Seat::<FourPlayer>::ALL[1],
+                Ben::new(1)
+            )
+        );
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Progress {
+        Continue(Chang, RoundIndex),
+        Extend(Chang, RoundIndex),
+        Finish(FinishReason),
+    }
+
+    fn progress_at(
+        round: Round<FourPlayer, RoundEnded<FourPlayer>>,
+        chang: Chang,
+        round_index: u16,
+        start_scores: [i32; FourPlayer::PLAYER_COUNT],
+        rules: MatchRules,
+    ) -> Progress {
+        let zhuangjia = *round.zhuangjia();
+        let progress = settle_in(
+            TableMatchState::new(
+                chang,
+                RoundIndex::new(round_index),
+                Ben::new(0),
+                Lizhibang::new(0),
+                zhuangjia,
+                scores(start_scores),
+            ),
+            round,
+            &rules,
+        )
+        .into_progress(&rules);
+
+        match progress {
+            TableMatchProgress::Continue(state) => {
+                Progress::Continue(state.chang(), state.round_index())
+            }
+            TableMatchProgress::Extend(state) => {
+                Progress::Extend(state.chang(), state.round_index())
+            }
+            TableMatchProgress::Finish(_, reason) => Progress::Finish(reason),
+        }
+    }
+
+    fn terminating(termination: MatchTerminationPolicy) -> MatchRules {
+        MatchRules {
+            termination,
+            ..MatchRules::mahjong_soul()
+        }
+    }
+
+    fn pingju_round() -> Round<FourPlayer, RoundEnded<FourPlayer>> {
+        arranged_huangpai_pingju_round([TINGPAI_SEAT0, BUTING_SEAT1, BUTING_SEAT2, BUTING_SEAT3])
+    }
+
+    /// Leaves seat 1 at exactly zero after it pays for buting in the pingju.
+    const ONE_THOUSAND_SHORT: [i32; FourPlayer::PLAYER_COUNT] = [25_000, 1_000, 37_000, 37_000];
+
+    #[test]
+    fn zero_score_does_not_bust_by_default() {
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Dong,
+                0,
+                ONE_THOUSAND_SHORT,
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Continue(Chang::Dong, RoundIndex::new(0))
+        );
+    }
+
+    #[test]
+    fn zero_score_busts_when_the_rules_count_it() {
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Dong,
+                0,
+                ONE_THOUSAND_SHORT,
+                terminating(MatchTerminationPolicy {
+                    bust: Bust::ZeroOrBelow,
+                    ..MatchRules::mahjong_soul().termination
+                })
+            ),
+            Progress::Finish(FinishReason::Bust)
+        );
+    }
+
+    fn passing_on() -> MatchRules {
+        MatchRules {
+            pingju_lianzhuang: PingjuLianzhuang::Never,
+            ..MatchRules::mahjong_soul()
+        }
+    }
+
+    #[test]
+    fn last_regulation_round_ends_the_match_once_the_target_is_reached() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 0),
+                Chang::Nan,
+                3,
+                [25_000; 4],
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Finish(FinishReason::RegulationEnded)
+        );
+    }
+
+    #[test]
+    fn last_regulation_round_extends_the_match_below_the_target() {
+        assert_eq!(
+            progress_at(pingju_round(), Chang::Nan, 3, [25_000; 4], passing_on()),
+            Progress::Extend(Chang::Xi, RoundIndex::new(0))
+        );
+    }
+
+    #[test]
+    fn last_regulation_round_ends_the_match_below_the_target_without_extension() {
+        let passing_on = passing_on();
+
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Nan,
+                3,
+                [25_000; 4],
+                MatchRules {
+                    termination: MatchTerminationPolicy {
+                        extension: None,
+                        ..passing_on.termination
+                    },
+                    ..passing_on
+                }
+            ),
+            Progress::Finish(FinishReason::RegulationEnded)
+        );
+    }
+
+    #[test]
+    fn last_regulation_round_ends_the_match_below_the_target_without_an_extension_chang() {
+        let passing_on = passing_on();
+
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Nan,
+                3,
+                [25_000; 4],
+                MatchRules {
+                    termination: MatchTerminationPolicy {
+                        extension: Some(Extension {
+                            last_chang: Chang::Nan,
+                            ..passing_on.termination.extension.unwrap()
+                        }),
+                        ..passing_on.termination
+                    },
+                    ..passing_on
+                }
+            ),
+            Progress::Finish(FinishReason::RegulationEnded)
+        );
+    }
+
+    #[test]
+    fn dongfeng_regulation_ends_after_the_dong_chang() {
+        let mahjong_soul = MatchRules::mahjong_soul().termination;
+        let dongfeng = terminating(MatchTerminationPolicy {
+            last_chang: Chang::Dong,
+            extension: Some(Extension {
+                last_chang: Chang::Nan,
+                ..mahjong_soul.extension.unwrap()
+            }),
+            ..mahjong_soul
+        });
+
+        assert_eq!(
+            progress_at(first_zimo_hule(3, 0), Chang::Dong, 3, [25_000; 4], dongfeng),
+            Progress::Finish(FinishReason::RegulationEnded)
+        );
+    }
+
+    #[test]
+    fn default_regulation_continues_into_the_nan_chang() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 0),
+                Chang::Dong,
+                3,
+                [25_000; 4],
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Continue(Chang::Nan, RoundIndex::new(0))
+        );
+    }
+
+    fn without_sudden_death() -> MatchRules {
+        let mahjong_soul = MatchRules::mahjong_soul().termination;
+
+        terminating(MatchTerminationPolicy {
+            extension: Some(Extension {
+                sudden_death: false,
+                ..mahjong_soul.extension.unwrap()
+            }),
+            ..mahjong_soul
+        })
+    }
+
+    #[test]
+    fn sudden_death_ends_any_extension_round_that_reaches_the_target() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(1, 0),
+                Chang::Xi,
+                0,
+                [25_000; 4],
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Finish(FinishReason::TargetReached)
+        );
+    }
+
+    #[test]
+    fn extension_round_continues_past_the_target_without_sudden_death() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(1, 0),
+                Chang::Xi,
+                0,
+                [25_000; 4],
+                without_sudden_death()
+            ),
+            Progress::Extend(Chang::Xi, RoundIndex::new(1))
+        );
+    }
+
+    #[test]
+    fn last_extension_round_reaching_the_target_ends_the_match_without_sudden_death() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 0),
+                Chang::Xi,
+                3,
+                [25_000; 4],
+                without_sudden_death()
+            ),
+            Progress::Finish(FinishReason::TargetReached)
+        );
+    }
+
+    #[test]
+    fn last_extension_round_ends_the_match_below_the_target() {
+        assert_eq!(
+            progress_at(pingju_round(), Chang::Xi, 3, [25_000; 4], passing_on()),
+            Progress::Finish(FinishReason::ExtensionEnded)
+        );
+    }
+
+    #[test]
+    fn later_extension_chang_continues_the_match_below_the_target() {
+        let passing_on = passing_on();
+
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Xi,
+                3,
+                [25_000; 4],
+                MatchRules {
+                    termination: MatchTerminationPolicy {
+                        extension: Some(Extension {
+                            last_chang: Chang::Bei,
+                            ..passing_on.termination.extension.unwrap()
+                        }),
+                        ..passing_on.termination
+                    },
+                    ..passing_on
+                }
+            ),
+            Progress::Extend(Chang::Bei, RoundIndex::new(0))
+        );
+    }
+
+    #[test]
+    fn final_zhuangjia_hule_alone_at_the_top_stops_the_match() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 3),
+                Chang::Nan,
+                3,
+                [25_000; 4],
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Finish(FinishReason::FinalHuleStop)
+        );
+    }
+
+    #[test]
+    fn final_zhuangjia_hule_continues_without_the_stop_rule() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 3),
+                Chang::Nan,
+                3,
+                [25_000; 4],
+                terminating(MatchTerminationPolicy {
+                    final_hule_stop: false,
+                    ..MatchRules::mahjong_soul().termination
+                })
+            ),
+            Progress::Continue(Chang::Nan, RoundIndex::new(3))
+        );
+    }
+
+    #[test]
+    fn final_zhuangjia_hule_tied_at_the_top_continues() {
+        assert_eq!(
+            progress_at(
+                first_zimo_hule(3, 3),
+                Chang::Nan,
+                3,
+                [89_000, 17_000, 17_000, 25_000],
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Continue(Chang::Nan, RoundIndex::new(3))
+        );
+    }
+
+    /// Seat 0 is the last zhuangjia when seat 1 was the qijia.
+    const FINAL_TINGPAI_START: [i32; FourPlayer::PLAYER_COUNT] = [30_000, 25_000, 25_000, 20_000];
+
+    #[test]
+    fn final_tingpai_zhuangjia_alone_at_the_top_stops_the_match() {
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Nan,
+                3,
+                FINAL_TINGPAI_START,
+                MatchRules::mahjong_soul()
+            ),
+            Progress::Finish(FinishReason::FinalTingpaiStop)
+        );
+    }
+
+    #[test]
+    fn final_tingpai_zhuangjia_continues_without_the_stop_rule() {
+        assert_eq!(
+            progress_at(
+                pingju_round(),
+                Chang::Nan,
+                3,
+                FINAL_TINGPAI_START,
+                terminating(MatchTerminationPolicy {
+                    final_tingpai_stop: false,
+                    ..MatchRules::mahjong_soul().termination
+                })
+            ),
+            Progress::Continue(Chang::Nan, RoundIndex::new(3))
+        );
+    }
+
+    fn final_state(
+        round_index: u16,
+        zhuangjia: usize,
+        lizhibang: u16,
+        final_scores: [i32; FourPlayer::PLAYER_COUNT],
+    ) -> TableMatchState<FourPlayer> {
+        TableMatchState::new(
+            Chang::Xi,
+            RoundIndex::new(round_index),
+            Ben::new(0),
+            Lizhibang::new(lizhibang),
+            Seat::<FourPlayer>::ALL[zhuangjia],
+            scores(final_scores),
+        )
+    }
+
+    fn placements(result: &TableMatchResult<FourPlayer>) -> [u8; FourPlayer::PLAYER_COUNT] {
+        result.placements().map(|placement| placement.value())
+    }
+
+    fn points(result: &TableMatchResult<FourPlayer>) -> [i32; FourPlayer::PLAYER_COUNT] {
+        result.points().map(|points| points.value())
+    }
+
+    #[test]
+    fn qijia_is_found_back_from_the_round_index() {
+        assert_eq!(
+            final_state(3, 0, 0, [25_000; 4]).qijia(),
+            Seat::<FourPlayer>::ALL[1]
+        );
+    }
+
+    #[test]
+    fn qijia_is_the_zhuangjia_of_the_first_round() {
+        assert_eq!(
+            final_state(0, 2, 0, [25_000; 4]).qijia(),
+            Seat::<FourPlayer>::ALL[2]
+        );
+    }
+
+    #[test]
+    fn qijia_is_found_back_past_the_last_round_index() {
+        assert_eq!(
+            final_state(4, 1, 0, [25_000; 4]).qijia(),
+            Seat::<FourPlayer>::ALL[1]
+        );
+    }
+
+    fn returning_at_30_000(tie: PlacementTie) -> MatchResultPolicy<FourPlayer> {
+        MatchResultPolicy {
+            return_score: Score::new(30_000),
+            placement_points: scores([20_000, 10_000, -10_000, -20_000]),
+            tie,
+            ..MatchResultPolicy::mahjong_soul()
+        }
+    }
+
+    /// Seats 0 and 2 tie at the top and seats 1 and 3 at the bottom, with seat 2 the qijia.
+    fn tied_result(tie: PlacementTie) -> TableMatchResult<FourPlayer> {
+        final_state(0, 2, 1, [30_000, 19_500, 30_000, 19_500])
+            .into_result(&returning_at_30_000(tie), Score::new(1_000))
+    }
+
+    #[test]
+    fn result_keeps_the_final_scores() {
+        assert_eq!(
+            tied_result(PlacementTie::QijiaOrder).scores(),
+            &scores([30_000, 19_500, 30_000, 19_500])
+        );
+    }
+
+    #[test]
+    fn qijia_order_places_tied_seats() {
+        assert_eq!(
+            placements(&tied_result(PlacementTie::QijiaOrder)),
+            [2, 4, 1, 3]
+        );
+    }
+
+    #[test]
+    fn qijia_order_gives_the_top_its_bonus_and_lizhibang() {
+        assert_eq!(
+            points(&tied_result(PlacementTie::QijiaOrder)),
+            [10_000, -30_500, 41_000, -20_500]
+        );
+    }
+
+    #[test]
+    fn shared_placements_place_tied_seats_together() {
+        assert_eq!(placements(&tied_result(PlacementTie::Shared)), [1, 3, 1, 3]);
+    }
+
+    #[test]
+    fn shared_placements_split_every_placement_they_cover() {
+        assert_eq!(
+            points(&tied_result(PlacementTie::Shared)),
+            [25_500, -25_500, 25_500, -25_500]
+        );
+    }
+
+    /// Seats 0, 1 and 2 share the top three placements, with seat 1 the qijia.
+    fn three_way_tie_result() -> TableMatchResult<FourPlayer> {
+        final_state(0, 1, 0, [30_000, 30_000, 30_000, 10_000]).into_result(
+            &MatchResultPolicy {
+                placement_points: scores([20_000, 10_000, -10_000, -20_000]),
+                tie: PlacementTie::Shared,
+                ..MatchResultPolicy::mahjong_soul()
+            },
+            Score::new(1_000),
+        )
+    }
+
+    #[test]
+    fn three_way_tie_shares_the_top_placement() {
+        assert_eq!(placements(&three_way_tie_result()), [1, 1, 1, 4]);
+    }
+
+    #[test]
+    fn split_remainder_goes_to_the_seats_nearest_the_qijia() {
+        assert_eq!(
+            points(&three_way_tie_result()),
+            [11_666, 11_667, 11_667, -35_000]
+        );
+    }
+
+    fn leftover_lizhibang_result(
+        leftover_lizhibang: LeftoverLizhibang,
+    ) -> TableMatchResult<FourPlayer> {
+        final_state(0, 0, 2, [40_000, 30_000, 20_000, 8_000]).into_result(
+            &MatchResultPolicy {
+                leftover_lizhibang,
+                ..MatchResultPolicy::mahjong_soul()
+            },
+            Score::new(1_000),
+        )
+    }
+
+    #[test]
+    fn leftover_lizhibang_goes_to_the_top() {
+        assert_eq!(
+            points(&leftover_lizhibang_result(LeftoverLizhibang::Top)),
+            [32_000, 10_000, -10_000, -32_000]
+        );
+    }
+
+    #[test]
+    fn discarded_lizhibang_is_left_out_of_the_points() {
+        assert_eq!(
+            points(&leftover_lizhibang_result(LeftoverLizhibang::Discarded)),
+            [30_000, 10_000, -10_000, -32_000]
+        );
+    }
+
+    /// The thousands of `points` under floor, half up and five down six up rounding.
+    fn thousands(points: i32) -> [i32; 3] {
+        [
+            ThousandRounding::Floor,
+            ThousandRounding::HalfUp,
+            ThousandRounding::FiveDownSixUp,
+        ]
+        .map(|rounding| rounding.apply(Score::new(points)))
+    }
+
+    #[test]
+    fn positive_half_thousand_rounds_by_the_chosen_mode() {
+        assert_eq!(thousands(24_500), [24, 25, 24]);
+    }
+
+    #[test]
+    fn positive_six_hundred_rounds_by_the_chosen_mode() {
+        assert_eq!(thousands(24_600), [24, 25, 25]);
+    }
+
+    #[test]
+    fn negative_half_thousand_rounds_by_the_chosen_mode() {
+        assert_eq!(thousands(-15_500), [-16, -15, -16]);
+    }
+
+    #[test]
+    fn negative_four_hundred_rounds_by_the_chosen_mode() {
+        assert_eq!(thousands(-15_400), [-16, -15, -15]);
+    }
+
+    #[test]
+    fn result_rounds_the_points_of_a_seat_to_thousands() {
+        let result = final_state(0, 0, 0, [40_500, 30_000, 20_000, 9_500])
+            .into_result(&MatchResultPolicy::mahjong_soul(), Score::new(1_000));
+
+        assert_eq!(
+            result.thousands(Seat::<FourPlayer>::ALL[3], ThousandRounding::HalfUp),
+            -30
+        );
+    }
+
+    /// Deals the same arranged qipai and zimo every round and checks that rounds are asked for
+    /// in order.
+    struct ArrangedWalls {
+        /// `None` fails every request.
+        qipai: Option<[[TileKind; 13]; 4]>,
+        zimo: &'static [TileKind],
+        next_round_number: u32,
+    }
+
+    impl WallSource<FourPlayer> for ArrangedWalls {
+        type Error = &'static str;
+
+        fn bipai(&mut self, round_number: u32) -> Result<Bipai<FourPlayer>, Self::Error> {
+            assert_eq!(round_number, self.next_round_number);
+            self.next_round_number += 1;
+            let qipai = self.qipai.ok_or("no wall")?;
+            Ok(Bipai::<FourPlayer>::red_three_arranged(
+                qipai,
+                self.zimo,
+                &[],
+            ))
+        }
+    }
+
+    fn table_match(
+        qipai: Option<[[TileKind; 13]; 4]>,
+    ) -> TableMatch<FourPlayer, ArrangedWalls, BetweenRounds<FourPlayer>> {
+        arranged_table_match(qipai, &[])
+    }
+
+    fn arranged_table_match(
+        qipai: Option<[[TileKind; 13]; 4]>,
+        zimo: &'static [TileKind],
+    ) -> TableMatch<FourPlayer, ArrangedWalls, BetweenRounds<FourPlayer>> {
+        TableMatch::new(
+            TableMatchState::new(
+                Chang::Dong,
+                RoundIndex::new(0),
+                Ben::new(0),
+                Lizhibang::new(0),
+                Seat::<FourPlayer>::ALL[0],
+                scores([25_000; 4]),
+            ),
+            MatchRules::mahjong_soul(),
+            MatchResultPolicy::mahjong_soul(),
+            RoundPolicy::mahjong_soul(),
+            FirstZimoOrigin::LiveWall,
+            ArrangedWalls {
+                qipai,
+                zimo,
+                next_round_number: 0,
+            },
+        )
+    }
+
+    /// Plays every round by moqie until the match finishes, returning the rounds played too.
+    fn play_table_match(
+        qipai: [[TileKind; 13]; 4],
+    ) -> (TableMatchResult<FourPlayer>, FinishReason, u32) {
+        let mut table_match = table_match(Some(qipai));
+        loop {
+            let zhuangjia = table_match.state().zhuangjia();
+            let Ok((in_play, round)) = table_match.start_round() else {
+                panic!("expected the round to start");
+            };
+            assert_eq!(*round.zhuangjia(), zhuangjia);
+            let round_count = in_play.round_count();
+            let Ok(settled) = in_play.settle(play_moqie(round)) else {
+                panic!("expected the round to settle");
+            };
+            match settled.next() {
+                TableMatchStep::Next(next) => table_match = next,
+                TableMatchStep::Finished(result, reason) => return (result, reason, round_count),
+            }
+        }
+    }
+
+    /// The seat after the zhuangjia is the only tingpai seat, so the zhuangjia passes on every
+    /// round and the scores come back level after each chang.
+    fn level_table_match() -> (TableMatchResult<FourPlayer>, FinishReason, u32) {
+        play_table_match([BUTING_SEAT1, TINGPAI_SEAT0, BUTING_SEAT2, BUTING_SEAT3])
+    }
+
+    #[test]
+    fn table_match_plays_regulation_and_extension_rounds_to_the_last() {
+        let (_, reason, _) = level_table_match();
+
+        assert_eq!(reason, FinishReason::ExtensionEnded);
+    }
+
+    #[test]
+    fn table_match_counts_every_regulation_and_extension_round() {
+        let (_, _, round_count) = level_table_match();
+
+        assert_eq!(round_count, 12);
+    }
+
+    #[test]
+    fn table_match_result_keeps_the_level_scores() {
+        let (result, _, _) = level_table_match();
+
+        assert_eq!(result.scores(), &scores([25_000; 4]));
+    }
+
+    #[test]
+    fn table_match_places_level_seats_in_qijia_order() {
+        let (result, _, _) = level_table_match();
+
+        assert_eq!(placements(&result), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn table_match_gives_level_seats_only_their_placement_points() {
+        let (result, _, _) = level_table_match();
+
+        assert_eq!(points(&result), [15_000, 5_000, -5_000, -15_000]);
+    }
+
+    /// The tingpai zhuangjia stays and takes 1,000 from each other seat every round.
+    fn bust_table_match() -> (TableMatchResult<FourPlayer>, FinishReason, u32) {
+        play_table_match([TINGPAI_SEAT0, BUTING_SEAT1, BUTING_SEAT2, BUTING_SEAT3])
+    }
+
+    #[test]
+    fn table_match_stops_when_a_seat_busts() {
+        let (_, reason, _) = bust_table_match();
+
+        assert_eq!(reason, FinishReason::Bust);
+    }
+
+    #[test]
+    fn table_match_stops_at_the_round_that_busts_a_seat() {
+        let (_, _, round_count) = bust_table_match();
+
+        assert_eq!(round_count, 26);
+    }
+
+    #[test]
+    fn table_match_result_keeps_the_busted_scores() {
+        let (result, _, _) = bust_table_match();
+
+        assert_eq!(result.scores(), &scores([103_000, -1_000, -1_000, -1_000]));
+    }
+
+    #[test]
+    fn table_match_places_busted_seats_in_qijia_order() {
+        let (result, _, _) = bust_table_match();
+
+        assert_eq!(placements(&result), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn wall_source_failure_names_the_round() {
+        assert_eq!(
+            table_match(None)
+                .start_round()
+                .err()
+                .map(|(_, error)| error),
+            Some(TableMatchError::WallSource {
+                round_number: 0,
+                error: "no wall"
+            })
+        );
+    }
+
+    #[test]
+    fn wall_source_failure_hands_back_the_table_match_at_the_same_round() {
+        let Err((table_match, _)) = table_match(None).start_round() else {
+            panic!("expected the wall source to fail");
+        };
+
+        assert_eq!(table_match.round_count(), 0);
+    }
+
+    #[test]
+    fn settle_rejects_a_round_the_table_match_did_not_start() {
+        let Ok((in_play, _)) = table_match(Some([
+            TINGPAI_SEAT0,
+            BUTING_SEAT1,
+            BUTING_SEAT2,
+            BUTING_SEAT3,
+        ]))
+        .start_round() else {
+            panic!("expected the round to start");
+        };
+        let foreign = arranged_huangpai_pingju_round([
+            BUTING_SEAT1,
+            TINGPAI_SEAT0,
+            BUTING_SEAT2,
+            BUTING_SEAT3,
+        ]);
+
+        assert_eq!(
+            in_play.settle(foreign).err().map(|(_, _, error)| error),
+            Some(TableMatchError::ForeignRound)
+        );
+    }
+
+    /// Starts the match on a tianhu of seat 0 and scores the round with every yaku disabled.
+    fn unscored_tianhu() -> RejectedRound<FourPlayer, ArrangedWalls, &'static str> {
+        let qipai = [
+            [S1, S2, S3, S4, S5, S6, S7, S8, S9, Z1, Z1, Z1, Z2],
+            [M1, M1, M1, M9, M9, M9, P1, P2, P3, P4, P5, P6, P7],
+            [Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, Z7, P9, P9, P9, P8],
+            [M2, M3, M4, M5, M6, M7, M8, P2, P3, P4, P5, P6, Z3],
+        ];
+        let Ok((in_play, round)) = arranged_table_match(Some(qipai), &[Z2]).start_round() else {
+            panic!("expected the round to start");
+        };
+        let mut round = round.zimo().unwrap().zimo_hule().unwrap();
+        round.policy_mut().yaku = YakuPolicy::from_fn(|_| YakuRule::DISABLED);
+        let Err(rejected) = in_play.settle(round) else {
+            panic!("expected the settlement to fail");
+        };
+        rejected
+    }
+
+    #[test]
+    fn settle_rejects_a_hule_without_yaku() {
+        let (_, _, error) = unscored_tianhu();
+
+        assert_eq!(
+            error,
+            TableMatchError::Settlement(SettlementError::UnscoredHule { index: 0 })
+        );
+    }
+
+    #[test]
+    fn rejected_round_settles_again_once_it_can_be_scored() {
+        let (in_play, mut round, _) = unscored_tianhu();
+        round.policy_mut().yaku = YakuPolicy::mahjong_soul();
+
+        assert!(in_play.settle(round).is_ok());
+    }
+
+    #[test]
+    fn settled_round_exposes_its_transfers() {
+        let qipai = [TINGPAI_SEAT0, BUTING_SEAT1, BUTING_SEAT2, BUTING_SEAT3];
+        let Ok((in_play, round)) = table_match(Some(qipai)).start_round() else {
+            panic!("expected the round to start");
+        };
+        let Ok(settled) = in_play.settle(play_moqie(round)) else {
+            panic!("expected the round to settle");
+        };
+        let seat = |index| TransferParty::Seat(Seat::<FourPlayer>::ALL[index]);
+        let buting_fafu = |from| {
+            Transfer::new(
+                seat(from),
+                seat(0),
+                Score::new(1_000),
+                TransferReason::ButingFafu,
+            )
+        };
+
+        assert_eq!(
+            settled.settlement().transfers(),
+            [buting_fafu(1), buting_fafu(2), buting_fafu(3)]
+        );
+    }
 }
@@ -0,0 +1,250 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::bipai::BipaiSpec;
+use crate::player_set::PlayerSet;
+use crate::round::{FirstZimoOrigin, Round, RoundEnded, RoundPolicy, ZimoPending};
+use crate::wall_source::WallSource;
+
+use super::{
+    Ben, Chang, FinishReason, MatchResultPolicy, MatchRules, RoundIndex, RoundSettlement,
+    SettlementError, TableMatchProgress, TableMatchResult, TableMatchState,
+};
+
+/// The table match is waiting to start its next round from its state.
+pub struct BetweenRounds<P: PlayerSet> {
+    state: TableMatchState<P>,
+}
+
+/// The round the table match started is being played by the caller, and holds the state.
+pub struct RoundInPlay<P: BipaiSpec> {
+    chang: Chang,
+    round_index: RoundIndex,
+    ben: Ben,
+    /// The wall the round was dealt from, which tells the round apart from any other round at
+    /// the same chang, round index and ben.
+    tiles: P::BipaiTiles,
+}
+
+/// The round is settled, and the caller may inspect its settlement before moving on.
+pub struct RoundSettled<P: BipaiSpec> {
+    settlement: RoundSettlement<P>,
+}
+
+/// Chains the rounds of one table match from a wall source until it finishes.
+///
+/// Each round is played through the transitions of `Round` itself, so an outer scheduler meets
+/// the same suspensions as when it drives a single round.
+pub struct TableMatch<P: BipaiSpec, W, Stage> {
+    rules: MatchRules,
+    result_policy: MatchResultPolicy<P>,
+    round_policy: RoundPolicy<P>,
+    first_zimo_origin: FirstZimoOrigin,
+    wall_source: W,
+    /// Rounds started so far, which numbers the next round for the wall source.
+    round_count: u32,
+    stage: Stage,
+}
+
+/// A table match with the round it just started.
+type StartedRound<P, W> = (TableMatch<P, W, RoundInPlay<P>>, Round<P, ZimoPending>);
+
+/// A table match with the round it just settled.
+type SettledRound<P, W> = TableMatch<P, W, RoundSettled<P>>;
+
+/// A transition the table match rejected, with the table match handed back unchanged.
+type Rejected<T, E> = (T, TableMatchError<E>);
+
+/// A round the table match refused to settle, with the table match and the round handed back
+/// unchanged.
+pub(super) type RejectedRound<P, W, E> = (
+    TableMatch<P, W, RoundInPlay<P>>,
+    Round<P, RoundEnded<P>>,
+    TableMatchError<E>,
+);
+
+/// Where the table match goes after a settled round.
+#[expect(
+    clippy::large_enum_variant,
+    reason = "a step is moved once per round and boxing it would allocate every round"
+)]
+pub enum TableMatchStep<P: BipaiSpec, W> {
+    /// The next round, regulation or extension, is ready to start.
+    Next(TableMatch<P, W, BetweenRounds<P>>),
+    Finished(TableMatchResult<P>, FinishReason),
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TableMatchError<E> {
+    #[error("wall source failed for round {round_number}: {error}")]
+    WallSource { round_number: u32, error: E },
+    #[error("round was not started by this table match")]
+    ForeignRound,
+    #[error(transparent)]
+    Settlement(#[from] SettlementError),
+}
+
+impl<P: BipaiSpec, W, Stage> TableMatch<P, W, Stage> {
+    pub fn round_count(&self) -> u32 {
+        self.round_count
+    }
+}
+
+impl<P: BipaiSpec, W> TableMatch<P, W, BetweenRounds<P>> {
+    pub fn state(&self) -> &TableMatchState<P> {
+        &self.stage.state
+    }
+}
+
+impl<P: BipaiSpec, W: WallSource<P>> TableMatch<P, W, BetweenRounds<P>>
+where
+    RoundPolicy<P>: Clone,
+{
+    pub fn new(
+        state: TableMatchState<P>,
+        rules: MatchRules,
+        result_policy: MatchResultPolicy<P>,
+        round_policy: RoundPolicy<P>,
+        first_zimo_origin: FirstZimoOrigin,
+        wall_source: W,
+    ) -> Self {
+        Self {
+            rules,
+            result_policy,
+            round_policy,
+            first_zimo_origin,
+            wall_source,
+            round_count: 0,
+            stage: BetweenRounds { state },
+        }
+    }
+
+    /// Asks the wall source for the next wall and starts the round with the current zhuangjia
+    /// and scores.
+    ///
+    /// The caller plays the round to its end and hands it back to [`TableMatch::settle`]. When
+    /// the wall source fails, the table match comes back with the error and asks for the same
+    /// round again on the next call.
+    #[expect(
+        clippy::result_large_err,
+        reason = "the rejected table match is handed back so the caller can retry"
+    )]
+    pub fn start_round(self) -> Result<StartedRound<P, W>, Rejected<Self, W::Error>> {
+        let mut table_match = self;
+        let round_number = table_match.round_count;
+        let bipai = match table_match.wall_source.bipai(round_number) {
+            Ok(bipai) => bipai,
+            Err(error) => {
+                return Err((
+                    table_match,
+                    TableMatchError::WallSource {
+                        round_number,
+                        error,
+                    },
+                ));
+            }
+        };
+        let state = table_match.stage.state;
+        let stage = RoundInPlay {
+            chang: state.chang(),
+            round_index: state.round_index(),
+            ben: state.ben(),
+            tiles: *bipai.tiles(),
+        };
+        let round = Round::new(
+            bipai,
+            table_match.first_zimo_origin,
+            table_match.round_policy.clone(),
+            state,
+        );
+
+        Ok((
+            TableMatch {
+                rules: table_match.rules,
+                result_policy: table_match.result_policy,
+                round_policy: table_match.round_policy,
+                first_zimo_origin: table_match.first_zimo_origin,
+                wall_source: table_match.wall_source,
+                round_count: round_number + 1,
+                stage,
+            },
+            round,
+        ))
+    }
+}
+
+impl<P: BipaiSpec, W: WallSource<P>> TableMatch<P, W, RoundInPlay<P>> {
+    /// Settles the round the match started and checks that it paid every hule and kept the
+    /// points.
+    ///
+    /// A round the match did not start, or one that fails to settle, is rejected, and the table
+    /// match comes back with the round so the caller can settle again.
+    #[expect(
+        clippy::result_large_err,
+        reason = "the rejected table match and round are handed back so the caller can retry"
+    )]
+    pub fn settle(
+        self,
+        round: Round<P, RoundEnded<P>>,
+    ) -> Result<SettledRound<P, W>, RejectedRound<P, W, W::Error>> {
+        if !self.started(&round) {
+            return Err((self, round, TableMatchError::ForeignRound));
+        }
+        let settlement = match round.try_into_settlement(&self.rules) {
+            Ok(settlement) => settlement,
+            Err((round, error)) => return Err((self, round, error.into())),
+        };
+        if let Err(error) = settlement.check_conservation() {
+            return Err((self, settlement.round, error.into()));
+        }
+
+        Ok(TableMatch {
+            rules: self.rules,
+            result_policy: self.result_policy,
+            round_policy: self.round_policy,
+            first_zimo_origin: self.first_zimo_origin,
+            wall_source: self.wall_source,
+            round_count: self.round_count,
+            stage: RoundSettled { settlement },
+        })
+    }
+
+    fn started(&self, round: &Round<P, RoundEnded<P>>) -> bool {
+        let state = round.table_match_state();
+        (state.chang(), state.round_index(), state.ben())
+            == (self.stage.chang, self.stage.round_index, self.stage.ben)
+            && round.bipai().tiles().as_ref() == self.stage.tiles.as_ref()
+    }
+}
+
+impl<P: BipaiSpec, W> TableMatch<P, W, RoundSettled<P>> {
+    /// The settlement of the round, with its transfers and the state the next round starts from.
+    pub fn settlement(&self) -> &RoundSettlement<P> {
+        &self.stage.settlement
+    }
+
+    /// Moves on to the next round, or places the final scores once the match is over.
+    pub fn next(self) -> TableMatchStep<P, W> {
+        let state = match self.stage.settlement.into_progress(&self.rules) {
+            TableMatchProgress::Continue(state) | TableMatchProgress::Extend(state) => state,
+            TableMatchProgress::Finish(state, reason) => {
+                let result =
+                    state.into_result(&self.result_policy, self.round_policy.lizhi_deposit);
+                return TableMatchStep::Finished(result, reason);
+            }
+        };
+
+        TableMatchStep::Next(TableMatch {
+            rules: self.rules,
+            result_policy: self.result_policy,
+            round_policy: self.round_policy,
+            first_zimo_origin: self.first_zimo_origin,
+            wall_source: self.wall_source,
+            round_count: self.round_count,
+            stage: BetweenRounds { state },
+        })
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::bipai::BipaiSpec;
+use crate::player_set::PlayerSet;
+use crate::round::RoundOutcome;
+
+use super::{MatchRules, RoundSettlement, TableMatchState};
+
+/// Whether the match goes on after a settled round, with the state it goes on or ends with.
+pub enum TableMatchProgress<P: PlayerSet> {
+    /// The next round is a regulation round.
+    Continue(TableMatchState<P>),
+    /// The next round is an extension round.
+    Extend(TableMatchState<P>),
+    Finish(TableMatchState<P>, FinishReason),
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FinishReason {
+    /// A seat fell to a bust score.
+    Bust,
+    /// The last regulation round passed with the target reached, or without an extension chang.
+    RegulationEnded,
+    /// An extension round ended with the target reached.
+    TargetReached,
+    /// The last extension round passed without anyone reaching the target.
+    ExtensionEnded,
+    FinalHuleStop,
+    FinalTingpaiStop,
+}
+
+impl<P: PlayerSet + BipaiSpec> RoundSettlement<P> {
+    /// Decides under `rules` whether the match goes on after the settled round, and takes the
+    /// state for the next round or the final state.
+    ///
+    /// A bust score ends the match first. In the final round, the last of regulation or of
+    /// extension, a zhuangjia that stays by hule or tingpai while alone at the top ends it when
+    /// the rules stop there and the target is reached. Otherwise the match ends once the final
+    /// round passes, or during extension once the target is reached, and every other settlement
+    /// continues with the next round.
+    ///
+    /// `rules` must be those the settlement was made with. An extension that does not last past
+    /// `MatchTerminationPolicy::last_chang` leaves no extension chang, so regulation ends the
+    /// match.
+    pub fn into_progress(self, rules: &MatchRules) -> TableMatchProgress<P> {
+        let rules = &rules.termination;
+        let zhuangjia = *self.round.zhuangjia();
+        let outcome = self.round.round_outcome();
+        let zhuangjia_tingpai = self.round.tingpai_seats().contains(&zhuangjia);
+        let state = self.round.into_table_match_state();
+        let scores = state.scores().as_ref();
+        if scores.iter().any(|&score| rules.bust.is_bust(score)) {
+            return TableMatchProgress::Finish(state, FinishReason::Bust);
+        }
+
+        let top = scores.iter().map(|score| score.value()).max().unwrap_or(0);
+        let reached = rules
+            .extension
+            .is_none_or(|extension| top >= extension.target_score.value());
+        let extending = self.chang > rules.last_chang;
+        let extension_left = rules
+            .extension
+            .is_some_and(|extension| extension.last_chang > rules.last_chang);
+        let last_chang = match rules.extension {
+            Some(extension) if extending => extension.last_chang,
+            _ => rules.last_chang,
+        };
+        let last_round = usize::from(self.round_index.value()) + 1 == P::PLAYER_COUNT;
+        let final_round = last_round && self.chang == last_chang;
+
+        if final_round && self.lianzhuang && reached {
+            let zhuangjia_score = scores[zhuangjia.index()].value();
+            let alone_at_top = scores.iter().enumerate().all(|(index, score)| {
+                index == zhuangjia.index() || score.value() < zhuangjia_score
+            });
+            let stop = match outcome {
+                RoundOutcome::Hule => rules.final_hule_stop.then_some(FinishReason::FinalHuleStop),
+                RoundOutcome::HuangpaiPingju if zhuangjia_tingpai => rules
+                    .final_tingpai_stop
+                    .then_some(FinishReason::FinalTingpaiStop),
+                _ => None,
+            };
+            if alone_at_top && let Some(reason) = stop {
+                return TableMatchProgress::Finish(state, reason);
+            }
+        }
+
+        let chang_ended = last_round && !self.lianzhuang;
+        if !extending {
+            return if !(final_round && chang_ended) {
+                TableMatchProgress::Continue(state)
+            } else if reached || !extension_left {
+                TableMatchProgress::Finish(state, FinishReason::RegulationEnded)
+            } else {
+                TableMatchProgress::Extend(state)
+            };
+        }
+
+        let sudden_death = rules
+            .extension
+            .is_some_and(|extension| extension.sudden_death);
+        if reached && (sudden_death || chang_ended) {
+            TableMatchProgress::Finish(state, FinishReason::TargetReached)
+        } else if final_round && chang_ended {
+            TableMatchProgress::Finish(state, FinishReason::ExtensionEnded)
+        } else {
+            TableMatchProgress::Extend(state)
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use core::cmp::Reverse;
+
+use heapless::Vec;
+
+use crate::player_set::PlayerSet;
+use crate::score::Score;
+use crate::seat::{Seat, SeatArray};
+
+use super::{MAX_PLAYER_COUNT, TableMatchState};
+
+const THOUSAND: i32 = 1_000;
+
+/// The part of the validated match rules that turns final scores into placements and points.
+///
+/// It is kept apart from `MatchRules` because its placement points are laid out for the player
+/// set.
+pub struct MatchResultPolicy<P: PlayerSet> {
+    pub start_score: Score,
+    /// The score each seat's points are counted from. The difference from `start_score` over
+    /// every seat goes to the top.
+    pub return_score: Score,
+    /// Points added for each placement, the top first.
+    pub placement_points: P::Scores,
+    pub tie: PlacementTie,
+    pub leftover_lizhibang: LeftoverLizhibang,
+}
+
+#[cfg(test)]
+impl MatchResultPolicy<crate::player_set::FourPlayer> {
+    pub(crate) const fn mahjong_soul() -> Self {
+        Self {
+            start_score: Score::new(25_000),
+            return_score: Score::new(25_000),
+            placement_points: [
+                Score::new(15_000),
+                Score::new(5_000),
+                Score::new(-5_000),
+                Score::new(-15_000),
+            ],
+            tie: PlacementTie::QijiaOrder,
+            leftover_lizhibang: LeftoverLizhibang::Top,
+        }
+    }
+}
+
+/// How seats with the same final score are placed.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PlacementTie {
+    /// The seat nearer the qijia in turn order is placed higher.
+    QijiaOrder,
+    /// The seats share the higher placement and split the placement points of every placement
+    /// they cover.
+    Shared,
+}
+
+/// Where the lizhibang left on the table at the end of the match goes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LeftoverLizhibang {
+    /// Added to the points of the top, split like the placement points on a shared top.
+    Top,
+    Discarded,
+}
+
+/// How points become thousand-point units.
+///
+/// Each mode counts up from the floor of the value, so negative points round the same way as
+/// positive ones.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum ThousandRounding {
+    Floor,
+    /// A remainder of 500 or more rounds up.
+    HalfUp,
+    /// A remainder of 600 or more rounds up, and 500 rounds down.
+    FiveDownSixUp,
+}
+
+impl ThousandRounding {
+    pub const fn apply(self, points: Score) -> i32 {
+        let thousands = points.value().div_euclid(THOUSAND);
+        let remainder = points.value().rem_euclid(THOUSAND);
+        let up = match self {
+            Self::Floor => false,
+            Self::HalfUp => remainder >= 500,
+            Self::FiveDownSixUp => remainder >= 600,
+        };
+        thousands + up as i32
+    }
+}
+
+/// A seat's placement, 1 for the top.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Placement(u8);
+
+impl Placement {
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+}
+
+/// The final scores of a finished match with the placement and points of each seat.
+pub struct TableMatchResult<P: PlayerSet> {
+    scores: P::Scores,
+    placements: P::Placements,
+    /// Difference from the return score with placement points, the top's bonus and any
+    /// leftover lizhibang, in points.
+    points: P::Scores,
+}
+
+impl<P: PlayerSet> TableMatchState<P> {
+    /// Places the seats by final score and counts their points under `policy`; each leftover
+    /// lizhibang is worth `deposit`.
+    ///
+    /// The top takes the difference between the return and start scores of every seat, and the
+    /// leftover lizhibang unless it is discarded. Seats sharing a placement split these and their
+    /// placement points evenly, the remainder going a point each to the seats nearest the qijia.
+    pub fn into_result(self, policy: &MatchResultPolicy<P>, deposit: Score) -> TableMatchResult<P> {
+        let player_count = P::PLAYER_COUNT;
+        let qijia = self.qijia().index();
+        let scores = self.scores.as_ref();
+        let mut order = (0..player_count).collect::<Vec<usize, MAX_PLAYER_COUNT>>();
+        order.sort_unstable_by_key(|&index| {
+            (
+                Reverse(scores[index].value()),
+                (index + player_count - qijia) % player_count,
+            )
+        });
+
+        let top_bonus = (policy.return_score.value() - policy.start_score.value())
+            * player_count as i32
+            + match policy.leftover_lizhibang {
+                LeftoverLizhibang::Top => i32::from(self.lizhibang.value()) * deposit.value(),
+                LeftoverLizhibang::Discarded => 0,
+            };
+        let mut placements = P::Placements::from_fn(|_| Placement(0));
+        let mut points = P::Scores::from_fn(|index| {
+            Score::new(scores[index].value() - policy.return_score.value())
+        });
+        let mut position = 0;
+        while position < player_count {
+            let tied = match policy.tie {
+                PlacementTie::QijiaOrder => 1,
+                PlacementTie::Shared => order[position..]
+                    .iter()
+                    .take_while(|&&index| scores[index] == scores[order[position]])
+                    .count(),
+            };
+            let covered = position..position + tied;
+            let mut bonus = policy.placement_points.as_ref()[covered.clone()]
+                .iter()
+                .map(|points| points.value())
+                .sum::<i32>();
+            if position == 0 {
+                bonus += top_bonus;
+            }
+            for (offset, &index) in order[covered].iter().enumerate() {
+                let share = bonus.div_euclid(tied as i32)
+                    + i32::from(offset < bonus.rem_euclid(tied as i32) as usize);
+                placements.as_mut()[index] = Placement(position as u8 + 1);
+                points.as_mut()[index] = Score::new(points[index].value() + share);
+            }
+            position += tied;
+        }
+
+        TableMatchResult {
+            scores: self.scores,
+            placements,
+            points,
+        }
+    }
+}
+
+impl<P: PlayerSet> TableMatchResult<P> {
+    /// Final scores by seat, before any points are counted.
+    pub fn scores(&self) -> &P::Scores {
+        &self.scores
+    }
+
+    pub fn placements(&self) -> &P::Placements {
+        &self.placements
+    }
+
+    /// Points by seat, in points.
+    pub fn points(&self) -> &P::Scores {
+        &self.points
+    }
+
+    /// The points of `seat` in thousand-point units under `rounding`.
+    pub fn thousands(&self, seat: Seat<P>, rounding: ThousandRounding) -> i32 {
+        rounding.apply(self.points[seat.index()])
+    }
+}
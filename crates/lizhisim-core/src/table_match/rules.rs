@@ -0,0 +1,130 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::bipai::BipaiSpec;
+use crate::player_set::PlayerSet;
+use crate::round::{Round, RoundEnded, RoundOutcome};
+use crate::score::Score;
+
+use super::{Chang, paid_as_hule};
+
+/// The part of the validated match rules that settles a round into the next `TableMatchState`
+/// and decides when the match ends.
+///
+/// The value carries no preset identity or source, like `RoundPolicy`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MatchRules {
+    /// Whether a hule by the zhuangjia, one of several rong or a liuju manguan paid as hule
+    /// included, keeps the zhuangjia.
+    pub hule_lianzhuang: bool,
+    pub pingju_lianzhuang: PingjuLianzhuang,
+    /// Whether an abortive draw keeps the zhuangjia.
+    pub liuju_lianzhuang: bool,
+    pub termination: MatchTerminationPolicy,
+}
+
+/// When the match ends after a settled round.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct MatchTerminationPolicy {
+    /// The chang whose last round ends regulation play: `Dong` for dongfeng, `Nan` for dongnan.
+    pub last_chang: Chang,
+    /// Play past `last_chang` while no seat has reached a target score, or `None` to end after it.
+    pub extension: Option<Extension>,
+    pub bust: Bust,
+    /// Whether the match ends when the zhuangjia of the final round stays by hule while alone at
+    /// the top.
+    pub final_hule_stop: bool,
+    /// Whether the match ends when the zhuangjia of the final round stays by tingpai at huangpai
+    /// pingju while alone at the top.
+    pub final_tingpai_stop: bool,
+}
+
+/// Extension rounds played after regulation.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Extension {
+    /// The score the top seat must reach for the match to end, checked after regulation too.
+    pub target_score: Score,
+    /// The chang whose last round ends the match even if nobody has reached the target.
+    pub last_chang: Chang,
+    /// Whether the match ends after any extension round in which the target is reached, instead
+    /// of only after the last round of a chang.
+    pub sudden_death: bool,
+}
+
+/// Which scores end the match as soon as a seat falls to them.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Bust {
+    Never,
+    BelowZero,
+    ZeroOrBelow,
+}
+
+impl Bust {
+    pub(crate) fn is_bust(self, score: Score) -> bool {
+        match self {
+            Self::Never => false,
+            Self::BelowZero => score.value() < 0,
+            Self::ZeroOrBelow => score.value() <= 0,
+        }
+    }
+}
+
+/// Whether huangpai pingju keeps the zhuangjia.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PingjuLianzhuang {
+    Never,
+    /// The zhuangjia stays when confirmed tingpai.
+    Tingpai,
+    Always,
+}
+
+impl MatchRules {
+    /// Whether the zhuangjia of `round` stays for the next round.
+    pub(crate) fn lianzhuang<P: PlayerSet + BipaiSpec>(
+        &self,
+        round: &Round<P, RoundEnded<P>>,
+    ) -> bool {
+        let zhuangjia = round.zhuangjia();
+        match round.round_outcome() {
+            RoundOutcome::Hule => {
+                self.hule_lianzhuang && round.hule().iter().any(|hule| hule.seat() == zhuangjia)
+            }
+            RoundOutcome::HuangpaiPingju if paid_as_hule(round) => {
+                self.hule_lianzhuang && round.liuju_manguan_seats().contains(zhuangjia)
+            }
+            RoundOutcome::HuangpaiPingju => match self.pingju_lianzhuang {
+                PingjuLianzhuang::Never => false,
+                PingjuLianzhuang::Tingpai => round.tingpai_seats().contains(zhuangjia),
+                PingjuLianzhuang::Always => true,
+            },
+            RoundOutcome::JiuzhongJiupai
+            | RoundOutcome::SifengLianda
+            | RoundOutcome::SijiaLizhi
+            | RoundOutcome::SigangSanle
+            | RoundOutcome::SanjiaHele => self.liuju_lianzhuang,
+        }
+    }
+}
+
+#[cfg(test)]
+impl MatchRules {
+    pub(crate) const fn mahjong_soul() -> Self {
+        Self {
+            hule_lianzhuang: true,
+            pingju_lianzhuang: PingjuLianzhuang::Tingpai,
+            liuju_lianzhuang: true,
+            termination: MatchTerminationPolicy {
+                last_chang: Chang::Nan,
+                extension: Some(Extension {
+                    target_score: Score::new(30_000),
+                    last_chang: Chang::Xi,
+                    sudden_death: true,
+                }),
+                bust: Bust::BelowZero,
+                final_hule_stop: true,
+                final_tingpai_stop: true,
+            },
+        }
+    }
+}
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+use thiserror::Error;
+
+use crate::score::Score;
+use crate::seat::Seat;
+
+/// Four liuju manguan seats paid by three seats each, ben included, fit with the lizhibang
+/// pickup.
+const MAX_TRANSFER_COUNT: usize = 32;
+
+pub type Transfers<P> = Vec<Transfer<P>, MAX_TRANSFER_COUNT>;
+
+/// One movement of points at settlement.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Transfer<P> {
+    from: TransferParty<P>,
+    to: TransferParty<P>,
+    amount: Score,
+    reason: TransferReason,
+}
+
+impl<P> Transfer<P> {
+    pub(crate) const fn new(
+        from: TransferParty<P>,
+        to: TransferParty<P>,
+        amount: Score,
+        reason: TransferReason,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            reason,
+        }
+    }
+
+    pub fn from(&self) -> TransferParty<P> {
+        self.from
+    }
+
+    pub fn to(&self) -> TransferParty<P> {
+        self.to
+    }
+
+    pub fn amount(&self) -> Score {
+        self.amount
+    }
+
+    pub fn reason(&self) -> TransferReason {
+        self.reason
+    }
+}
+
+impl<P> Clone for Transfer<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Transfer<P> {}
+
+/// Where a transfer takes points from or gives them to.
+#[derive(Debug, Eq, PartialEq)]
+pub enum TransferParty<P> {
+    Seat(Seat<P>),
+    /// The lizhibang on the table, counted in points.
+    Lizhibang,
+}
+
+impl<P> Clone for TransferParty<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for TransferParty<P> {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TransferReason {
+    /// A hule payment before ben.
+    Hule,
+    Ben,
+    /// The lizhibang taken by a winner.
+    Lizhibang,
+    ButingFafu,
+    LiujuManguan,
+    /// The part of a hule paid by the seat liable for it.
+    Bao,
+    /// A payer's part of the zimo share a missing seat of a smaller table would have paid.
+    MissingSeatShare,
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum SettlementError {
+    #[error("settlement changed scores and lizhibang from {before} to {after} points in total")]
+    NotConserved { before: i32, after: i32 },
+    #[error("hule of seat index {index} has no reading with yaku to be paid")]
+    UnscoredHule { index: usize },
+}
@@ -55,6 +55,35 @@ impl TileKind {
     pub const fn is_hong_baopai(self) -> bool {
         matches!(self, Self::M0 | Self::P0 | Self::S0)
     }
+
+    /// Whether the tile is a terminal or an honor.
+    pub const fn is_yaojiu(self) -> bool {
+        matches!(
+            self,
+            Self::M1
+                | Self::M9
+                | Self::P1
+                | Self::P9
+                | Self::S1
+                | Self::S9
+                | Self::Z1
+                | Self::Z2
+                | Self::Z3
+                | Self::Z4
+                | Self::Z5
+                | Self::Z6
+                | Self::Z7
+        )
+    }
+
+    pub const fn base_tile(self) -> Self {
+        match self {
+            Self::M0 => Self::M5,
+            Self::P0 => Self::P5,
+            Self::S0 => Self::S5,
+            _ => self,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -85,6 +114,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hong_baopai_base_tile_is_matching_base_five() {
+        assert_eq!(
+            [TileKind::M0, TileKind::P0, TileKind::S0].map(TileKind::base_tile),
+            [TileKind::M5, TileKind::P5, TileKind::S5],
+        );
+    }
+
+    #[test]
+    fn base_kinds_are_their_own_base_tile() {
+        assert!(
+            TileKind::ALL[..34]
+                .iter()
+                .copied()
+                .all(|tile_kind| tile_kind.base_tile() == tile_kind)
+        );
+    }
+
     #[test]
     fn base_kinds_are_not_hong_baopai() {
         assert!(
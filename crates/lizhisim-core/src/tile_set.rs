@@ -64,6 +64,26 @@ impl TileSet {
         }
     }
 
+    /// The three-player set without `M2` to `M8`, with one red five in tongzi and suozi.
+    #[cfg(test)]
+    pub(crate) const fn red_two_three_player() -> Self {
+        let mut counts = [4; 37];
+        let mut index = TileKind::M2.index();
+        while index <= TileKind::M8.index() {
+            counts[index] = 0;
+            index += 1;
+        }
+        counts[TileKind::M0.index()] = 0;
+        counts[TileKind::P0.index()] = 1;
+        counts[TileKind::P5.index()] = 3;
+        counts[TileKind::S0.index()] = 1;
+        counts[TileKind::S5.index()] = 3;
+        Self {
+            counts,
+            total_count: 108,
+        }
+    }
+
     pub const fn try_from_counts(counts: [u8; 37]) -> Result<Self, TileSetError> {
         let mut index = 0;
         let mut total_count = 0;
@@ -105,6 +125,29 @@ impl TileSet {
     pub const fn total_count(&self) -> u8 {
         self.total_count
     }
+
+    /// The baopai `indicator` points at: the next kind of its suit, of the winds or of the
+    /// dragons, skipping kinds the set holds no copy of.
+    pub(crate) fn baopai(&self, indicator: TileKind) -> TileKind {
+        let base_tile = indicator.base_tile();
+        let index = base_tile.index();
+        let (start, len) = match base_tile {
+            TileKind::Z1 | TileKind::Z2 | TileKind::Z3 | TileKind::Z4 => (TileKind::Z1.index(), 4),
+            TileKind::Z5 | TileKind::Z6 | TileKind::Z7 => (TileKind::Z5.index(), 3),
+            _ => (index / 9 * 9, 9),
+        };
+        (1..len)
+            .map(|step| TileKind::ALL[start + (index - start + step) % len])
+            .find(|&tile_kind| self.holds(tile_kind))
+            .unwrap_or(base_tile)
+    }
+
+    /// Whether the set holds a copy of `base_tile`, red fives included.
+    fn holds(&self, base_tile: TileKind) -> bool {
+        TileKind::ALL
+            .into_iter()
+            .any(|tile_kind| tile_kind.base_tile() == base_tile && self.max_count(tile_kind) > 0)
+    }
 }
 
 #[cfg(test)]
@@ -204,4 +247,52 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn baopai_follows_the_indicator_within_its_suit() {
+        assert_eq!(
+            TileSet::red_three_four_player().baopai(TileKind::P3),
+            TileKind::P4
+        );
+    }
+
+    #[test]
+    fn baopai_after_nine_wraps_to_one() {
+        assert_eq!(
+            TileSet::red_three_four_player().baopai(TileKind::S9),
+            TileKind::S1
+        );
+    }
+
+    #[test]
+    fn baopai_after_bei_wraps_to_dong() {
+        assert_eq!(
+            TileSet::red_three_four_player().baopai(TileKind::Z4),
+            TileKind::Z1
+        );
+    }
+
+    #[test]
+    fn baopai_after_zhong_wraps_to_bai() {
+        assert_eq!(
+            TileSet::red_three_four_player().baopai(TileKind::Z7),
+            TileKind::Z5
+        );
+    }
+
+    #[test]
+    fn red_five_indicator_points_at_the_six() {
+        assert_eq!(
+            TileSet::red_three_four_player().baopai(TileKind::M0),
+            TileKind::M6
+        );
+    }
+
+    #[test]
+    fn three_player_baopai_after_m1_skips_to_m9() {
+        assert_eq!(
+            TileSet::red_two_three_player().baopai(TileKind::M1),
+            TileKind::M9
+        );
+    }
 }
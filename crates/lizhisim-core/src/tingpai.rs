@@ -0,0 +1,119 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use crate::bingpai::Bingpai;
+use crate::fulu::Fulu;
+use crate::hule::{Hule, HuleHand};
+use crate::tile::TileKind;
+
+pub(crate) const BASE_TILE_KIND_COUNT: usize = 34;
+pub(crate) const YAOJIU_INDICES: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+
+pub(crate) fn base_counts(bingpai: &Bingpai) -> [u8; BASE_TILE_KIND_COUNT] {
+    let mut counts = [0; BASE_TILE_KIND_COUNT];
+    for (tile_kind, count) in TileKind::ALL.iter().zip(bingpai.counts()) {
+        counts[tile_kind.base_tile().index()] += count;
+    }
+    counts
+}
+
+/// Base tile kinds that complete the hand; a kind already held four times is no wait.
+pub(crate) fn tingpai_kinds<H: Hule, P>(
+    hule: &H,
+    bingpai: &Bingpai,
+    fulu: &[Fulu<P>],
+) -> [bool; BASE_TILE_KIND_COUNT] {
+    let hule_kinds = hule_kinds(hule, bingpai, fulu);
+    let counts = base_counts(bingpai);
+    core::array::from_fn(|index| hule_kinds[index] && counts[index] < 4)
+}
+
+/// Base tile kinds that `hule` reads as completing the hand, even as a fifth copy.
+pub(crate) fn hule_kinds<H: Hule, P>(
+    hule: &H,
+    bingpai: &Bingpai,
+    fulu: &[Fulu<P>],
+) -> [bool; BASE_TILE_KIND_COUNT] {
+    core::array::from_fn(|index| {
+        !hule
+            .decompose(&HuleHand::new(bingpai, fulu, TileKind::ALL[index]))
+            .is_empty()
+    })
+}
+
+/// Number of distinct yaojiu base kinds held at least once.
+pub(crate) fn yaojiu_kind_count(counts: &[u8; BASE_TILE_KIND_COUNT]) -> usize {
+    YAOJIU_INDICES
+        .iter()
+        .filter(|&&index| counts[index] > 0)
+        .count()
+}
+
+pub(crate) fn is_tingpai<H: Hule, P>(hule: &H, bingpai: &Bingpai, fulu: &[Fulu<P>]) -> bool {
+    tingpai_kinds(hule, bingpai, fulu).contains(&true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hule::InHouseHule;
+    use crate::player_set::FourPlayer;
+
+    use super::*;
+
+    use TileKind::*;
+
+    fn bingpai(tile_kinds: &[TileKind]) -> Bingpai {
+        tile_kinds
+            .iter()
+            .fold(Bingpai::red_three_four_player(), |bingpai, &tile_kind| {
+                bingpai.with_added(tile_kind).unwrap()
+            })
+    }
+
+    #[test]
+    fn base_counts_project_hong_baopai_to_base_five() {
+        let bingpai = bingpai(&[M0, M5]);
+
+        assert_eq!(base_counts(&bingpai)[M5.index()], 2);
+    }
+
+    #[test]
+    fn tingpai_kinds_list_every_completing_base_tile() {
+        let kinds = tingpai_kinds::<_, FourPlayer>(
+            &InHouseHule,
+            &bingpai(&[M2, M3, M4, M5, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1]),
+            &[],
+        );
+
+        assert_eq!(
+            TileKind::ALL
+                .iter()
+                .filter(|tile_kind| kinds.get(tile_kind.index()) == Some(&true))
+                .copied()
+                .collect::<std::vec::Vec<_>>(),
+            [M2, M5]
+        );
+    }
+
+    #[test]
+    fn wait_on_fifth_copy_is_not_tingpai() {
+        let bingpai = bingpai(&[M1, M1, M1, M1, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1]);
+
+        assert!(!is_tingpai::<_, FourPlayer>(&InHouseHule, &bingpai, &[]));
+    }
+
+    #[test]
+    fn wait_on_fifth_copy_is_still_a_hule_kind() {
+        let bingpai = bingpai(&[M1, M1, M1, M1, P1, P2, P3, S7, S8, S9, Z1, Z1, Z1]);
+
+        assert!(hule_kinds::<_, FourPlayer>(&InHouseHule, &bingpai, &[])[M1.index()]);
+    }
+
+    #[test]
+    fn qiduizi_wait_is_tingpai() {
+        let bingpai = bingpai(&[M1, M1, M9, M9, P2, P2, P8, P8, S3, S3, Z1, Z1, Z7]);
+
+        assert!(is_tingpai::<_, FourPlayer>(&InHouseHule, &bingpai, &[]));
+    }
+}
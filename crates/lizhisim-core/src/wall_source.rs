@@ -0,0 +1,182 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+mod seeded;
+
+use crate::bipai::{Bipai, BipaiSpec};
+
+pub use seeded::{
+    ExperimentSeed, RoundSeed, SeededWallSource, TableMatchSeed, WallGenerationError, WallRecord,
+    WallShuffle,
+};
+
+/// The port that supplies the wall of every round of a table match.
+///
+/// An adapter may replay recorded walls or generate them; the table match asks for one wall per
+/// round, in order.
+pub trait WallSource<P: BipaiSpec> {
+    type Error;
+
+    /// The wall of the round numbered `round_number`, counted from 0 over the table match.
+    fn bipai(&mut self, round_number: u32) -> Result<Bipai<P>, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::player_set::{FourPlayer, ThreePlayer};
+    use crate::tile::TileKind;
+    use crate::tile_set::TileSet;
+
+    use super::*;
+
+    use TileKind::*;
+
+    fn four_player_walls(seed: TableMatchSeed) -> SeededWallSource {
+        SeededWallSource::new(
+            WallShuffle::SplitMix64FisherYatesV1,
+            TileSet::red_three_four_player(),
+            seed,
+        )
+    }
+
+    #[test]
+    fn experiment_seed_derives_table_match_seed_from_split_mix64() {
+        let table_match = ExperimentSeed::new(0).table_match(0);
+
+        assert_eq!(table_match.value(), 0xe220_a839_7b1d_cdaf);
+    }
+
+    #[test]
+    fn table_match_seed_derives_round_seed_from_split_mix64() {
+        let round = TableMatchSeed::new(0xe220_a839_7b1d_cdaf).round(0);
+
+        assert_eq!(round.value(), 0xa706_dd2f_4d19_7e6f);
+    }
+
+    #[test]
+    fn experiment_seed_derives_a_distinct_seed_per_table_match() {
+        let experiment = ExperimentSeed::new(0);
+
+        assert_ne!(experiment.table_match(1), experiment.table_match(0));
+    }
+
+    #[test]
+    fn table_match_seed_derives_a_distinct_seed_per_round() {
+        let table_match = TableMatchSeed::new(0);
+
+        assert_ne!(table_match.round(1), table_match.round(0));
+    }
+
+    #[test]
+    fn wall_shuffle_id_is_stable() {
+        assert_eq!(
+            WallShuffle::SplitMix64FisherYatesV1.id(),
+            "splitmix64-fisher-yates/1"
+        );
+    }
+
+    #[test]
+    fn wall_shuffle_id_round_trips() {
+        let algorithms = WallShuffle::ALL.map(|algorithm| WallShuffle::from_id(algorithm.id()));
+
+        assert_eq!(algorithms, WallShuffle::ALL.map(Some));
+    }
+
+    #[test]
+    fn wall_shuffle_rejects_an_unknown_id() {
+        assert_eq!(WallShuffle::from_id("splitmix64-fisher-yates/0"), None);
+    }
+
+    #[test]
+    fn split_mix64_fisher_yates_v1_shuffles_the_sorted_tile_set_the_same_way() {
+        let mut tiles = [M1, M2, M3, M4, M5, M6, M7, M8, M9, Z1];
+        WallShuffle::SplitMix64FisherYatesV1.shuffle(RoundSeed::new(0), &mut tiles);
+
+        assert_eq!(tiles, [M5, Z1, M3, M6, M2, M8, M7, M1, M4, M9]);
+    }
+
+    #[test]
+    fn split_mix64_fisher_yates_v1_deals_a_fixed_qipai_from_a_fixed_seed() {
+        let mut walls = four_player_walls(ExperimentSeed::new(0).table_match(0));
+
+        let bipai: Bipai<FourPlayer> = walls.bipai(0).unwrap();
+        let (_, qipai) = bipai.qipai();
+
+        let mut counts = [0; 37];
+        for tile_kind in [M1, M5, M5, M6, M7, M9, S2, S3, S4, S6, S8, Z1, Z1] {
+            counts[tile_kind.index()] += 1;
+        }
+        assert_eq!(qipai[0].counts(), &counts);
+    }
+
+    #[test]
+    fn seeded_wall_source_regenerates_each_round_from_its_record() {
+        let mut walls = four_player_walls(ExperimentSeed::new(7).table_match(3));
+
+        let regenerated: [Result<Bipai<FourPlayer>, _>; 3] =
+            [0, 1, 2].map(|round_number| walls.record(round_number).regenerate());
+        let generated: [Result<Bipai<FourPlayer>, _>; 3] =
+            [0, 1, 2].map(|round_number| walls.bipai(round_number));
+
+        assert_eq!(regenerated, generated);
+    }
+
+    #[test]
+    fn wall_record_keeps_the_tile_set_of_the_wall_source() {
+        let walls = SeededWallSource::new(
+            WallShuffle::SplitMix64FisherYatesV1,
+            TileSet::red_two_three_player(),
+            TableMatchSeed::new(1),
+        );
+
+        assert_eq!(walls.record(0).tile_set, TileSet::red_two_three_player());
+    }
+
+    #[test]
+    fn seeded_wall_source_gives_each_round_its_own_wall() {
+        let mut walls = four_player_walls(ExperimentSeed::new(7).table_match(0));
+
+        let first: Bipai<FourPlayer> = walls.bipai(0).unwrap();
+        let second: Bipai<FourPlayer> = walls.bipai(1).unwrap();
+
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn seeded_wall_source_gives_each_table_match_its_own_wall() {
+        let experiment = ExperimentSeed::new(7);
+        let mut walls = four_player_walls(experiment.table_match(0));
+        let mut other_walls = four_player_walls(experiment.table_match(1));
+
+        let first: Bipai<FourPlayer> = walls.bipai(0).unwrap();
+        let other: Bipai<FourPlayer> = other_walls.bipai(0).unwrap();
+
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn seeded_wall_source_fills_a_three_player_wall_from_a_three_player_tile_set() {
+        let mut walls = SeededWallSource::new(
+            WallShuffle::SplitMix64FisherYatesV1,
+            TileSet::red_two_three_player(),
+            TableMatchSeed::new(1),
+        );
+
+        let bipai: Result<Bipai<ThreePlayer>, _> = walls.bipai(0);
+
+        assert!(bipai.is_ok());
+    }
+
+    #[test]
+    fn seeded_wall_source_rejects_a_tile_set_that_does_not_fill_the_wall() {
+        let mut walls = four_player_walls(TableMatchSeed::new(1));
+
+        let bipai: Result<Bipai<ThreePlayer>, _> = walls.bipai(0);
+
+        assert_eq!(
+            bipai,
+            Err(WallGenerationError::TileCountMismatch { tile_count: 136 })
+        );
+    }
+}
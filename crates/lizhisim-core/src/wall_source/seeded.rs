@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use thiserror::Error;
+
+use crate::bipai::{Bipai, BipaiError, BipaiSpec};
+use crate::tile::TileKind;
+use crate::tile_set::TileSet;
+
+use super::WallSource;
+
+/// A validated tile set holds at most four tiles of each of the 34 base kinds.
+const MAX_TILE_COUNT: usize = 136;
+
+const SPLITMIX64_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The SplitMix64 output function.
+const fn mix64(value: u64) -> u64 {
+    let value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    value ^ (value >> 31)
+}
+
+/// Output `index` of a SplitMix64 stream seeded with `parent`, counted from 0.
+const fn child_seed(parent: u64, index: u64) -> u64 {
+    mix64(parent.wrapping_add(SPLITMIX64_GAMMA.wrapping_mul(index.wrapping_add(1))))
+}
+
+/// The seed of one experiment, from which the seed of each of its table matches follows.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ExperimentSeed(u64);
+
+impl ExperimentSeed {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// The seed of the table match numbered `table_match_number` within the experiment.
+    pub const fn table_match(self, table_match_number: u64) -> TableMatchSeed {
+        TableMatchSeed(child_seed(self.0, table_match_number))
+    }
+}
+
+/// The seed of one table match, from which the seed of each of its rounds follows.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct TableMatchSeed(u64);
+
+impl TableMatchSeed {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    /// The seed of the round numbered `round_number`, counted from 0 over the table match as
+    /// [`WallSource`] numbers it.
+    pub const fn round(self, round_number: u32) -> RoundSeed {
+        RoundSeed(child_seed(self.0, round_number as u64))
+    }
+}
+
+/// The seed one wall is shuffled from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct RoundSeed(u64);
+
+impl RoundSeed {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+}
+
+/// A versioned algorithm that shuffles a tile set into a wall.
+///
+/// A released variant never changes what it generates; a changed algorithm is added as a new
+/// variant with a new ID, so a recorded ID and seed regenerate the same wall.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum WallShuffle {
+    /// Lays the tile set out in `TileKind::ALL` order, each kind repeated by its count, then
+    /// shuffles it by Fisher–Yates from the last tile down. The tile at `i` is swapped with one
+    /// drawn uniformly from `0..=i` by Lemire's multiply-and-reject method, over a SplitMix64
+    /// stream seeded with the round seed.
+    SplitMix64FisherYatesV1,
+}
+
+impl WallShuffle {
+    pub const ALL: [Self; 1] = [Self::SplitMix64FisherYatesV1];
+
+    /// The stable ID that records the algorithm.
+    pub const fn id(self) -> &'static str {
+        match self {
+            Self::SplitMix64FisherYatesV1 => "splitmix64-fisher-yates/1",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.id() == id)
+    }
+
+    pub(crate) fn shuffle(self, seed: RoundSeed, tiles: &mut [TileKind]) {
+        match self {
+            Self::SplitMix64FisherYatesV1 => {
+                let mut stream = SplitMix64(seed.value());
+                for index in (1..tiles.len()).rev() {
+                    let other = stream.below(index as u64 + 1) as usize;
+                    tiles.swap(index, other);
+                }
+            }
+        }
+    }
+}
+
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(SPLITMIX64_GAMMA);
+        mix64(self.0)
+    }
+
+    /// A value drawn uniformly from `0..bound`, `bound` being nonzero.
+    fn below(&mut self, bound: u64) -> u64 {
+        let mut product = u128::from(self.next()) * u128::from(bound);
+        if (product as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (product as u64) < threshold {
+                product = u128::from(self.next()) * u128::from(bound);
+            }
+        }
+        (product >> 64) as u64
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum WallGenerationError {
+    #[error("tile set of {tile_count} tiles does not fill the wall")]
+    TileCountMismatch { tile_count: u8 },
+    #[error(transparent)]
+    Bipai(#[from] BipaiError),
+}
+
+/// What regenerates one wall bit-for-bit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WallRecord {
+    pub algorithm: WallShuffle,
+    pub tile_set: TileSet,
+    pub seed: RoundSeed,
+}
+
+impl WallRecord {
+    pub fn regenerate<P: BipaiSpec>(&self) -> Result<Bipai<P>, WallGenerationError> {
+        let tile_set = &self.tile_set;
+        let mut tiles = [TileKind::M1; MAX_TILE_COUNT];
+        let sorted = TileKind::ALL.into_iter().flat_map(|tile_kind| {
+            core::iter::repeat_n(tile_kind, tile_set.max_count(tile_kind).into())
+        });
+        for (slot, tile_kind) in tiles.iter_mut().zip(sorted) {
+            *slot = tile_kind;
+        }
+        let tile_count = tile_set.total_count();
+        let tiles = &mut tiles[..usize::from(tile_count)];
+        self.algorithm.shuffle(self.seed, tiles);
+
+        let tiles = P::BipaiTiles::try_from(tiles)
+            .map_err(|_| WallGenerationError::TileCountMismatch { tile_count })?;
+        Ok(Bipai::try_new(tiles, tile_set.clone())?)
+    }
+}
+
+/// Shuffles the wall of every round of a table match from seeds derived from the table match
+/// seed, without an external crate.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeededWallSource {
+    algorithm: WallShuffle,
+    tile_set: TileSet,
+    seed: TableMatchSeed,
+}
+
+impl SeededWallSource {
+    pub fn new(algorithm: WallShuffle, tile_set: TileSet, seed: TableMatchSeed) -> Self {
+        Self {
+            algorithm,
+            tile_set,
+            seed,
+        }
+    }
+
+    /// The record of the wall of the round numbered `round_number`.
+    pub fn record(&self, round_number: u32) -> WallRecord {
+        WallRecord {
+            algorithm: self.algorithm,
+            tile_set: self.tile_set.clone(),
+            seed: self.seed.round(round_number),
+        }
+    }
+}
+
+impl<P: BipaiSpec> WallSource<P> for SeededWallSource {
+    type Error = WallGenerationError;
+
+    fn bipai(&mut self, round_number: u32) -> Result<Bipai<P>, Self::Error> {
+        self.record(round_number).regenerate()
+    }
+}
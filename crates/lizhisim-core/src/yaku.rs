@@ -0,0 +1,343 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+mod evaluate;
+
+use heapless::Vec;
+
+use crate::hule::{HuleShape, MAX_HULE_SHAPE_COUNT};
+use crate::lizhi::LizhiState;
+use crate::tile::TileKind;
+
+/// Every yaku a preset can score, including local yaku and the double-yiman variants.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum YakuRuleId {
+    Lizhi,
+    LiangLizhi,
+    Yifa,
+    MenqianqingZimohe,
+    Pinghe,
+    Duanyaojiu,
+    Yibeikou,
+    YipaiMenfeng,
+    YipaiQuanfeng,
+    YipaiBai,
+    YipaiFa,
+    YipaiZhong,
+    /// A kezi of `Z4`, scored only while `RoundPolicy::bei_yipai` holds.
+    YipaiBei,
+    LingshangKaihua,
+    Qianggang,
+    HaidiLaoyue,
+    HediLaoyu,
+    Sansetongshun,
+    Yiqitongguan,
+    Hunquandaiyaojiu,
+    Qiduizi,
+    Duiduihe,
+    Sananke,
+    Sangangzi,
+    Sansetongke,
+    Hunlaotou,
+    Xiaosanyuan,
+    Hunyise,
+    Chunquandaiyaojiu,
+    Erbeikou,
+    Qingyise,
+    Tianhu,
+    Dihu,
+    Renhu,
+    Guoshi,
+    /// Guoshi on the thirteen-sided wait; scored instead of [`Self::Guoshi`] when enabled.
+    GuoshiShisanmian,
+    Sianke,
+    /// Sianke on a danqi wait; scored instead of [`Self::Sianke`] when enabled.
+    SiankeDanqi,
+    Jiulianbaodeng,
+    /// Jiulianbaodeng on the nine-sided wait; scored instead of [`Self::Jiulianbaodeng`] when
+    /// enabled.
+    ChunzhengJiulianbaodeng,
+    Dasanyuan,
+    Xiaosixi,
+    Dasixi,
+    Ziyise,
+    Lvyise,
+    Qinglaotou,
+    Sigangzi,
+    /// Qiduizi of `P2` to `P8`.
+    Dachelun,
+}
+
+const YAKU_RULE_COUNT: usize = 48;
+
+impl YakuRuleId {
+    pub const ALL: [Self; YAKU_RULE_COUNT] = [
+        Self::Lizhi,
+        Self::LiangLizhi,
+        Self::Yifa,
+        Self::MenqianqingZimohe,
+        Self::Pinghe,
+        Self::Duanyaojiu,
+        Self::Yibeikou,
+        Self::YipaiMenfeng,
+        Self::YipaiQuanfeng,
+        Self::YipaiBai,
+        Self::YipaiFa,
+        Self::YipaiZhong,
+        Self::YipaiBei,
+        Self::LingshangKaihua,
+        Self::Qianggang,
+        Self::HaidiLaoyue,
+        Self::HediLaoyu,
+        Self::Sansetongshun,
+        Self::Yiqitongguan,
+        Self::Hunquandaiyaojiu,
+        Self::Qiduizi,
+        Self::Duiduihe,
+        Self::Sananke,
+        Self::Sangangzi,
+        Self::Sansetongke,
+        Self::Hunlaotou,
+        Self::Xiaosanyuan,
+        Self::Hunyise,
+        Self::Chunquandaiyaojiu,
+        Self::Erbeikou,
+        Self::Qingyise,
+        Self::Tianhu,
+        Self::Dihu,
+        Self::Renhu,
+        Self::Guoshi,
+        Self::GuoshiShisanmian,
+        Self::Sianke,
+        Self::SiankeDanqi,
+        Self::Jiulianbaodeng,
+        Self::ChunzhengJiulianbaodeng,
+        Self::Dasanyuan,
+        Self::Xiaosixi,
+        Self::Dasixi,
+        Self::Ziyise,
+        Self::Lvyise,
+        Self::Qinglaotou,
+        Self::Sigangzi,
+        Self::Dachelun,
+    ];
+
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// What one yaku is worth.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum YakuValue {
+    Fan(u8),
+    /// A multiple of yiman, such as 2 for a double-yiman variant.
+    Yiman(u8),
+}
+
+/// A preset's value for one yaku; `None` leaves the yaku unscored in that state of the hand.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct YakuRule {
+    /// Value while every fulu is angang.
+    pub menqian: Option<YakuValue>,
+    /// Value once the hand is open; `None` makes the yaku menqian-only.
+    pub fulu: Option<YakuValue>,
+}
+
+impl YakuRule {
+    pub const DISABLED: Self = Self {
+        menqian: None,
+        fulu: None,
+    };
+
+    pub const fn menqian_only(value: YakuValue) -> Self {
+        Self {
+            menqian: Some(value),
+            fulu: None,
+        }
+    }
+
+    /// The same value open or closed.
+    pub const fn always(value: YakuValue) -> Self {
+        Self {
+            menqian: Some(value),
+            fulu: Some(value),
+        }
+    }
+
+    /// `fan` closed and one fan less once the hand is open, never below zero.
+    pub const fn reduced_when_open(fan: u8) -> Self {
+        Self {
+            menqian: Some(YakuValue::Fan(fan)),
+            fulu: Some(YakuValue::Fan(fan.saturating_sub(1))),
+        }
+    }
+
+    const fn value(self, menqian: bool) -> Option<YakuValue> {
+        if menqian { self.menqian } else { self.fulu }
+    }
+}
+
+/// Which yaku a preset scores and what each is worth.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct YakuPolicy {
+    rules: [YakuRule; YAKU_RULE_COUNT],
+}
+
+impl YakuPolicy {
+    pub fn from_fn(mut rule: impl FnMut(YakuRuleId) -> YakuRule) -> Self {
+        Self {
+            rules: YakuRuleId::ALL.map(&mut rule),
+        }
+    }
+
+    pub fn rule(&self, id: YakuRuleId) -> YakuRule {
+        self.rules[id.index()]
+    }
+
+    pub fn with_rule(mut self, id: YakuRuleId, rule: YakuRule) -> Self {
+        self.rules[id.index()] = rule;
+        self
+    }
+}
+
+#[cfg(test)]
+impl YakuPolicy {
+    /// Mahjong Soul ranked: kuitan on, no local yaku, every double-yiman variant counted double.
+    pub(crate) fn mahjong_soul() -> Self {
+        use YakuRuleId::*;
+        use YakuValue::{Fan, Yiman};
+
+        Self::from_fn(|id| match id {
+            Lizhi | Yifa | MenqianqingZimohe | Pinghe | Yibeikou => YakuRule::menqian_only(Fan(1)),
+            LiangLizhi | Qiduizi => YakuRule::menqian_only(Fan(2)),
+            Erbeikou => YakuRule::menqian_only(Fan(3)),
+            Duanyaojiu | YipaiMenfeng | YipaiQuanfeng | YipaiBai | YipaiFa | YipaiZhong
+            | YipaiBei | LingshangKaihua | Qianggang | HaidiLaoyue | HediLaoyu => {
+                YakuRule::always(Fan(1))
+            }
+            Sansetongshun | Yiqitongguan | Hunquandaiyaojiu => YakuRule::reduced_when_open(2),
+            Duiduihe | Sananke | Sangangzi | Sansetongke | Hunlaotou | Xiaosanyuan => {
+                YakuRule::always(Fan(2))
+            }
+            Hunyise | Chunquandaiyaojiu => YakuRule::reduced_when_open(3),
+            Qingyise => YakuRule::reduced_when_open(6),
+            Tianhu | Dihu | Guoshi | Sianke | Jiulianbaodeng => YakuRule::menqian_only(Yiman(1)),
+            GuoshiShisanmian | SiankeDanqi | ChunzhengJiulianbaodeng => {
+                YakuRule::menqian_only(Yiman(2))
+            }
+            Dasanyuan | Xiaosixi | Ziyise | Lvyise | Qinglaotou | Sigangzi => {
+                YakuRule::always(Yiman(1))
+            }
+            Dasixi => YakuRule::always(Yiman(2)),
+            Renhu | Dachelun => YakuRule::DISABLED,
+        })
+    }
+}
+
+/// Facts about how the hand was completed that the tiles alone do not show.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct HuleSituation {
+    pub zimo: bool,
+    pub lizhi: LizhiState,
+    /// Whether the winner's first turn was still uninterrupted.
+    pub first_turn: bool,
+    pub lingshang: bool,
+    /// Whether the live wall was empty at the hulepai.
+    pub haidi: bool,
+    pub qianggang: bool,
+    pub zhuangjia: bool,
+    pub quanfengpai: TileKind,
+    pub menfengpai: TileKind,
+    pub bei_yipai: bool,
+}
+
+pub type YakuEvaluations = Vec<YakuEvaluation, MAX_HULE_SHAPE_COUNT>;
+
+/// One scored yaku of the breakdown.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Yaku {
+    id: YakuRuleId,
+    value: YakuValue,
+}
+
+impl Yaku {
+    pub fn id(&self) -> YakuRuleId {
+        self.id
+    }
+
+    pub fn value(&self) -> YakuValue {
+        self.value
+    }
+}
+
+/// The yaku of one reading. Once any yiman is scored, the fan yaku are left out.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct YakuEvaluation {
+    shape: HuleShape,
+    yaku: Vec<Yaku, YAKU_RULE_COUNT>,
+}
+
+impl YakuEvaluation {
+    pub fn shape(&self) -> &HuleShape {
+        &self.shape
+    }
+
+    /// The scored yaku in [`YakuRuleId`] order.
+    pub fn yaku(&self) -> &[Yaku] {
+        &self.yaku
+    }
+
+    pub fn has_yaku(&self) -> bool {
+        !self.yaku.is_empty()
+    }
+
+    pub fn fan(&self) -> u8 {
+        self.yaku
+            .iter()
+            .map(|yaku| match yaku.value {
+                YakuValue::Fan(fan) => fan,
+                YakuValue::Yiman(_) => 0,
+            })
+            .sum()
+    }
+
+    /// Total yiman multiple; zero when only fan yaku are scored.
+    pub fn yiman(&self) -> u8 {
+        self.yaku
+            .iter()
+            .map(|yaku| match yaku.value {
+                YakuValue::Fan(_) => 0,
+                YakuValue::Yiman(multiple) => multiple,
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_when_open_takes_one_fan_off_the_open_hand() {
+        assert_eq!(
+            YakuRule::reduced_when_open(2),
+            YakuRule {
+                menqian: Some(YakuValue::Fan(2)),
+                fulu: Some(YakuValue::Fan(1)),
+            }
+        );
+    }
+
+    #[test]
+    fn reduced_when_open_keeps_zero_fan_at_zero() {
+        assert_eq!(
+            YakuRule::reduced_when_open(0),
+            YakuRule {
+                menqian: Some(YakuValue::Fan(0)),
+                fulu: Some(YakuValue::Fan(0)),
+            }
+        );
+    }
+}
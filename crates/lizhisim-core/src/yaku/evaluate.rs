@@ -0,0 +1,1199 @@
+// SPDX-FileCopyrightText: 2026 Apricot S.
+// SPDX-License-Identifier: MIT
+// This file is part of https://github.com/Apricot-S/lizhisim
+
+use heapless::Vec;
+
+use crate::fulu::Fulu;
+use crate::hule::{Hule, HuleHand, HuleShape, HuleWait, Mianzi, MianziKind};
+use crate::tile::TileKind;
+use crate::tingpai::{BASE_TILE_KIND_COUNT, base_counts};
+use crate::yaku::{
+    HuleSituation, YAKU_RULE_COUNT, Yaku, YakuEvaluation, YakuEvaluations, YakuPolicy, YakuRuleId,
+    YakuValue,
+};
+
+const SUIT_TILE_KIND_COUNT: usize = 27;
+const SANYUANPAI: [usize; 3] = [31, 32, 33];
+const LVYISE_TILE_KINDS: [TileKind; 6] = [
+    TileKind::S2,
+    TileKind::S3,
+    TileKind::S4,
+    TileKind::S6,
+    TileKind::S8,
+    TileKind::Z6,
+];
+const JIULIAN_COUNTS: [u8; 9] = [3, 1, 1, 1, 1, 1, 1, 1, 3];
+
+impl YakuPolicy {
+    /// Scores every reading `hule` gives for the hand; a reading without yaku keeps an empty
+    /// breakdown so the caller can tell it apart from a hand that is not complete.
+    pub fn evaluate<P>(
+        &self,
+        hule: &impl Hule,
+        hand: &HuleHand<'_, P>,
+        situation: &HuleSituation,
+    ) -> YakuEvaluations {
+        let tiles = HandTiles::new(hand);
+        hule.decompose(hand)
+            .into_iter()
+            .map(|shape| {
+                let mut scored = Scored::new();
+                self.score_situation(&mut scored, &tiles, situation);
+                match &shape {
+                    HuleShape::Mianzi {
+                        quetou,
+                        mianzi,
+                        wait,
+                    } => {
+                        self.score_tiles(&mut scored, &tiles);
+                        self.score_mianzi(&mut scored, &tiles, situation, *quetou, mianzi, *wait);
+                    }
+                    HuleShape::Qiduizi { duizi } => {
+                        self.score_tiles(&mut scored, &tiles);
+                        scored.insert(YakuRuleId::Qiduizi);
+                        if duizi.iter().all(|tile_kind| {
+                            (TileKind::P2.index()..=TileKind::P8.index())
+                                .contains(&tile_kind.index())
+                        }) {
+                            scored.insert(YakuRuleId::Dachelun);
+                        }
+                    }
+                    HuleShape::Guoshi { quetou } => scored.insert(self.variant(
+                        tiles.menqian,
+                        *quetou == tiles.hulepai,
+                        YakuRuleId::GuoshiShisanmian,
+                        YakuRuleId::Guoshi,
+                    )),
+                }
+                YakuEvaluation {
+                    yaku: scored.into_yaku(self, tiles.menqian),
+                    shape,
+                }
+            })
+            .collect()
+    }
+
+    /// `variant` when the hand qualifies and the preset scores it, otherwise `base`.
+    fn variant(
+        &self,
+        menqian: bool,
+        qualifies: bool,
+        variant: YakuRuleId,
+        base: YakuRuleId,
+    ) -> YakuRuleId {
+        if qualifies && self.rule(variant).value(menqian).is_some() {
+            variant
+        } else {
+            base
+        }
+    }
+
+    fn score_situation(&self, scored: &mut Scored, tiles: &HandTiles, situation: &HuleSituation) {
+        if situation.lizhi.is_established() {
+            scored.insert(self.variant(
+                tiles.menqian,
+                situation.lizhi.liang_lizhi(),
+                YakuRuleId::LiangLizhi,
+                YakuRuleId::Lizhi,
+            ));
+            scored.insert_if(situation.lizhi.yifa(), YakuRuleId::Yifa);
+        }
+        scored.insert_if(
+            tiles.menqian && situation.zimo,
+            YakuRuleId::MenqianqingZimohe,
+        );
+        scored.insert_if(
+            situation.lingshang && situation.zimo,
+            YakuRuleId::LingshangKaihua,
+        );
+        scored.insert_if(situation.qianggang, YakuRuleId::Qianggang);
+        scored.insert_if(situation.haidi && situation.zimo, YakuRuleId::HaidiLaoyue);
+        scored.insert_if(situation.haidi && !situation.zimo, YakuRuleId::HediLaoyu);
+        if situation.first_turn {
+            scored.insert(match (situation.zimo, situation.zhuangjia) {
+                (true, true) => YakuRuleId::Tianhu,
+                (true, false) => YakuRuleId::Dihu,
+                (false, _) => YakuRuleId::Renhu,
+            });
+        }
+    }
+
+    /// Yaku decided by which tile kinds the hand holds, whatever the reading.
+    fn score_tiles(&self, scored: &mut Scored, tiles: &HandTiles) {
+        let held = |index: usize| tiles.counts[index] > 0;
+        let has_zipai = (SUIT_TILE_KIND_COUNT..BASE_TILE_KIND_COUNT).any(held);
+        let suits = (0..3)
+            .filter(|suit| (suit * 9..suit * 9 + 9).any(held))
+            .count();
+        let yaojiu_only = (0..BASE_TILE_KIND_COUNT)
+            .filter(|&index| held(index))
+            .all(|index| TileKind::ALL[index].is_yaojiu());
+
+        scored.insert_if(
+            (0..BASE_TILE_KIND_COUNT)
+                .filter(|&index| held(index))
+                .all(|index| !TileKind::ALL[index].is_yaojiu()),
+            YakuRuleId::Duanyaojiu,
+        );
+        match (suits, has_zipai) {
+            (0, _) => scored.insert(YakuRuleId::Ziyise),
+            (1, true) => scored.insert(YakuRuleId::Hunyise),
+            (1, false) => scored.insert(YakuRuleId::Qingyise),
+            _ => {}
+        }
+        if yaojiu_only && suits > 0 {
+            scored.insert(if has_zipai {
+                YakuRuleId::Hunlaotou
+            } else {
+                YakuRuleId::Qinglaotou
+            });
+        }
+        scored.insert_if(
+            (0..BASE_TILE_KIND_COUNT)
+                .filter(|&index| held(index))
+                .all(|index| LVYISE_TILE_KINDS.contains(&TileKind::ALL[index])),
+            YakuRuleId::Lvyise,
+        );
+    }
+
+    fn score_mianzi(
+        &self,
+        scored: &mut Scored,
+        tiles: &HandTiles,
+        situation: &HuleSituation,
+        quetou: TileKind,
+        mianzi: &[Mianzi],
+        wait: HuleWait,
+    ) {
+        let quetou = quetou.index();
+        let shunzi = mianzi
+            .iter()
+            .filter(|mianzi| mianzi.kind() == MianziKind::Shunzi)
+            .map(|mianzi| mianzi.tile_kind().index());
+        let kezi = || {
+            mianzi
+                .iter()
+                .filter(|mianzi| mianzi.kind() != MianziKind::Shunzi)
+                .map(|mianzi| mianzi.tile_kind().index())
+        };
+        let has_shunzi = shunzi.clone().next().is_some();
+        let has_shunzi_at = |index: usize| shunzi.clone().any(|start| start == index);
+        let has_kezi_at = |index: usize| kezi().any(|tile| tile == index);
+        let yipai = yipai_tile_kinds(situation);
+
+        scored.insert_if(
+            kezi().next().is_none()
+                && wait == HuleWait::Liangmian
+                && !yipai
+                    .iter()
+                    .any(|&(tile_kind, _)| tile_kind.index() == quetou),
+            YakuRuleId::Pinghe,
+        );
+
+        let mut beikou_count = 0;
+        let mut starts: Vec<usize, 4> = shunzi.clone().collect();
+        starts.sort_unstable();
+        let mut index = 0;
+        while index + 1 < starts.len() {
+            if starts[index] == starts[index + 1] {
+                beikou_count += 1;
+                index += 2;
+            } else {
+                index += 1;
+            }
+        }
+        if beikou_count > 0 {
+            scored.insert(self.variant(
+                tiles.menqian,
+                beikou_count == 2,
+                YakuRuleId::Erbeikou,
+                YakuRuleId::Yibeikou,
+            ));
+        }
+
+        for (tile_kind, id) in yipai {
+            scored.insert_if(has_kezi_at(tile_kind.index()), id);
+        }
+
+        scored.insert_if(
+            (0..7).any(|number| (0..3).all(|suit| has_shunzi_at(suit * 9 + number))),
+            YakuRuleId::Sansetongshun,
+        );
+        scored.insert_if(
+            (0..3).any(|suit| {
+                [0, 3, 6]
+                    .iter()
+                    .all(|&number| has_shunzi_at(suit * 9 + number))
+            }),
+            YakuRuleId::Yiqitongguan,
+        );
+
+        let block_has_yaojiu = |mianzi: &Mianzi| {
+            let index = mianzi.tile_kind().index();
+            match mianzi.kind() {
+                MianziKind::Shunzi => matches!(index % 9, 0 | 6),
+                MianziKind::Kezi | MianziKind::Gangzi => mianzi.tile_kind().is_yaojiu(),
+            }
+        };
+        if has_shunzi && TileKind::ALL[quetou].is_yaojiu() && mianzi.iter().all(block_has_yaojiu) {
+            let has_zipai =
+                quetou >= SUIT_TILE_KIND_COUNT || kezi().any(|index| index >= SUIT_TILE_KIND_COUNT);
+            scored.insert(if has_zipai {
+                YakuRuleId::Hunquandaiyaojiu
+            } else {
+                YakuRuleId::Chunquandaiyaojiu
+            });
+        }
+
+        scored.insert_if(!has_shunzi, YakuRuleId::Duiduihe);
+
+        let anke_count = mianzi
+            .iter()
+            .filter(|mianzi| mianzi.is_anke(tiles.hulepai, wait, situation.zimo))
+            .count();
+        match anke_count {
+            3 => scored.insert(YakuRuleId::Sananke),
+            4 => scored.insert(self.variant(
+                tiles.menqian,
+                wait == HuleWait::Danqi,
+                YakuRuleId::SiankeDanqi,
+                YakuRuleId::Sianke,
+            )),
+            _ => {}
+        }
+
+        match mianzi
+            .iter()
+            .filter(|mianzi| mianzi.kind() == MianziKind::Gangzi)
+            .count()
+        {
+            3 => scored.insert(YakuRuleId::Sangangzi),
+            4 => scored.insert(YakuRuleId::Sigangzi),
+            _ => {}
+        }
+
+        scored.insert_if(
+            (0..9).any(|number| (0..3).all(|suit| has_kezi_at(suit * 9 + number))),
+            YakuRuleId::Sansetongke,
+        );
+
+        let sanyuan_kezi_count = SANYUANPAI
+            .iter()
+            .filter(|&&index| has_kezi_at(index))
+            .count();
+        match (sanyuan_kezi_count, SANYUANPAI.contains(&quetou)) {
+            (3, _) => scored.insert(YakuRuleId::Dasanyuan),
+            (2, true) => scored.insert(YakuRuleId::Xiaosanyuan),
+            _ => {}
+        }
+
+        let fengpai = SUIT_TILE_KIND_COUNT..SANYUANPAI[0];
+        let feng_kezi_count = fengpai.clone().filter(|&index| has_kezi_at(index)).count();
+        match (feng_kezi_count, fengpai.contains(&quetou)) {
+            (4, _) => scored.insert(YakuRuleId::Dasixi),
+            (3, true) => scored.insert(YakuRuleId::Xiaosixi),
+            _ => {}
+        }
+
+        if tiles.menqian
+            && let Some(suit) = (0..3).find(|suit| {
+                let counts = &tiles.counts[suit * 9..suit * 9 + 9];
+                counts.iter().sum::<u8>() == 14
+                    && counts
+                        .iter()
+                        .zip(JIULIAN_COUNTS)
+                        .all(|(&held, need)| held >= need)
+            })
+        {
+            let mut before_hule = tiles.counts;
+            before_hule[tiles.hulepai.index()] -= 1;
+            scored.insert(self.variant(
+                tiles.menqian,
+                before_hule[suit * 9..suit * 9 + 9] == JIULIAN_COUNTS,
+                YakuRuleId::ChunzhengJiulianbaodeng,
+                YakuRuleId::Jiulianbaodeng,
+            ));
+        }
+    }
+}
+
+/// Base tile kinds that score a yipai as a kezi or gangzi, each with its yaku.
+fn yipai_tile_kinds(situation: &HuleSituation) -> Vec<(TileKind, YakuRuleId), 6> {
+    let mut yipai = Vec::new();
+    for entry in [
+        (situation.menfengpai, YakuRuleId::YipaiMenfeng),
+        (situation.quanfengpai, YakuRuleId::YipaiQuanfeng),
+        (TileKind::Z5, YakuRuleId::YipaiBai),
+        (TileKind::Z6, YakuRuleId::YipaiFa),
+        (TileKind::Z7, YakuRuleId::YipaiZhong),
+    ] {
+        let _ = yipai.push(entry);
+    }
+    if situation.bei_yipai {
+        let _ = yipai.push((TileKind::Z4, YakuRuleId::YipaiBei));
+    }
+    yipai
+}
+
+/// The whole hand as base tile counts, fulu included.
+struct HandTiles {
+    counts: [u8; BASE_TILE_KIND_COUNT],
+    hulepai: TileKind,
+    menqian: bool,
+}
+
+impl HandTiles {
+    fn new<P>(hand: &HuleHand<'_, P>) -> Self {
+        let hulepai = hand.hulepai().base_tile();
+        let mut counts = base_counts(hand.bingpai());
+        counts[hulepai.index()] += 1;
+        for tile_kind in hand.fulu().iter().flat_map(Fulu::tiles) {
+            counts[tile_kind.base_tile().index()] += 1;
+        }
+        Self {
+            counts,
+            hulepai,
+            menqian: !hand.fulu().iter().any(Fulu::is_open),
+        }
+    }
+}
+
+/// The yaku a reading qualifies for, before the preset values them.
+struct Scored([bool; YAKU_RULE_COUNT]);
+
+impl Scored {
+    const fn new() -> Self {
+        Self([false; YAKU_RULE_COUNT])
+    }
+
+    fn insert(&mut self, id: YakuRuleId) {
+        self.0[id.index()] = true;
+    }
+
+    fn insert_if(&mut self, condition: bool, id: YakuRuleId) {
+        if condition {
+            self.insert(id);
+        }
+    }
+
+    /// Values each qualifying yaku, dropping those the preset does not score and every fan yaku
+    /// once a yiman is scored.
+    fn into_yaku(self, policy: &YakuPolicy, menqian: bool) -> Vec<Yaku, YAKU_RULE_COUNT> {
+        let yaku: Vec<Yaku, YAKU_RULE_COUNT> = YakuRuleId::ALL
+            .into_iter()
+            .filter(|id| self.0[id.index()])
+            .filter_map(|id| {
+                policy
+                    .rule(id)
+                    .value(menqian)
+                    .map(|value| Yaku { id, value })
+            })
+            .collect();
+        if yaku
+            .iter()
+            .any(|yaku| matches!(yaku.value, YakuValue::Yiman(_)))
+        {
+            yaku.into_iter()
+                .filter(|yaku| matches!(yaku.value, YakuValue::Yiman(_)))
+                .collect()
+        } else {
+            yaku
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::vec::Vec;
+
+    use crate::bingpai::Bingpai;
+    use crate::he::{He, Sipai};
+    use crate::hule::InHouseHule;
+    use crate::lizhi::LizhiState;
+    use crate::player_set::FourPlayer;
+    use crate::seat::Seat;
+    use crate::yaku::YakuRule;
+
+    use super::*;
+
+    use TileKind::*;
+    use YakuRuleId::*;
+    use YakuValue::{Fan, Yiman};
+
+    const PINGHE_TANYAO: [TileKind; 13] = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S6, S7, P8, P8];
+
+    fn seat(index: usize) -> Seat<FourPlayer> {
+        Seat::<FourPlayer>::ALL[index]
+    }
+
+    fn rong() -> HuleSituation {
+        HuleSituation {
+            zimo: false,
+            lizhi: LizhiState::Undeclared,
+            first_turn: false,
+            lingshang: false,
+            haidi: false,
+            qianggang: false,
+            zhuangjia: false,
+            quanfengpai: Z1,
+            menfengpai: Z2,
+            bei_yipai: false,
+        }
+    }
+
+    fn zimo() -> HuleSituation {
+        HuleSituation {
+            zimo: true,
+            ..rong()
+        }
+    }
+
+    fn established_lizhi(liang_lizhi: bool, yifa: bool) -> LizhiState {
+        let he = He::new()
+            .with_appended(Sipai {
+                tile_kind: Z1,
+                moqie: false,
+            })
+            .unwrap();
+        LizhiState::Established {
+            sipai_index: he.last_index().unwrap(),
+            liang_lizhi,
+            yifa,
+        }
+    }
+
+    fn evaluate_with(
+        policy: &YakuPolicy,
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> YakuEvaluations {
+        let bingpai = tile_kinds
+            .iter()
+            .fold(Bingpai::red_three_four_player(), |bingpai, &tile_kind| {
+                bingpai.with_added(tile_kind).unwrap()
+            });
+        policy.evaluate(
+            &InHouseHule,
+            &HuleHand::new(&bingpai, fulu, hulepai),
+            situation,
+        )
+    }
+
+    /// The breakdown of every reading as `(id, value)` pairs.
+    fn yaku_with(
+        policy: &YakuPolicy,
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> Vec<Vec<(YakuRuleId, YakuValue)>> {
+        evaluate_with(policy, tile_kinds, fulu, hulepai, situation)
+            .iter()
+            .map(|evaluation| {
+                evaluation
+                    .yaku()
+                    .iter()
+                    .map(|yaku| (yaku.id(), yaku.value()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn yaku(
+        tile_kinds: &[TileKind],
+        fulu: &[Fulu<FourPlayer>],
+        hulepai: TileKind,
+        situation: &HuleSituation,
+    ) -> Vec<Vec<(YakuRuleId, YakuValue)>> {
+        yaku_with(
+            &YakuPolicy::mahjong_soul(),
+            tile_kinds,
+            fulu,
+            hulepai,
+            situation,
+        )
+    }
+
+    #[test]
+    fn menqian_zimo_scores_pinghe_and_duanyaojiu() {
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &zimo()),
+            [[
+                (MenqianqingZimohe, Fan(1)),
+                (Pinghe, Fan(1)),
+                (Duanyaojiu, Fan(1))
+            ]]
+        );
+    }
+
+    #[test]
+    fn pinghe_allows_a_quetou_without_fan() {
+        let bei_quetou = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S6, S7, Z4, Z4];
+
+        assert_eq!(yaku(&bei_quetou, &[], S8, &rong()), [[(Pinghe, Fan(1))]]);
+    }
+
+    #[test]
+    fn pinghe_needs_a_liangmian_wait() {
+        let qianzhang = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S6, S8, Z4, Z4];
+
+        assert_eq!(
+            evaluate_with(&YakuPolicy::mahjong_soul(), &qianzhang, &[], S7, &rong())
+                .iter()
+                .map(YakuEvaluation::has_yaku)
+                .collect::<Vec<_>>(),
+            [false]
+        );
+    }
+
+    #[test]
+    fn pinghe_needs_a_quetou_without_fan() {
+        let bai_quetou = [M2, M3, M4, P3, P4, P5, S4, S5, S6, S6, S7, Z5, Z5];
+
+        assert_eq!(yaku(&bai_quetou, &[], S8, &rong()), [[]]);
+    }
+
+    fn kuitan_fulu() -> [Fulu<FourPlayer>; 1] {
+        [Fulu::chi([M3, M4], M2, seat(3))]
+    }
+
+    const KUITAN_BINGPAI: [TileKind; 10] = [P3, P4, P5, S4, S5, S6, S6, S7, P8, P8];
+
+    #[test]
+    fn open_duanyaojiu_scores_under_kuitan() {
+        assert_eq!(
+            yaku(&KUITAN_BINGPAI, &kuitan_fulu(), S8, &rong()),
+            [[(Duanyaojiu, Fan(1))]]
+        );
+    }
+
+    #[test]
+    fn open_duanyaojiu_scores_nothing_without_kuitan() {
+        let without_kuitan =
+            YakuPolicy::mahjong_soul().with_rule(Duanyaojiu, YakuRule::menqian_only(Fan(1)));
+
+        assert_eq!(
+            yaku_with(
+                &without_kuitan,
+                &KUITAN_BINGPAI,
+                &kuitan_fulu(),
+                S8,
+                &rong()
+            ),
+            [[]]
+        );
+    }
+
+    #[test]
+    fn closed_hand_scores_the_full_value() {
+        let closed = [M1, M2, M3, M6, M7, M8, M9, M9, M9, Z5, Z5, Z1, Z1];
+
+        assert_eq!(
+            evaluate_with(&YakuPolicy::mahjong_soul(), &closed, &[], Z5, &rong())[0].fan(),
+            4
+        );
+    }
+
+    const OPEN_HUNYISE: [TileKind; 10] = [M6, M7, M8, M9, M9, M9, Z5, Z5, Z1, Z1];
+
+    fn open_hunyise_fulu() -> [Fulu<FourPlayer>; 1] {
+        [Fulu::chi([M2, M3], M1, seat(3))]
+    }
+
+    #[test]
+    fn open_hand_scores_the_reduced_value() {
+        assert_eq!(
+            evaluate_with(
+                &YakuPolicy::mahjong_soul(),
+                &OPEN_HUNYISE,
+                &open_hunyise_fulu(),
+                Z5,
+                &rong()
+            )[0]
+            .fan(),
+            3
+        );
+    }
+
+    #[test]
+    fn open_hand_lists_the_reduced_yaku_value() {
+        assert_eq!(
+            yaku(&OPEN_HUNYISE, &open_hunyise_fulu(), Z5, &rong()),
+            [[(YipaiBai, Fan(1)), (Hunyise, Fan(2))]]
+        );
+    }
+
+    const ERBEIKOU_QIDUIZI: [TileKind; 13] = [M1, M1, M2, M2, M3, M3, P4, P4, P5, P5, P6, P6, Z1];
+
+    #[test]
+    fn erbeikou_and_qiduizi_readings_are_scored_separately() {
+        assert_eq!(
+            yaku(&ERBEIKOU_QIDUIZI, &[], Z1, &rong()),
+            [[(Erbeikou, Fan(3))], [(Qiduizi, Fan(2))]]
+        );
+    }
+
+    #[test]
+    fn disabled_erbeikou_falls_back_to_yibeikou() {
+        let without_erbeikou = YakuPolicy::mahjong_soul().with_rule(Erbeikou, YakuRule::DISABLED);
+
+        assert_eq!(
+            yaku_with(&without_erbeikou, &ERBEIKOU_QIDUIZI, &[], Z1, &rong()),
+            [[(Yibeikou, Fan(1))], [(Qiduizi, Fan(2))]]
+        );
+    }
+
+    fn liang_lizhi_yifa() -> HuleSituation {
+        HuleSituation {
+            lizhi: established_lizhi(true, true),
+            ..rong()
+        }
+    }
+
+    #[test]
+    fn established_lizhi_scores_liang_lizhi_and_yifa() {
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &liang_lizhi_yifa()),
+            [[
+                (LiangLizhi, Fan(2)),
+                (Yifa, Fan(1)),
+                (Pinghe, Fan(1)),
+                (Duanyaojiu, Fan(1))
+            ]]
+        );
+    }
+
+    #[test]
+    fn disabled_liang_lizhi_falls_back_to_lizhi() {
+        let without_liang_lizhi =
+            YakuPolicy::mahjong_soul().with_rule(LiangLizhi, YakuRule::DISABLED);
+
+        assert_eq!(
+            yaku_with(
+                &without_liang_lizhi,
+                &PINGHE_TANYAO,
+                &[],
+                S8,
+                &liang_lizhi_yifa()
+            )[0][0],
+            (Lizhi, Fan(1))
+        );
+    }
+
+    #[test]
+    fn double_fengpai_scores_menfeng_and_quanfeng() {
+        let situation = HuleSituation {
+            zhuangjia: true,
+            menfengpai: Z1,
+            ..rong()
+        };
+
+        assert_eq!(
+            yaku(
+                &[Z1, Z1, Z1, M2, M3, M4, P3, P4, P5, S4, S5, S6, S9],
+                &[],
+                S9,
+                &situation
+            ),
+            [[(YipaiMenfeng, Fan(1)), (YipaiQuanfeng, Fan(1))]]
+        );
+    }
+
+    const BEI_KEZI: [TileKind; 13] = [Z4, Z4, Z4, M2, M3, M4, P3, P4, P5, S4, S5, S6, S9];
+
+    #[test]
+    fn bei_scores_nothing_unless_the_preset_makes_it_yipai() {
+        assert_eq!(yaku(&BEI_KEZI, &[], S9, &rong()), [[]]);
+    }
+
+    #[test]
+    fn bei_scores_yipai_when_the_preset_makes_it_yipai() {
+        let situation = HuleSituation {
+            bei_yipai: true,
+            ..rong()
+        };
+
+        assert_eq!(yaku(&BEI_KEZI, &[], S9, &situation), [[(YipaiBei, Fan(1))]]);
+    }
+
+    const SANANKE_SHUANGPENG: [TileKind; 13] = [M1, M1, M1, P9, P9, P9, M4, M5, M6, S2, S2, S7, S7];
+
+    #[test]
+    fn kezi_completed_by_rong_is_not_an_anke() {
+        assert_eq!(yaku(&SANANKE_SHUANGPENG, &[], S7, &rong()), [[]]);
+    }
+
+    #[test]
+    fn kezi_completed_by_zimo_is_an_anke() {
+        assert_eq!(
+            yaku(&SANANKE_SHUANGPENG, &[], S7, &zimo()),
+            [[(MenqianqingZimohe, Fan(1)), (Sananke, Fan(2))]]
+        );
+    }
+
+    const SIANKE_DANQI: [TileKind; 13] = [M1, M1, M1, P9, P9, P9, S2, S2, S2, Z6, Z6, Z6, Z7];
+
+    #[test]
+    fn sianke_on_a_danqi_wait_scores_sianke_danqi() {
+        assert_eq!(
+            yaku(&SIANKE_DANQI, &[], Z7, &rong()),
+            [[(SiankeDanqi, Yiman(2))]]
+        );
+    }
+
+    #[test]
+    fn sianke_danqi_falls_back_to_sianke_when_disabled() {
+        let without_danqi = YakuPolicy::mahjong_soul().with_rule(SiankeDanqi, YakuRule::DISABLED);
+
+        assert_eq!(
+            yaku_with(&without_danqi, &SIANKE_DANQI, &[], Z7, &rong()),
+            [[(Sianke, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn sianke_on_a_shuangpeng_zimo_scores_sianke() {
+        let shuangpeng = [M1, M1, M1, P9, P9, P9, S2, S2, S2, Z6, Z6, Z7, Z7];
+
+        assert_eq!(yaku(&shuangpeng, &[], Z7, &zimo()), [[(Sianke, Yiman(1))]]);
+    }
+
+    #[test]
+    fn guoshi_on_the_thirteen_sided_wait_scores_double() {
+        assert_eq!(
+            yaku(
+                &[M1, M9, P1, P9, S1, S9, Z1, Z2, Z3, Z4, Z5, Z6, Z7],
+                &[],
+                M1,
+                &rong()
+            ),
+            [[(GuoshiShisanmian, Yiman(2))]]
+        );
+    }
+
+    #[test]
+    fn guoshi_on_a_single_wait_scores_single() {
+        assert_eq!(
+            yaku(
+                &[M1, M1, M9, P1, P9, S1, S9, Z1, Z2, Z3, Z4, Z5, Z6],
+                &[],
+                Z7,
+                &rong()
+            ),
+            [[(Guoshi, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn jiulianbaodeng_on_the_nine_sided_wait_scores_double() {
+        assert_eq!(
+            yaku(
+                &[M1, M1, M1, M2, M3, M4, M5, M6, M7, M8, M9, M9, M9],
+                &[],
+                M5,
+                &rong()
+            ),
+            [[(ChunzhengJiulianbaodeng, Yiman(2))]]
+        );
+    }
+
+    #[test]
+    fn jiulianbaodeng_on_another_wait_scores_single() {
+        assert_eq!(
+            yaku(
+                &[M1, M1, M1, M2, M3, M4, M5, M5, M6, M7, M8, M9, M9],
+                &[],
+                M9,
+                &rong()
+            ),
+            [[(Jiulianbaodeng, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn yaojiu_and_zipai_in_every_block_score_hunquandaiyaojiu() {
+        assert_eq!(
+            yaku(
+                &[M1, M2, M3, P7, P8, P9, S9, S9, S9, Z7, Z7, Z7, Z1],
+                &[],
+                Z1,
+                &rong()
+            ),
+            [[(YipaiZhong, Fan(1)), (Hunquandaiyaojiu, Fan(2))]]
+        );
+    }
+
+    #[test]
+    fn laotoupai_in_every_block_score_chunquandaiyaojiu() {
+        assert_eq!(
+            yaku(
+                &[M1, M2, M3, P7, P8, P9, S1, S2, S3, M9, M9, M9, S9],
+                &[],
+                S9,
+                &rong()
+            ),
+            [[(Chunquandaiyaojiu, Fan(3))]]
+        );
+    }
+
+    #[test]
+    fn laotoupai_and_zipai_kezi_score_hunlaotou() {
+        assert_eq!(
+            yaku(
+                &[M1, M1, M1, P9, P9, P9, Z7, Z7, Z7, S1, S1, Z2, Z2],
+                &[],
+                Z2,
+                &rong()
+            ),
+            [[
+                (YipaiMenfeng, Fan(1)),
+                (YipaiZhong, Fan(1)),
+                (Duiduihe, Fan(2)),
+                (Sananke, Fan(2)),
+                (Hunlaotou, Fan(2))
+            ]]
+        );
+    }
+
+    fn qinglaotou_sianke() -> YakuEvaluations {
+        evaluate_with(
+            &YakuPolicy::mahjong_soul(),
+            &[M1, M1, M1, M9, M9, M9, P1, P1, P1, S9, S9, P9, P9],
+            &[],
+            P9,
+            &zimo(),
+        )
+    }
+
+    #[test]
+    fn laotoupai_kezi_score_qinglaotou() {
+        assert_eq!(
+            qinglaotou_sianke()[0].yaku(),
+            [
+                Yaku {
+                    id: Sianke,
+                    value: Yiman(1)
+                },
+                Yaku {
+                    id: Qinglaotou,
+                    value: Yiman(1)
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn stacked_yiman_add_up() {
+        assert_eq!(qinglaotou_sianke()[0].yiman(), 2);
+    }
+
+    #[test]
+    fn yiman_drops_the_fan_yaku() {
+        assert_eq!(qinglaotou_sianke()[0].fan(), 0);
+    }
+
+    #[test]
+    fn same_shunzi_in_three_suits_scores_sansetongshun() {
+        assert_eq!(
+            yaku(
+                &[M2, M3, M4, P2, P3, P4, S2, S3, S4, S7, S8, S9, Z3],
+                &[],
+                Z3,
+                &rong()
+            ),
+            [[(Sansetongshun, Fan(2))]]
+        );
+    }
+
+    #[test]
+    fn one_to_nine_in_one_suit_scores_yiqitongguan() {
+        assert_eq!(
+            yaku(
+                &[M1, M2, M3, M4, M5, M6, M7, M8, M9, P2, P3, P4, Z3],
+                &[],
+                Z3,
+                &rong()
+            ),
+            [[(Yiqitongguan, Fan(2))]]
+        );
+    }
+
+    #[test]
+    fn gangzi_count_toward_sansetongke_sangangzi_and_sananke() {
+        let fulu = [
+            Fulu::daminggang([M2, M2, M2], M2, seat(1)),
+            Fulu::angang([P2, P2, P2, P2]),
+            Fulu::angang([S2, S2, S2, S2]),
+        ];
+
+        assert_eq!(
+            yaku(&[Z3, Z3, Z3, Z4], &fulu, Z4, &rong()),
+            [[
+                (Duiduihe, Fan(2)),
+                (Sananke, Fan(2)),
+                (Sangangzi, Fan(2)),
+                (Sansetongke, Fan(2))
+            ]]
+        );
+    }
+
+    #[test]
+    fn two_sanyuanpai_kezi_and_a_quetou_score_xiaosanyuan() {
+        assert_eq!(
+            yaku(
+                &[Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, M1, M2, M3, M5, M6],
+                &[],
+                M7,
+                &rong()
+            ),
+            [[
+                (YipaiBai, Fan(1)),
+                (YipaiFa, Fan(1)),
+                (Xiaosanyuan, Fan(2)),
+                (Hunyise, Fan(3))
+            ]]
+        );
+    }
+
+    #[test]
+    fn three_sanyuanpai_kezi_score_dasanyuan() {
+        assert_eq!(
+            yaku(
+                &[Z5, Z5, Z5, Z6, Z6, Z6, Z7, Z7, M1, M2, M3, M9, M9],
+                &[],
+                Z7,
+                &rong()
+            ),
+            [[(Dasanyuan, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn three_fengpai_kezi_and_a_quetou_score_xiaosixi() {
+        assert_eq!(
+            yaku(
+                &[Z1, Z1, Z1, Z2, Z2, Z2, Z3, Z3, Z3, Z4, Z4, M1, M2],
+                &[],
+                M3,
+                &rong()
+            ),
+            [[(Xiaosixi, Yiman(1))]]
+        );
+    }
+
+    fn dasixi_danqi() -> YakuEvaluations {
+        evaluate_with(
+            &YakuPolicy::mahjong_soul(),
+            &[Z1, Z1, Z1, Z2, Z2, Z2, Z3, Z3, Z3, Z4, Z4, Z4, M1],
+            &[],
+            M1,
+            &rong(),
+        )
+    }
+
+    #[test]
+    fn four_fengpai_kezi_score_dasixi_stacked_with_other_yiman() {
+        assert_eq!(
+            dasixi_danqi()
+                .iter()
+                .map(|evaluation| evaluation.yaku().iter().map(Yaku::id).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            [[SiankeDanqi, Dasixi]]
+        );
+    }
+
+    #[test]
+    fn dasixi_counts_double_yiman() {
+        assert_eq!(dasixi_danqi()[0].yiman(), 4);
+    }
+
+    #[test]
+    fn zipai_only_scores_ziyise_without_the_fan_yaku() {
+        assert_eq!(
+            yaku(
+                &[Z1, Z1, Z2, Z2, Z3, Z3, Z4, Z4, Z5, Z5, Z6, Z6, Z7],
+                &[],
+                Z7,
+                &rong()
+            ),
+            [[(Ziyise, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn green_tiles_only_score_lvyise_without_the_fan_yaku() {
+        assert_eq!(
+            yaku(
+                &[S2, S3, S4, S2, S3, S4, S6, S6, S6, Z6, Z6, S8, S8],
+                &[],
+                S8,
+                &rong()
+            ),
+            [[(Lvyise, Yiman(1))]]
+        );
+    }
+
+    fn dachelun_qiduizi_reading(policy: &YakuPolicy) -> YakuEvaluation {
+        evaluate_with(
+            policy,
+            &[P2, P2, P3, P3, P4, P4, P5, P5, P6, P6, P7, P7, P8],
+            &[],
+            P8,
+            &rong(),
+        )
+        .into_iter()
+        .find(|evaluation| matches!(evaluation.shape(), HuleShape::Qiduizi { .. }))
+        .unwrap()
+    }
+
+    #[test]
+    fn dachelun_is_not_scored_by_default() {
+        assert_eq!(
+            dachelun_qiduizi_reading(&YakuPolicy::mahjong_soul())
+                .yaku()
+                .iter()
+                .map(Yaku::id)
+                .collect::<Vec<_>>(),
+            [Duanyaojiu, Qiduizi, Qingyise]
+        );
+    }
+
+    #[test]
+    fn dachelun_is_scored_only_when_enabled() {
+        let with_dachelun =
+            YakuPolicy::mahjong_soul().with_rule(Dachelun, YakuRule::menqian_only(Yiman(1)));
+
+        assert_eq!(
+            dachelun_qiduizi_reading(&with_dachelun).yaku(),
+            [Yaku {
+                id: Dachelun,
+                value: Yiman(1)
+            }]
+        );
+    }
+
+    fn first_turn() -> HuleSituation {
+        HuleSituation {
+            first_turn: true,
+            ..rong()
+        }
+    }
+
+    #[test]
+    fn zhuangjia_first_turn_zimo_scores_tianhu() {
+        let situation = HuleSituation {
+            zimo: true,
+            zhuangjia: true,
+            ..first_turn()
+        };
+
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &situation),
+            [[(Tianhu, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn sanjia_first_turn_zimo_scores_dihu() {
+        let situation = HuleSituation {
+            zimo: true,
+            ..first_turn()
+        };
+
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &situation),
+            [[(Dihu, Yiman(1))]]
+        );
+    }
+
+    #[test]
+    fn first_turn_rong_scores_no_renhu_by_default() {
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &first_turn()),
+            [[(Pinghe, Fan(1)), (Duanyaojiu, Fan(1))]]
+        );
+    }
+
+    #[test]
+    fn first_turn_rong_scores_renhu_when_enabled() {
+        let with_renhu =
+            YakuPolicy::mahjong_soul().with_rule(Renhu, YakuRule::menqian_only(Yiman(1)));
+
+        assert_eq!(
+            yaku_with(&with_renhu, &PINGHE_TANYAO, &[], S8, &first_turn()),
+            [[(Renhu, Yiman(1))]]
+        );
+    }
+
+    fn haidi() -> HuleSituation {
+        HuleSituation {
+            haidi: true,
+            ..rong()
+        }
+    }
+
+    #[test]
+    fn rong_on_the_last_tile_scores_hedi_laoyu() {
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &haidi()),
+            [[(Pinghe, Fan(1)), (Duanyaojiu, Fan(1)), (HediLaoyu, Fan(1))]]
+        );
+    }
+
+    #[test]
+    fn zimo_on_the_last_tile_scores_haidi_laoyue() {
+        let situation = HuleSituation {
+            zimo: true,
+            ..haidi()
+        };
+
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &situation),
+            [[
+                (MenqianqingZimohe, Fan(1)),
+                (Pinghe, Fan(1)),
+                (Duanyaojiu, Fan(1)),
+                (HaidiLaoyue, Fan(1))
+            ]]
+        );
+    }
+
+    #[test]
+    fn rong_on_a_gang_tile_scores_qianggang() {
+        let situation = HuleSituation {
+            qianggang: true,
+            ..rong()
+        };
+
+        assert_eq!(
+            yaku(&PINGHE_TANYAO, &[], S8, &situation),
+            [[(Pinghe, Fan(1)), (Duanyaojiu, Fan(1)), (Qianggang, Fan(1))]]
+        );
+    }
+
+    #[test]
+    fn zimo_on_a_lingshang_tile_scores_lingshang_kaihua() {
+        let situation = HuleSituation {
+            zimo: true,
+            lingshang: true,
+            ..rong()
+        };
+
+        assert_eq!(
+            yaku(
+                &[M2, M3, M4, P3, P4, P5, S6, S7, P6, P6],
+                &[Fulu::angang([M8, M8, M8, M8])],
+                S8,
+                &situation
+            ),
+            [[
+                (MenqianqingZimohe, Fan(1)),
+                (Duanyaojiu, Fan(1)),
+                (LingshangKaihua, Fan(1))
+            ]]
+        );
+    }
+}
@@ -4,7 +4,13 @@
 
 use thiserror::Error;
 
-use lizhisim_core::{TileKind, TileSet, TileSetError};
+use core::marker::PhantomData;
+
+use lizhisim_core::{
+    Babei, BaoPolicy, Chang, GangBaopaiTiming, LiujuManguan, MatchRules, RongResolution,
+    RoundPolicy, Score, ScorePolicy, Shiti, SigangSanle, TileKind, TileSet, TileSetError,
+    YakuPolicy, YakuRule, YakuRuleId,
+};
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct HongBaopaiConfig {
@@ -13,14 +19,56 @@ pub struct HongBaopaiConfig {
     pub s0_count: u8,
 }
 
+/// The table rules a `Round` consults, apart from the yaku, score and bao policies.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableRulesConfig {
+    /// Whether the initial baopai indicator is revealed after qipai.
+    pub baopai: bool,
+    pub angang_baopai_timing: GangBaopaiTiming,
+    /// Timing shared by daminggang and jiagang.
+    pub minggang_baopai_timing: GangBaopaiTiming,
+    pub guoshi_angang_qianggang: bool,
+    pub lizhi_required_score: Score,
+    pub lizhi_required_remaining_count: usize,
+    pub lizhi_deposit: Score,
+    pub jiuzhong_jiupai: bool,
+    pub sifeng_lianda: bool,
+    pub sijia_lizhi: bool,
+    pub sigang_sanle: SigangSanle,
+    pub rong_resolution: RongResolution,
+    pub sanjia_hele: bool,
+    pub xingshi_tingpai: bool,
+    pub shouzhong_kongting: bool,
+    pub buting_fafu: Score,
+    pub liuju_manguan: LiujuManguan,
+    pub shiti: Shiti,
+    pub babei: Babei,
+    pub babei_rong: bool,
+    pub bei_yipai: bool,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RawRuleSpec {
     pub hong_baopai: HongBaopaiConfig,
+    /// Base tile kinds left out of the tile set entirely, such as `M2` to `M8` in sanma.
+    pub excluded_tile_kinds: Vec<TileKind>,
+    pub table: TableRulesConfig,
+    /// The adopted yaku with their values; a yaku left out is not adopted.
+    pub yaku: Vec<(YakuRuleId, YakuRule)>,
+    pub score: ScorePolicy,
+    pub bao: BaoPolicy,
+    pub match_rules: MatchRules,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RuleSpec {
     hong_baopai: HongBaopaiConfig,
+    excluded_tile_kinds: Vec<TileKind>,
+    table: TableRulesConfig,
+    yaku: Vec<(YakuRuleId, YakuRule)>,
+    score: ScorePolicy,
+    bao: BaoPolicy,
+    match_rules: MatchRules,
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -31,6 +79,29 @@ pub enum RuleSpecError {
         actual_count: u8,
         max_count: u8,
     },
+    #[error("excluded tile kind {tile_kind:?} is not a base tile")]
+    ExcludedTileKindNotBase { tile_kind: TileKind },
+    #[error("tile kind {tile_kind:?} is excluded more than once")]
+    DuplicateExcludedTileKind { tile_kind: TileKind },
+    #[error("{hong_baopai:?} count {actual_count} requires {base_tile:?}, which is excluded")]
+    HongBaopaiOfExcludedFive {
+        hong_baopai: TileKind,
+        base_tile: TileKind,
+        actual_count: u8,
+    },
+    #[error("lizhi deposit {deposit} is not positive")]
+    LizhiDepositNotPositive { deposit: i32 },
+    #[error("yaku {id:?} is listed more than once")]
+    DuplicateYakuRule { id: YakuRuleId },
+    #[error("max yiman 0 would leave every yiman unpaid")]
+    MaxYimanZero,
+    #[error(
+        "extension ends with {extension_last_chang:?}, not past regulation ending with {last_chang:?}"
+    )]
+    ExtensionNotPastRegulation {
+        last_chang: Chang,
+        extension_last_chang: Chang,
+    },
     #[error("failed to resolve tile set: {0}")]
     TileSet(#[from] TileSetError),
 }
@@ -53,6 +124,64 @@ impl HongBaopaiConfig {
     }
 }
 
+fn validate_excluded_tile_kinds(
+    excluded_tile_kinds: &[TileKind],
+    hong_baopai: &HongBaopaiConfig,
+) -> Result<(), RuleSpecError> {
+    for (index, &tile_kind) in excluded_tile_kinds.iter().enumerate() {
+        if tile_kind.is_hong_baopai() {
+            return Err(RuleSpecError::ExcludedTileKindNotBase { tile_kind });
+        }
+        if excluded_tile_kinds[..index].contains(&tile_kind) {
+            return Err(RuleSpecError::DuplicateExcludedTileKind { tile_kind });
+        }
+    }
+    for (hong_baopai, actual_count) in [
+        (TileKind::M0, hong_baopai.m0_count),
+        (TileKind::P0, hong_baopai.p0_count),
+        (TileKind::S0, hong_baopai.s0_count),
+    ] {
+        let base_tile = hong_baopai.base_tile();
+        if actual_count > 0 && excluded_tile_kinds.contains(&base_tile) {
+            return Err(RuleSpecError::HongBaopaiOfExcludedFive {
+                hong_baopai,
+                base_tile,
+                actual_count,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn validate_yaku(yaku: &[(YakuRuleId, YakuRule)]) -> Result<(), RuleSpecError> {
+    for (index, &(id, _)) in yaku.iter().enumerate() {
+        if yaku[..index].iter().any(|&(listed, _)| listed == id) {
+            return Err(RuleSpecError::DuplicateYakuRule { id });
+        }
+    }
+    Ok(())
+}
+
+fn validate_policies(raw: &RawRuleSpec) -> Result<(), RuleSpecError> {
+    let deposit = raw.table.lizhi_deposit.value();
+    if deposit <= 0 {
+        return Err(RuleSpecError::LizhiDepositNotPositive { deposit });
+    }
+    if raw.score.max_yiman == 0 {
+        return Err(RuleSpecError::MaxYimanZero);
+    }
+    let termination = &raw.match_rules.termination;
+    if let Some(extension) = termination.extension
+        && extension.last_chang <= termination.last_chang
+    {
+        return Err(RuleSpecError::ExtensionNotPastRegulation {
+            last_chang: termination.last_chang,
+            extension_last_chang: extension.last_chang,
+        });
+    }
+    validate_yaku(&raw.yaku)
+}
+
 const fn validate_hong_baopai_count(
     hong_baopai: TileKind,
     actual_count: u8,
@@ -72,9 +201,17 @@ impl TryFrom<RawRuleSpec> for RuleSpec {
 
     fn try_from(raw: RawRuleSpec) -> Result<Self, Self::Error> {
         raw.hong_baopai.validate()?;
+        validate_excluded_tile_kinds(&raw.excluded_tile_kinds, &raw.hong_baopai)?;
+        validate_policies(&raw)?;
 
         Ok(Self {
             hong_baopai: raw.hong_baopai,
+            excluded_tile_kinds: raw.excluded_tile_kinds,
+            table: raw.table,
+            yaku: raw.yaku,
+            score: raw.score,
+            bao: raw.bao,
+            match_rules: raw.match_rules,
         })
     }
 }
@@ -89,15 +226,185 @@ impl RuleSpec {
         counts[TileKind::P5.index()] = 4 - self.hong_baopai.p0_count;
         counts[TileKind::S0.index()] = self.hong_baopai.s0_count;
         counts[TileKind::S5.index()] = 4 - self.hong_baopai.s0_count;
+        // Validation keeps the red five of an excluded base five at zero.
+        for tile_kind in &self.excluded_tile_kinds {
+            counts[tile_kind.index()] = 0;
+        }
 
         TileSet::try_from_counts(counts).map_err(RuleSpecError::TileSet)
     }
+
+    /// The policy a `Round` of `P` runs under.
+    pub fn round_policy<P>(&self) -> RoundPolicy<P> {
+        let table = &self.table;
+        RoundPolicy {
+            baopai: table.baopai,
+            angang_baopai_timing: table.angang_baopai_timing,
+            minggang_baopai_timing: table.minggang_baopai_timing,
+            guoshi_angang_qianggang: table.guoshi_angang_qianggang,
+            lizhi_required_score: table.lizhi_required_score,
+            lizhi_required_remaining_count: table.lizhi_required_remaining_count,
+            lizhi_deposit: table.lizhi_deposit,
+            jiuzhong_jiupai: table.jiuzhong_jiupai,
+            sifeng_lianda: table.sifeng_lianda,
+            sijia_lizhi: table.sijia_lizhi,
+            sigang_sanle: table.sigang_sanle,
+            rong_resolution: table.rong_resolution,
+            sanjia_hele: table.sanjia_hele,
+            xingshi_tingpai: table.xingshi_tingpai,
+            shouzhong_kongting: table.shouzhong_kongting,
+            buting_fafu: table.buting_fafu,
+            liuju_manguan: table.liuju_manguan,
+            shiti: table.shiti,
+            babei: table.babei,
+            babei_rong: table.babei_rong,
+            bei_yipai: table.bei_yipai,
+            bao: self.bao_policy(),
+            yaku: self.yaku_policy(),
+            score: self.score_policy(),
+            player_set: PhantomData,
+        }
+    }
+
+    /// The yaku policy, with every yaku left out of the spec not adopted.
+    pub fn yaku_policy(&self) -> YakuPolicy {
+        YakuPolicy::from_fn(|id| {
+            self.yaku
+                .iter()
+                .find(|&&(listed, _)| listed == id)
+                .map_or(YakuRule::DISABLED, |&(_, rule)| rule)
+        })
+    }
+
+    pub fn score_policy(&self) -> ScorePolicy {
+        self.score
+    }
+
+    pub fn bao_policy(&self) -> BaoPolicy {
+        self.bao
+    }
+
+    pub fn match_rules(&self) -> MatchRules {
+        self.match_rules
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use lizhisim_core::{
+        Bipai, Bust, Extension, FourPlayer, LeijiYiman, MatchTerminationPolicy, MissingSeatShare,
+        PingjuLianzhuang, ThreePlayer, YakuValue,
+    };
+
     use super::*;
 
+    /// Mahjong Soul ranked four-player rules with one red five in each suit.
+    fn mahjong_soul_raw() -> RawRuleSpec {
+        use YakuRuleId::*;
+        use YakuValue::{Fan, Yiman};
+
+        let yaku = YakuRuleId::ALL
+            .into_iter()
+            .filter_map(|id| {
+                let rule = match id {
+                    Lizhi | Yifa | MenqianqingZimohe | Pinghe | Yibeikou => {
+                        YakuRule::menqian_only(Fan(1))
+                    }
+                    LiangLizhi | Qiduizi => YakuRule::menqian_only(Fan(2)),
+                    Erbeikou => YakuRule::menqian_only(Fan(3)),
+                    Duanyaojiu | YipaiMenfeng | YipaiQuanfeng | YipaiBai | YipaiFa | YipaiZhong
+                    | YipaiBei | LingshangKaihua | Qianggang | HaidiLaoyue | HediLaoyu => {
+                        YakuRule::always(Fan(1))
+                    }
+                    Sansetongshun | Yiqitongguan | Hunquandaiyaojiu => {
+                        YakuRule::reduced_when_open(2)
+                    }
+                    Duiduihe | Sananke | Sangangzi | Sansetongke | Hunlaotou | Xiaosanyuan => {
+                        YakuRule::always(Fan(2))
+                    }
+                    Hunyise | Chunquandaiyaojiu => YakuRule::reduced_when_open(3),
+                    Qingyise => YakuRule::reduced_when_open(6),
+                    Tianhu | Dihu | Guoshi | Sianke | Jiulianbaodeng => {
+                        YakuRule::menqian_only(Yiman(1))
+                    }
+                    GuoshiShisanmian | SiankeDanqi | ChunzhengJiulianbaodeng => {
+                        YakuRule::menqian_only(Yiman(2))
+                    }
+                    Dasanyuan | Xiaosixi | Ziyise | Lvyise | Qinglaotou | Sigangzi => {
+                        YakuRule::always(Yiman(1))
+                    }
+                    Dasixi => YakuRule::always(Yiman(2)),
+                    Renhu | Dachelun => return None,
+                };
+                Some((id, rule))
+            })
+            .collect();
+
+        RawRuleSpec {
+            hong_baopai: HongBaopaiConfig {
+                m0_count: 1,
+                p0_count: 1,
+                s0_count: 1,
+            },
+            excluded_tile_kinds: Vec::new(),
+            table: TableRulesConfig {
+                baopai: true,
+                angang_baopai_timing: GangBaopaiTiming::Immediate,
+                minggang_baopai_timing: GangBaopaiTiming::AfterDapai,
+                guoshi_angang_qianggang: true,
+                lizhi_required_score: Score::new(1_000),
+                lizhi_required_remaining_count: 4,
+                lizhi_deposit: Score::new(1_000),
+                jiuzhong_jiupai: true,
+                sifeng_lianda: true,
+                sijia_lizhi: true,
+                sigang_sanle: SigangSanle::ExceptSinglePlayer,
+                rong_resolution: RongResolution::Multiple,
+                sanjia_hele: true,
+                xingshi_tingpai: true,
+                shouzhong_kongting: false,
+                buting_fafu: Score::new(3_000),
+                liuju_manguan: LiujuManguan::Hule,
+                shiti: Shiti::ForbidSameKindAndSuji,
+                babei: Babei::Disabled,
+                babei_rong: false,
+                bei_yipai: false,
+            },
+            yaku,
+            score: ScorePolicy {
+                pinghe_zimo_fu: false,
+                lingshang_zimo_fu: true,
+                qiduizi_fu: 25,
+                lianfengpai_quetou_fu: 4,
+                qieshang_manguan: false,
+                leiji_yiman: LeijiYiman::Yiman,
+                max_yiman: 6,
+                missing_seat_share: MissingSeatShare::Lost,
+            },
+            bao: BaoPolicy {
+                dasanyuan: true,
+                dasixi: true,
+                daminggang_lingshang: false,
+            },
+            match_rules: MatchRules {
+                hule_lianzhuang: true,
+                pingju_lianzhuang: PingjuLianzhuang::Tingpai,
+                liuju_lianzhuang: true,
+                termination: MatchTerminationPolicy {
+                    last_chang: Chang::Nan,
+                    extension: Some(Extension {
+                        target_score: Score::new(30_000),
+                        last_chang: Chang::Xi,
+                        sudden_death: true,
+                    }),
+                    bust: Bust::BelowZero,
+                    final_hule_stop: true,
+                    final_tingpai_stop: true,
+                },
+            },
+        }
+    }
+
     fn raw(m0_count: u8, p0_count: u8, s0_count: u8) -> RawRuleSpec {
         RawRuleSpec {
             hong_baopai: HongBaopaiConfig {
@@ -105,6 +412,23 @@ mod tests {
                 p0_count,
                 s0_count,
             },
+            ..mahjong_soul_raw()
+        }
+    }
+
+    /// Standard sanma: `M2` to `M8` excluded, with one red five in tongzi and suozi.
+    fn sanma_raw() -> RawRuleSpec {
+        RawRuleSpec {
+            excluded_tile_kinds: vec![
+                TileKind::M2,
+                TileKind::M3,
+                TileKind::M4,
+                TileKind::M5,
+                TileKind::M6,
+                TileKind::M7,
+                TileKind::M8,
+            ],
+            ..raw(0, 1, 1)
         }
     }
 
@@ -257,4 +581,242 @@ mod tests {
             [4, 0, 4, 0, 4, 0],
         );
     }
+
+    fn sanma_tile_set() -> TileSet {
+        RuleSpec::try_from(sanma_raw())
+            .unwrap()
+            .resolve_tile_set()
+            .unwrap()
+    }
+
+    #[test]
+    fn rule_spec_resolves_sanma_excluded_manzu_to_zero_copies() {
+        let tile_set = sanma_tile_set();
+
+        assert_eq!(
+            TileKind::ALL[..9]
+                .iter()
+                .map(|&tile_kind| tile_set.max_count(tile_kind))
+                .collect::<Vec<_>>(),
+            vec![4, 0, 0, 0, 0, 0, 0, 0, 4],
+        );
+    }
+
+    #[test]
+    fn rule_spec_resolves_sanma_without_red_manzu() {
+        assert_eq!(sanma_tile_set().max_count(TileKind::M0), 0);
+    }
+
+    #[test]
+    fn rule_spec_resolves_sanma_to_108_tiles() {
+        assert_eq!(sanma_tile_set().total_count(), 108);
+    }
+
+    #[test]
+    fn rule_spec_resolves_sanma_to_tiles_accepted_by_three_player_bipai() {
+        let tile_set = sanma_tile_set();
+        let tiles = TileKind::ALL
+            .into_iter()
+            .flat_map(|tile_kind| (0..tile_set.max_count(tile_kind)).map(move |_| tile_kind))
+            .collect::<Vec<_>>();
+
+        assert!(Bipai::<ThreePlayer>::try_new(tiles.try_into().unwrap(), tile_set).is_ok());
+    }
+
+    #[test]
+    fn rule_spec_rejects_red_five_of_excluded_base_five() {
+        let raw = RawRuleSpec {
+            hong_baopai: HongBaopaiConfig {
+                m0_count: 1,
+                p0_count: 1,
+                s0_count: 1,
+            },
+            ..sanma_raw()
+        };
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::HongBaopaiOfExcludedFive {
+                hong_baopai: TileKind::M0,
+                base_tile: TileKind::M5,
+                actual_count: 1,
+            }),
+        );
+    }
+
+    #[test]
+    fn rule_spec_rejects_excluded_red_five() {
+        let raw = RawRuleSpec {
+            excluded_tile_kinds: vec![TileKind::P0],
+            ..raw(0, 0, 0)
+        };
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::ExcludedTileKindNotBase {
+                tile_kind: TileKind::P0,
+            }),
+        );
+    }
+
+    #[test]
+    fn rule_spec_rejects_duplicate_excluded_tile_kind() {
+        let raw = RawRuleSpec {
+            excluded_tile_kinds: vec![TileKind::M2, TileKind::M3, TileKind::M2],
+            ..raw(1, 1, 1)
+        };
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::DuplicateExcludedTileKind {
+                tile_kind: TileKind::M2,
+            }),
+        );
+    }
+
+    #[test]
+    fn round_policy_takes_the_table_rules() {
+        let mut raw = mahjong_soul_raw();
+        raw.table.sigang_sanle = SigangSanle::Always;
+        let rule_spec = RuleSpec::try_from(raw).unwrap();
+
+        assert_eq!(
+            rule_spec.round_policy::<FourPlayer>().sigang_sanle,
+            SigangSanle::Always
+        );
+    }
+
+    #[test]
+    fn round_policy_carries_the_score_policy() {
+        let mut raw = mahjong_soul_raw();
+        raw.score.missing_seat_share = MissingSeatShare::Split;
+        let rule_spec = RuleSpec::try_from(raw).unwrap();
+
+        assert_eq!(
+            rule_spec.round_policy::<ThreePlayer>().score,
+            rule_spec.score_policy()
+        );
+    }
+
+    #[test]
+    fn round_policy_carries_the_bao_policy() {
+        let mut raw = mahjong_soul_raw();
+        raw.bao.daminggang_lingshang = true;
+        let rule_spec = RuleSpec::try_from(raw).unwrap();
+
+        assert!(
+            rule_spec
+                .round_policy::<FourPlayer>()
+                .bao
+                .daminggang_lingshang
+        );
+    }
+
+    #[test]
+    fn round_policy_carries_the_yaku_policy() {
+        let rule_spec = RuleSpec::try_from(mahjong_soul_raw()).unwrap();
+
+        assert_eq!(
+            rule_spec.round_policy::<FourPlayer>().yaku,
+            rule_spec.yaku_policy()
+        );
+    }
+
+    #[test]
+    fn yaku_policy_takes_each_listed_rule() {
+        let rule_spec = RuleSpec::try_from(mahjong_soul_raw()).unwrap();
+
+        assert_eq!(
+            rule_spec.yaku_policy().rule(YakuRuleId::Qingyise),
+            YakuRule::reduced_when_open(6)
+        );
+    }
+
+    #[test]
+    fn yaku_policy_leaves_an_unlisted_yaku_unadopted() {
+        let rule_spec = RuleSpec::try_from(mahjong_soul_raw()).unwrap();
+
+        assert_eq!(
+            rule_spec.yaku_policy().rule(YakuRuleId::Renhu),
+            YakuRule::DISABLED
+        );
+    }
+
+    #[test]
+    fn match_rules_take_the_termination_policy() {
+        let mut raw = mahjong_soul_raw();
+        raw.match_rules.termination.extension = None;
+        let rule_spec = RuleSpec::try_from(raw).unwrap();
+
+        assert_eq!(rule_spec.match_rules().termination.extension, None);
+    }
+
+    #[test]
+    fn rule_spec_rejects_a_zero_lizhi_deposit() {
+        let mut raw = mahjong_soul_raw();
+        raw.table.lizhi_deposit = Score::new(0);
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::LizhiDepositNotPositive { deposit: 0 }),
+        );
+    }
+
+    #[test]
+    fn rule_spec_rejects_a_yaku_listed_twice() {
+        let mut raw = mahjong_soul_raw();
+        raw.yaku
+            .push((YakuRuleId::Pinghe, YakuRule::always(YakuValue::Fan(1))));
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::DuplicateYakuRule {
+                id: YakuRuleId::Pinghe,
+            }),
+        );
+    }
+
+    #[test]
+    fn rule_spec_rejects_a_zero_max_yiman() {
+        let mut raw = mahjong_soul_raw();
+        raw.score.max_yiman = 0;
+
+        assert_eq!(RuleSpec::try_from(raw), Err(RuleSpecError::MaxYimanZero));
+    }
+
+    #[test]
+    fn rule_spec_rejects_an_extension_that_ends_before_regulation() {
+        let mut raw = mahjong_soul_raw();
+        raw.match_rules.termination.extension = Some(Extension {
+            target_score: Score::new(30_000),
+            last_chang: Chang::Dong,
+            sudden_death: true,
+        });
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::ExtensionNotPastRegulation {
+                last_chang: Chang::Nan,
+                extension_last_chang: Chang::Dong,
+            }),
+        );
+    }
+
+    #[test]
+    fn rule_spec_rejects_an_extension_that_ends_with_regulation() {
+        let mut raw = mahjong_soul_raw();
+        raw.match_rules.termination.extension = Some(Extension {
+            target_score: Score::new(30_000),
+            last_chang: Chang::Nan,
+            sudden_death: true,
+        });
+
+        assert_eq!(
+            RuleSpec::try_from(raw),
+            Err(RuleSpecError::ExtensionNotPastRegulation {
+                last_chang: Chang::Nan,
+                extension_last_chang: Chang::Nan,
+            }),
+        );
+    }
 }